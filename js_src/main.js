@@ -42,6 +42,9 @@ global.help = function() {
   - hauler_rescan(): rescan the hauler network for each room
   - pause_exec(): pause execution of the bot
   - wipe_memory(): wipe all memory
+  - export_plan(room): the room's base plan, in building planner JSON
+  - import_plan(room, plan): build the room from a building planner plan instead of planning it
+  - clear_imported_plan(room): go back to planning the room ourselves
   `
 }
 
@@ -85,6 +88,32 @@ global.hauler_rescan = function() {
   }
 }
 
+global.export_plan = function(room_name) {
+  if (wasm_instance) {
+    return crustacean.export_plan(room_name)
+  } else {
+    return `[JS] Module not loaded.`
+  }
+}
+
+global.import_plan = function(room_name, plan) {
+  if (wasm_instance) {
+    const raw = typeof plan === "string" ? plan : JSON.stringify(plan);
+
+    return `[JS] ${crustacean.import_plan(room_name, raw)}`
+  } else {
+    return `[JS] Module not loaded.`
+  }
+}
+
+global.clear_imported_plan = function(room_name) {
+  if (wasm_instance) {
+    return `[JS] ${crustacean.clear_imported_plan(room_name)}`
+  } else {
+    return `[JS] Module not loaded.`
+  }
+}
+
 global.pause_exec = function() {
   pause_exec = !pause_exec;
   return `[JS] Setting execution pause to: ${pause_exec}`;
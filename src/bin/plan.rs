@@ -0,0 +1,65 @@
+// Plans bases from terrain dumps, no server needed.
+//
+// cargo run --bin plan --target <host triple> -- rooms.json [output dir]
+//
+// The target has to be given since .cargo/config.toml builds for wasm by default.
+// rooms.json is a list of rooms, see base_plan::PlanRoom for the fields. Each one gets a
+// <name>.json with the plan and a <name>.txt to eyeball it, in "plans" unless told otherwise.
+
+use std::{env, fs, path::PathBuf, process};
+
+use crustacean::base_plan::{self, PlanRoom};
+
+fn main() {
+    let args = env::args().collect::<Vec<_>>();
+
+    let Some(input) = args.get(1) else {
+        eprintln!("usage: plan <rooms.json> [output dir]");
+        process::exit(1);
+    };
+    let out_dir = PathBuf::from(args.get(2).map_or("plans", |dir| dir.as_str()));
+
+    let rooms: Vec<PlanRoom> = match fs::read_to_string(input).map(|raw| serde_json::from_str(&raw)) {
+        Ok(Ok(rooms)) => rooms,
+        Ok(Err(e)) => {
+            eprintln!("couldnt parse {}: {}", input, e);
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("couldnt read {}: {}", input, e);
+            process::exit(1);
+        }
+    };
+
+    if let Err(e) = fs::create_dir_all(&out_dir) {
+        eprintln!("couldnt create {}: {}", out_dir.display(), e);
+        process::exit(1);
+    }
+
+    for room in rooms {
+        let Some(plan) = base_plan::plan_base(&room) else {
+            println!("{}: no plan, bad terrain or nowhere for the core", room.name);
+            continue;
+        };
+
+        let core = base_plan::index_to_xy(plan.core);
+        let skipped = plan.stamp_centers.iter().filter(|center| **center < 0).count();
+        println!(
+            "{}: core at {}, {}, {} stamps placed, {} skipped, {} ramparts",
+            room.name,
+            core.x.u8(),
+            core.y.u8(),
+            plan.stamp_centers.len() - skipped,
+            skipped,
+            plan.ramparts.len()
+        );
+
+        let json = serde_json::to_string_pretty(&plan).expect("plans always serialize");
+        let written = fs::write(out_dir.join(format!("{}.json", room.name)), json)
+            .and_then(|_| fs::write(out_dir.join(format!("{}.txt", room.name)), base_plan::render_ascii(&room, &plan)));
+
+        if let Err(e) = written {
+            eprintln!("couldnt write the plan for {}: {}", room.name, e);
+        }
+    }
+}
@@ -13,6 +13,10 @@ pub fn run_global_goal_setters(memory: &mut ScreepsMemory, cache: &mut RoomCache
     setters::remote_invader_cleanup::determine_cleanup(memory, cache);
     setters::remote_defense::determine_remote_defense_needs(cache, memory);
     setters::room_claim::determine_room_claim_needs(memory, cache);
+    setters::room_claim::determine_shard_claim_needs(memory, cache);
+    setters::power_bank::determine_power_bank_needs(memory, cache);
+    setters::deposit::determine_deposit_needs(memory, cache);
+    setters::source_keeper::determine_source_keeper_needs(memory, cache);
 
     let post_goals = game::cpu::get_used();
     memory.stats.cpu.goal_creation = post_goals - pre_goals;
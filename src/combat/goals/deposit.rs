@@ -0,0 +1,132 @@
+use log::info;
+use screeps::{
+    find, game, Deposit, HasPosition, MapTextStyle, MapVisual, Part, Position, RoomCoordinate, RoomName,
+};
+
+use crate::{
+    config::DEPOSIT_MAX_COOLDOWN,
+    constants::CREEP_LIFETIME,
+    goal_memory::DepositGoal,
+    memory::{CreepMemory, Role, ScreepsMemory},
+    room::cache::RoomCache,
+    utils,
+};
+
+use super::{goal_creep_alive as alive, goal_creep_pending, keep_goal_creep, prune_requested, goal_spawn_request};
+
+// Harvesters are 20 work, 10 carry, 20 move. Haulers are 25 carry, 25 move.
+const WORK_PARTS: u32 = 20;
+const CARRY_PARTS: u32 = 25;
+// What a harvester has to bring back over its life to be worth the energy.
+const MIN_HARVEST_PER_LIFE: u32 = 1000;
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_goal(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    let cloned_goals = memory.goals.deposit.clone();
+
+    for goal_room in cloned_goals.keys() {
+        achieve_goal(goal_room, memory, cache);
+    }
+}
+
+// Deposits get a longer cooldown every harvest, so eventually its not worth the walk.
+pub fn deposit_profitable(last_cooldown: u32, distance: u32) -> bool {
+    if last_cooldown > DEPOSIT_MAX_COOLDOWN {
+        return false;
+    }
+
+    let working_ticks = CREEP_LIFETIME.saturating_sub(distance * 50);
+    let harvests = working_ticks / (last_cooldown + 1);
+
+    harvests * WORK_PARTS >= MIN_HARVEST_PER_LIFE
+}
+
+pub fn find_deposit(goal: &DepositGoal) -> Option<Deposit> {
+    let room = game::rooms().get(goal.deposit_room)?;
+
+    room.find(find::DEPOSITS, None)
+        .into_iter()
+        .find(|deposit| deposit.pos().xy() == goal.deposit_pos)
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn achieve_goal(target_room: &RoomName, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    let goal = memory.goals.deposit.get_mut(target_room).unwrap();
+
+    let requested = goal.requested.clone();
+    goal.harvesters.retain(|harvester| keep_goal_creep(harvester, &requested));
+    goal.haulers.retain(|hauler| keep_goal_creep(hauler, &requested));
+
+    let names = goal.harvesters.iter().chain(goal.haulers.iter()).collect::<Vec<_>>();
+    prune_requested(&mut goal.requested, &names);
+
+    if game::rooms().get(*target_room).is_some() {
+        if let Some(deposit) = find_deposit(goal) {
+            goal.last_cooldown = deposit.last_cooldown();
+            goal.decay_tick = game::time() + deposit.ticks_to_decay();
+        } else {
+            info!("[DEPOSIT] Deposit in {} is gone", target_room);
+            memory.goals.deposit.remove(target_room);
+            return;
+        }
+    }
+
+    let pos = Position::new(RoomCoordinate::new(15).unwrap(), RoomCoordinate::new(45).unwrap(), *target_room);
+    MapVisual::text(pos, format!("⛏️: {:?} {}", goal.deposit_type, goal.last_cooldown), MapTextStyle::default().color("#ff0000").font_size(7.0));
+
+    let distance = utils::calc_room_distance(target_room, &goal.responsible_room, true) as u32;
+
+    if goal.decay_tick <= game::time() || !deposit_profitable(goal.last_cooldown, distance) {
+        info!("[DEPOSIT] Deposit in {} isnt worth it anymore, cooldown {}", target_room, goal.last_cooldown);
+        memory.goals.deposit.remove(target_room);
+        return;
+    }
+
+    let harvesters_wanted = (goal.free_spots as usize).clamp(1, 2);
+
+    if goal.harvesters.len() < harvesters_wanted {
+        let name = format!("{}-{}-{}", utils::role_to_name(Role::DepositHarvester), goal.responsible_room, utils::get_unique_id());
+
+        goal.harvesters.push(name);
+    }
+
+    // One hauler per harvester thats actually out.
+    let live_harvesters = goal.harvesters.iter().filter(|harvester| alive(harvester)).count();
+    if goal.haulers.len() < live_harvesters {
+        let name = format!("{}-{}-{}", utils::role_to_name(Role::DepositHauler), goal.responsible_room, utils::get_unique_id());
+
+        goal.haulers.push(name);
+    }
+
+    // Keep asking for anyone that hasnt spawned yet.
+    for harvester in goal.harvesters.clone() {
+        if goal_creep_pending(&harvester, &goal.requested) {
+            let mut body = vec![Part::Work; WORK_PARTS as usize];
+            body.extend(vec![Part::Carry; 10]);
+            body.extend(vec![Part::Move; 20]);
+
+            push_request(cache, goal, Role::DepositHarvester, body, harvester);
+        }
+    }
+
+    for hauler in goal.haulers.clone() {
+        if goal_creep_pending(&hauler, &goal.requested) {
+            let mut body = vec![Part::Carry; CARRY_PARTS as usize];
+            body.extend(vec![Part::Move; CARRY_PARTS as usize]);
+
+            push_request(cache, goal, Role::DepositHauler, body, hauler);
+        }
+    }
+}
+
+fn push_request(cache: &mut RoomCache, goal: &mut DepositGoal, role: Role, body: Vec<Part>, name: String) {
+    let creep_memory = CreepMemory {
+        role,
+        owning_room: goal.responsible_room,
+        target_room: Some(goal.deposit_room),
+        ..CreepMemory::default()
+    };
+
+    let req = goal_spawn_request(cache, &mut goal.requested, body, 4.0, goal.deposit_room, creep_memory, name);
+    cache.spawning.global_spawn_queue.push(req);
+}
@@ -1,12 +1,17 @@
+use std::collections::HashMap;
+
 use log::info;
-use screeps::{game, Creep};
+use screeps::{game, Creep, Part, RoomName};
 
-use crate::{constants, memory::ScreepsMemory, room::cache::RoomCache};
+use crate::{config::GOAL_SPAWN_TIMEOUT, constants, memory::{CreepMemory, ScreepsMemory}, room::{cache::RoomCache, spawning::spawn_manager::SpawnRequest}, utils};
 
 pub mod room_reservation;
 pub mod remote_defense;
 pub mod remote_invader_cleanup;
 pub mod room_claim;
+pub mod power_bank;
+pub mod deposit;
+pub mod source_keeper;
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn run_goal_handlers(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
@@ -16,6 +21,9 @@ pub fn run_goal_handlers(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
     remote_defense::run_goal(memory, cache);
     remote_invader_cleanup::run_goal(memory, cache);
     room_claim::run_goal(memory, cache);
+    power_bank::run_goal(memory, cache);
+    deposit::run_goal(memory, cache);
+    source_keeper::run_goal(memory, cache);
 
     let post_goals = game::cpu::get_used();
     memory.stats.cpu.goal_execution = post_goals - pre_goals;
@@ -40,4 +48,44 @@ pub fn determine_group_attack_power(creeps: &Vec<Creep>) -> u32 {
 pub fn determine_single_attack_power(creep: &Creep) -> u32 {
     let body = creep.body();
     body.iter().map(|p| constants::part_attack_weight(&p.part())).sum::<u32>()
-}
\ No newline at end of file
+}
+
+pub fn goal_creep_alive(name: &str) -> bool {
+    game::creeps().get(name.to_string()).is_some()
+}
+
+// Goals name their creeps before asking for them, so a name sits in the goal well before the creep exists.
+// Keep it while the creep is out (or spawning), or while we are still waiting on the spawn.
+pub fn keep_goal_creep(name: &str, requested: &HashMap<String, u32>) -> bool {
+    goal_creep_alive(name) || requested.get(name).is_some_and(|tick| game::time() < tick + GOAL_SPAWN_TIMEOUT)
+}
+
+// Still waiting on it, and havent given up yet. Names the goal only just added havent been asked for yet,
+// goal_spawn_request stamps them the first time they go out.
+pub fn goal_creep_pending(name: &str, requested: &HashMap<String, u32>) -> bool {
+    !goal_creep_alive(name) && requested.get(name).map_or(true, |tick| game::time() < tick + GOAL_SPAWN_TIMEOUT)
+}
+
+// Forget about requests that spawned, or that the goal dropped.
+pub fn prune_requested(requested: &mut HashMap<String, u32>, names: &[&String]) {
+    requested.retain(|name, _| !goal_creep_alive(name) && names.contains(&name));
+}
+
+// The spawn queue is rebuilt every tick, so goals ask again each tick until the creep shows up.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn goal_spawn_request(cache: &RoomCache, requested: &mut HashMap<String, u32>, body: Vec<Part>, priority: f64, destination_room: RoomName, creep_memory: CreepMemory, name: String) -> SpawnRequest {
+    requested.entry(name.clone()).or_insert(game::time());
+
+    let cost = utils::get_body_cost(&body);
+
+    cache.spawning.create_global_spawn_request(
+        creep_memory.role,
+        body,
+        priority,
+        cost,
+        destination_room,
+        Some(creep_memory),
+        None,
+        Some(name),
+    )
+}
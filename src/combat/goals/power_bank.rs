@@ -0,0 +1,184 @@
+use log::info;
+use screeps::{
+    find, game, MapTextStyle, MapVisual, Part, Position, ResourceType, RoomCoordinate, RoomName,
+    StructureObject, StructurePowerBank, ATTACK_POWER,
+};
+
+use crate::{
+    goal_memory::PowerBankGoal,
+    memory::{CreepMemory, Role, ScreepsMemory},
+    room::cache::RoomCache,
+    utils,
+};
+
+use super::{goal_creep_alive as alive, goal_creep_pending, keep_goal_creep, prune_requested, goal_spawn_request};
+
+// Attackers are 20 attack, 20 move. Healers 25 heal, 25 move, which
+// out-heals the 50% damage the bank reflects back onto the attacker.
+const ATTACK_PARTS: u32 = 20;
+const HEAL_PARTS: u32 = 25;
+const CARRY_PARTS: u32 = 25;
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_goal(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    let cloned_goals = memory.goals.power_bank.clone();
+
+    for goal_room in cloned_goals.keys() {
+        achieve_goal(goal_room, memory, cache);
+    }
+}
+
+// No point sending more than 3 pairs, they start tripping over eachother.
+pub fn pair_count(free_spots: u8) -> u32 {
+    (free_spots as u32).clamp(1, 3)
+}
+
+pub fn estimate_break_ticks(hits: u32, attackers: u32) -> u32 {
+    hits / (attackers.max(1) * ATTACK_PARTS * ATTACK_POWER)
+}
+
+pub fn find_power_bank(room_name: &RoomName) -> Option<StructurePowerBank> {
+    let room = game::rooms().get(*room_name)?;
+
+    room.find(find::STRUCTURES, None).into_iter().find_map(|structure| {
+        if let StructureObject::StructurePowerBank(bank) = structure {
+            Some(bank)
+        } else {
+            None
+        }
+    })
+}
+
+pub fn power_left_in_room(room_name: &RoomName) -> bool {
+    if let Some(room) = game::rooms().get(*room_name) {
+        let dropped = room
+            .find(find::DROPPED_RESOURCES, None)
+            .iter()
+            .any(|r| r.resource_type() == ResourceType::Power);
+        let ruins = room
+            .find(find::RUINS, None)
+            .iter()
+            .any(|r| r.store().get_used_capacity(Some(ResourceType::Power)) > 0);
+
+        return dropped || ruins;
+    }
+
+    true
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn achieve_goal(target_room: &RoomName, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    let goal = memory.goals.power_bank.get_mut(target_room).unwrap();
+
+    let requested = goal.requested.clone();
+    // Half a pair is no use, so once either one is gone for good drop the pair and queue a fresh one.
+    // Whoever is left over sees the pair is gone and recycles.
+    goal.pairs.retain(|(attacker, healer)| keep_goal_creep(attacker, &requested) && keep_goal_creep(healer, &requested));
+    goal.haulers.retain(|hauler| keep_goal_creep(hauler, &requested));
+
+    let names = goal.pairs.iter().flat_map(|(attacker, healer)| [attacker, healer]).chain(goal.haulers.iter()).collect::<Vec<_>>();
+    prune_requested(&mut goal.requested, &names);
+
+    if game::rooms().get(*target_room).is_some() {
+        if let Some(bank) = find_power_bank(target_room) {
+            goal.hits = bank.hits();
+            goal.amount = bank.power();
+        } else {
+            goal.bank_broken = true;
+        }
+    }
+
+    let pos = Position::new(RoomCoordinate::new(15).unwrap(), RoomCoordinate::new(45).unwrap(), *target_room);
+    MapVisual::text(pos, format!("⚡: {} {}", goal.amount, goal.hits), MapTextStyle::default().color("#ff0000").font_size(7.0));
+
+    if goal.bank_broken && game::rooms().get(*target_room).is_some() && !power_left_in_room(target_room) {
+        info!("[POWER BANK] Power in {} has been cleared out", target_room);
+        memory.goals.power_bank.remove(target_room);
+        return;
+    }
+
+    if !goal.bank_broken && goal.decay_tick <= game::time() {
+        info!("[POWER BANK] Bank in {} decayed before we got to it", target_room);
+        memory.goals.power_bank.remove(target_room);
+        return;
+    }
+
+    // Everyone came home with their haul.
+    if goal.haulers_spawned && goal.haulers.is_empty() {
+        memory.goals.power_bank.remove(target_room);
+        return;
+    }
+
+    let travel_ticks = utils::calc_room_distance(target_room, &goal.responsible_room, true) as u32 * 50;
+    let live_attackers = goal.pairs.iter().filter(|(attacker, _)| alive(attacker)).count() as u32;
+    let break_ticks = estimate_break_ticks(goal.hits, live_attackers);
+    let ticks_left = goal.decay_tick.saturating_sub(game::time());
+
+    if !goal.bank_broken {
+        if goal.pairs.len() < pair_count(goal.free_spots) as usize {
+            // If a new pair wouldnt make it in time, dont waste the energy.
+            if travel_ticks + 300 + estimate_break_ticks(goal.hits, pair_count(goal.free_spots)) > ticks_left {
+                if goal.pairs.is_empty() {
+                    memory.goals.power_bank.remove(target_room);
+                    return;
+                }
+            } else {
+                let attacker_name = format!("{}-{}-{}", utils::role_to_name(Role::PowerBankAttacker), goal.responsible_room, utils::get_unique_id());
+                let healer_name = format!("{}-{}-{}", utils::role_to_name(Role::PowerBankHealer), goal.responsible_room, utils::get_unique_id());
+
+                goal.pairs.push((attacker_name, healer_name));
+            }
+        }
+
+        // Keep asking for whoever in a pair isnt out yet, so partners dont get left alone.
+        for (attacker, healer) in goal.pairs.clone() {
+            if goal_creep_pending(&attacker, &goal.requested) {
+                let mut body = vec![Part::Move; ATTACK_PARTS as usize];
+                body.extend(vec![Part::Attack; ATTACK_PARTS as usize]);
+
+                push_request(cache, goal, Role::PowerBankAttacker, body, attacker);
+            }
+
+            if goal_creep_pending(&healer, &goal.requested) {
+                let mut body = vec![Part::Move; HEAL_PARTS as usize];
+                body.extend(vec![Part::Heal; HEAL_PARTS as usize]);
+
+                push_request(cache, goal, Role::PowerBankHealer, body, healer);
+            }
+        }
+    }
+
+    // Time the haulers so they show up right as the bank breaks.
+    let haulers_wanted = goal.amount.div_ceil(CARRY_PARTS * 50);
+    if !goal.haulers_spawned && (goal.bank_broken || break_ticks <= travel_ticks + 150) {
+        if goal.haulers.len() as u32 >= haulers_wanted {
+            goal.haulers_spawned = true;
+        } else {
+            let name = format!("{}-{}-{}", utils::role_to_name(Role::PowerBankHauler), goal.responsible_room, utils::get_unique_id());
+
+            goal.haulers.push(name);
+        }
+    }
+
+    for hauler in goal.haulers.clone() {
+        if goal_creep_pending(&hauler, &goal.requested) {
+            let mut body = vec![Part::Carry; CARRY_PARTS as usize];
+            body.extend(vec![Part::Move; CARRY_PARTS as usize]);
+
+            push_request(cache, goal, Role::PowerBankHauler, body, hauler);
+        }
+    }
+}
+
+fn push_request(cache: &mut RoomCache, goal: &mut PowerBankGoal, role: Role, body: Vec<Part>, name: String) {
+    let creep_memory = CreepMemory {
+        role,
+        owning_room: goal.responsible_room,
+        target_room: Some(goal.bank_room),
+        ..CreepMemory::default()
+    };
+
+    // Travel time is worked out from the responsible room, and pairs have to show up together, so they all spawn there.
+    let req = goal_spawn_request(cache, &mut goal.requested, body, 6.0, goal.bank_room, creep_memory, name).pinned_to(goal.responsible_room);
+    cache.spawning.global_spawn_queue.push(req);
+}
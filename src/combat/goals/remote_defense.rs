@@ -2,7 +2,7 @@
 use log::info;
 use screeps::{game, Part, ResourceType, Room, RoomName, SharedCreepProperties};
 
-use crate::{constants::{part_attack_weight, HOSTILE_PARTS}, goal_memory::{AttackingCreep, RemoteDefenseGoal}, memory::{CreepMemory, Role, ScreepsMemory}, room::cache::RoomCache, utils::{self, get_body_cost, get_unique_id, role_to_name, under_storage_gate}};
+use crate::{constants::{part_attack_weight, HOSTILE_PARTS}, goal_memory::{AttackingCreep, RemoteDefenseGoal}, memory::{CreepMemory, Role, ScreepsMemory}, room::{cache::RoomCache, labs}, utils::{self, get_body_cost, get_unique_id, role_to_name, under_storage_gate}};
 
 use super::{determine_group_attack_power, determine_single_attack_power};
 
@@ -247,19 +247,12 @@ fn determine_spawn_needs(responsible_room: &Room, goal: &mut RemoteDefenseGoal,
             prio *= 2.0;
         }
 
+        let boosts = labs::plan_boosts(responsible_cache, &parts, &[Part::RangedAttack, Part::Heal, Part::Move]);
+
         info!("Spawning with body {:?} prio: {}, cost {}", parts, prio, cost);
-        let req = cache.spawning.create_room_spawn_request(Role::RemoteDefender, parts, prio, cost, responsible_room.name(), Some(creep_memory), None, Some(creep_name.clone()));
-
-        if let Some(reqs) = cache.spawning.room_spawn_queue.get_mut(&responsible_room.name()) {
-            info!("Has queue, pushing");
-            reqs.push(req);
-        } else {
-            info!("No queue.");
-            cache
-                .spawning
-                .room_spawn_queue
-                .insert(responsible_room.name(), vec![req]);
-        }
+        let req = cache.spawning.create_global_spawn_request(Role::RemoteDefender, parts, prio, cost, goal.defending_remote, Some(creep_memory), None, Some(creep_name.clone())).with_boosts(boosts);
+
+        cache.spawning.global_spawn_queue.push(req);
 
         goal.creeps_assigned.push(creep_name);
     } else {
@@ -127,12 +127,12 @@ pub fn achieve_goal(target_room: &RoomName, memory: &mut ScreepsMemory, cache: &
 
             priority += reservation as f64 / 100.0;
 
-            let req = cache.spawning.create_room_spawn_request(
+            let req = cache.spawning.create_global_spawn_request(
                 Role::InvaderCoreCleaner,
                 body,
                 priority,
                 current_cost,
-                responsible_room.room.name(),
+                *target_room,
                 Some(memory),
                 None,
                 Some(name.clone()),
@@ -140,18 +140,7 @@ pub fn achieve_goal(target_room: &RoomName, memory: &mut ScreepsMemory, cache: &
 
             goal.creeps_assigned.push(name.clone());
 
-            if let Some(reqs) = cache
-                .spawning
-                .room_spawn_queue
-                .get_mut(&responsible_room.room.name())
-            {
-                reqs.push(req);
-            } else {
-                cache
-                    .spawning
-                    .room_spawn_queue
-                    .insert(responsible_room.room.name(), vec![req]);
-            }
+            cache.spawning.global_spawn_queue.push(req);
         } else {
             info!("No responsible room found for {}", target_room);
         }
@@ -6,9 +6,10 @@ use screeps::{
 };
 
 use crate::{
-    goal_memory::RoomClaimGoal,
+    goal_memory::{ClaimTarget, RoomClaimGoal},
+    inter_shard,
     memory::{CreepMemory, Role, ScreepsMemory},
-    room::cache::RoomCache,
+    room::cache::{CachedRoom, RoomCache},
     traits::{intents_tracking::RoomExtensionsTracking, position::RoomXYExtensions},
     utils::{self, distance_transform, new_xy, role_to_name, under_storage_gate},
 };
@@ -18,8 +19,12 @@ pub fn run_goal(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
     let cloned_goals = memory.goals.room_claim.clone();
     let invader_goals = cloned_goals.keys();
 
-    for goal_room in invader_goals {
-        achieve_goal(goal_room, memory, cache);
+    for goal_key in invader_goals {
+        if let Some(target_shard) = &goal_key.shard {
+            achieve_shard_goal(goal_key, target_shard, memory, cache);
+        } else {
+            achieve_goal(goal_key, memory, cache);
+        }
     }
 }
 
@@ -39,13 +44,15 @@ pub fn clear_creeps(goal: &mut RoomClaimGoal) {
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
-fn achieve_goal(goal_room: &RoomName, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
-    let goal = memory.goals.room_claim.get_mut(goal_room).unwrap();
+fn achieve_goal(goal_key: &ClaimTarget, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    let goal_room = &goal_key.room;
+    let goal = memory.goals.room_claim.get_mut(goal_key).unwrap();
+
     let goal_game_room = game::rooms().get(*goal_room);
 
     if let Some(scouting_data) = memory.scouted_rooms.get(goal_room) {
         if scouting_data.owner.is_some() || scouting_data.reserved.is_some() {
-            memory.goals.room_claim.remove(goal_room);
+            memory.goals.room_claim.remove(goal_key);
             memory.expansion = None;
             return;
         }
@@ -103,29 +110,18 @@ fn achieve_goal(goal_room: &RoomName, memory: &mut ScreepsMemory, cache: &mut Ro
 
         goal.creeps_assigned.push(name.clone());
 
-        let spawn_request = cache.spawning.create_room_spawn_request(
+        let spawn_request = cache.spawning.create_global_spawn_request(
             Role::Claimer,
             claimer_body,
             40.0,
             claimer_cost,
-            responsible_room.unwrap(),
+            *goal_room,
             Some(creep_memory),
             None,
             Some(name),
         );
 
-        if let Some(reqs) = cache
-            .spawning
-            .room_spawn_queue
-            .get_mut(&responsible_room.unwrap())
-        {
-            reqs.push(spawn_request);
-        } else {
-            cache
-                .spawning
-                .room_spawn_queue
-                .insert(responsible_room.unwrap(), vec![spawn_request]);
-        }
+        cache.spawning.global_spawn_queue.push(spawn_request);
     } else if claimed {
         if goal.creeps_assigned.len() < 3 {
             let claimer_body =
@@ -170,29 +166,19 @@ fn achieve_goal(goal_room: &RoomName, memory: &mut ScreepsMemory, cache: &mut Ro
 
             goal.creeps_assigned.push(name.clone());
 
-            let spawn_request = cache.spawning.create_room_spawn_request(
+            // Any of our rooms nearby can help build it up, not just the parent.
+            let spawn_request = cache.spawning.create_global_spawn_request(
                 Role::ExpansionBuilder,
                 claimer_body,
                 priority,
                 claimer_cost,
-                responsible_room.unwrap(),
+                *goal_room,
                 Some(creep_memory),
                 None,
                 Some(name),
             );
 
-            if let Some(reqs) = cache
-                .spawning
-                .room_spawn_queue
-                .get_mut(&responsible_room.unwrap())
-            {
-                reqs.push(spawn_request);
-            } else {
-                cache
-                    .spawning
-                    .room_spawn_queue
-                    .insert(responsible_room.unwrap(), vec![spawn_request]);
-            }
+            cache.spawning.global_spawn_queue.push(spawn_request);
         }
 
         let expansion_game_room = game::rooms().get(*goal_room).unwrap();
@@ -211,7 +197,7 @@ fn achieve_goal(goal_room: &RoomName, memory: &mut ScreepsMemory, cache: &mut Ro
         if !expansion_cache.structures.spawns.is_empty()
             && expansion_game_room.controller().unwrap().level() >= 2
         {
-            memory.goals.room_claim.remove(goal_room);
+            memory.goals.room_claim.remove(goal_key);
 
             return;
         }
@@ -233,76 +219,172 @@ fn achieve_goal(goal_room: &RoomName, memory: &mut ScreepsMemory, cache: &mut Ro
                 memory,
                 None,
             );
-            let cached_room = cache.rooms.get_mut(&responsible_room.unwrap()).unwrap();
+            let cached_room = cache.rooms.get(&responsible_room.unwrap()).unwrap();
 
-            let available_positions = distance_transform(goal_room, None, true, false);
-            let mut available_xy = Vec::new();
+            place_spawn_site(goal_room, &expansion_game_room, cached_room);
+        }
+    }
+}
 
-            let exits = expansion_game_room.find(find::EXIT, None);
-            let mut xy_exits = Vec::new();
+// The colony is on another shard, so all we can see is what it publishes in InterShardMemory.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+fn achieve_shard_goal(goal_key: &ClaimTarget, target_shard: &str, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    let goal_room = &goal_key.room;
+    let shard_rooms = inter_shard::read_remote(target_shard).map(|data| data.rooms).unwrap_or_default();
+
+    // Spawn and a safemode, it can look after itself from here.
+    if shard_rooms.iter().any(|room| room.has_spawn && room.rcl >= 2) {
+        info!("[INTER SHARD] Colony on {} is standing on its own", target_shard);
+        memory.goals.room_claim.remove(goal_key);
+        return;
+    }
 
-            for exit in exits {
-                xy_exits.push(new_xy(exit.x(), exit.y()));
-            }
+    let portal_room = inter_shard::portals_to(memory, target_shard)
+        .into_iter()
+        .find(|(_, destination)| destination == goal_room)
+        .map(|(pos, _)| pos.room_name());
 
-            for x in 1..49 {
-                for y in 1..49 {
-                    let xy = new_xy(x, y);
+    let Some(portal_room) = portal_room else {
+        info!("[INTER SHARD] Lost the portal to {} on {}, dropping the claim", goal_room, target_shard);
+        memory.goals.room_claim.remove(goal_key);
+        return;
+    };
 
-                    let score = available_positions.get(xy);
-                    let mut should_continue = false;
+    let Some(responsible_room) = utils::find_closest_owned_room(&portal_room, cache, Some(4)) else {
+        return;
+    };
 
-                    for exit in &xy_exits {
-                        if exit.get_range_to(xy) <= 8 {
-                            should_continue = true;
-                        }
-                    }
+    // Creeps that went through the portal are gone from here, but they still count.
+    let handed_off = inter_shard::read_local().creeps;
+    let goal = memory.goals.room_claim.get_mut(goal_key).unwrap();
+    goal.creeps_assigned.retain(|name| game::creeps().get(name.to_string()).is_some() || handed_off.contains_key(name));
 
-                    if score >= 7 && !should_continue {
-                        available_xy.push(xy);
-                    }
-                }
-            }
+    let pos = Position::new(RoomCoordinate::new(5).unwrap(), RoomCoordinate::new(5).unwrap(), portal_room);
+    MapVisual::text(pos, format!("🚩 {}", target_shard), MapTextStyle::default());
 
-            let cpos = cached_room.structures.controller.as_ref().map(|controller| controller.pos());
+    let (role, body, target_room, priority) = if let Some(colony) = shard_rooms.first() {
+        let builders = goal
+            .creeps_assigned
+            .iter()
+            .filter(|name| utils::name_to_role(name) == Some(Role::ExpansionBuilder))
+            .count();
 
-            let mut lowest = u32::MAX;
-            let mut lowest_pos = None;
+        if builders >= 3 {
+            return;
+        }
 
-            for pos in available_xy {
-                let xy = pos.as_position(goal_room);
+        let body = get_creep_body(&game::rooms().get(responsible_room).unwrap());
 
-                let dist = cpos.unwrap().pos().xy().get_range_to(pos);
-                let mut source_dist = 0;
+        (Role::ExpansionBuilder, body, colony.name, 10.0)
+    } else {
+        if !goal.creeps_assigned.is_empty() {
+            return;
+        }
 
-                for source in &cached_room.resources.sources {
-                    source_dist += source.source.pos().xy().get_range_to(pos);
-                }
+        let mut body = vec![Part::Claim];
+        body.extend(vec![Part::Move; 5]);
 
-                let total_dist = dist + source_dist;
+        (Role::Claimer, body, *goal_room, 40.0)
+    };
 
-                if game::cpu::get_used() >= 475.0 {
-                    break;
-                }
+    let cost = utils::get_body_cost(&body);
 
-                if (total_dist as u32) < lowest {
-                    lowest = total_dist as u32;
-                    lowest_pos = Some(xy);
+    let creep_memory = CreepMemory {
+        role,
+        owning_room: responsible_room,
+        target_room: Some(target_room),
+        target_shard: Some(target_shard.to_string()),
+        ..Default::default()
+    };
+
+    let name = format!("{}-{}-{}", role_to_name(role), responsible_room, utils::get_unique_id());
+
+    goal.creeps_assigned.push(name.clone());
+
+    // The portal room is as close as we get to where its going.
+    let spawn_request = cache.spawning.create_global_spawn_request(
+        role,
+        body,
+        priority,
+        cost,
+        portal_room,
+        Some(creep_memory),
+        None,
+        Some(name),
+    );
+
+    cache.spawning.global_spawn_queue.push(spawn_request);
+}
+
+// Find somewhere open, close to the controller and sources, and put the spawn down.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn place_spawn_site(goal_room: &RoomName, expansion_game_room: &Room, cached_room: &CachedRoom) {
+    let available_positions = distance_transform(goal_room, None, true, false);
+    let mut available_xy = Vec::new();
+
+    let exits = expansion_game_room.find(find::EXIT, None);
+    let mut xy_exits = Vec::new();
+
+    for exit in exits {
+        xy_exits.push(new_xy(exit.x(), exit.y()));
+    }
+
+    for x in 1..49 {
+        for y in 1..49 {
+            let xy = new_xy(x, y);
+
+            let score = available_positions.get(xy);
+            let mut should_continue = false;
+
+            for exit in &xy_exits {
+                if exit.get_range_to(xy) <= 8 {
+                    should_continue = true;
                 }
             }
 
-            if lowest_pos.is_none() {
-                return;
+            if score >= 7 && !should_continue {
+                available_xy.push(xy);
             }
+        }
+    }
+
+    let cpos = cached_room.structures.controller.as_ref().map(|controller| controller.pos());
+
+    let mut lowest = u32::MAX;
+    let mut lowest_pos = None;
+
+    for pos in available_xy {
+        let xy = pos.as_position(goal_room);
 
-            //let flag = lowest_pos.unwrap().create_flag(
-            //    Some(&JsString::from_str("PlacedSpawn").unwrap()),
-            //    None,
-            //    None,
-            //);
-            let _ = expansion_game_room.ITcreate_construction_site(lowest_pos.unwrap().x().u8(), lowest_pos.unwrap().y().u8(), StructureType::Spawn, None);
+        let dist = cpos.unwrap().pos().xy().get_range_to(pos);
+        let mut source_dist = 0;
+
+        for source in &cached_room.resources.sources {
+            source_dist += source.source.pos().xy().get_range_to(pos);
+        }
+
+        let total_dist = dist + source_dist;
+
+        if game::cpu::get_used() >= 475.0 {
+            break;
+        }
+
+        if (total_dist as u32) < lowest {
+            lowest = total_dist as u32;
+            lowest_pos = Some(xy);
         }
     }
+
+    if lowest_pos.is_none() {
+        return;
+    }
+
+    //let flag = lowest_pos.unwrap().create_flag(
+    //    Some(&JsString::from_str("PlacedSpawn").unwrap()),
+    //    None,
+    //    None,
+    //);
+    let _ = expansion_game_room.ITcreate_construction_site(lowest_pos.unwrap().x().u8(), lowest_pos.unwrap().y().u8(), StructureType::Spawn, None);
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
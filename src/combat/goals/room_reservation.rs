@@ -1,8 +1,10 @@
+use std::collections::HashMap;
+
 use log::info;
 use screeps::{game, Part, RoomName, SharedCreepProperties};
 
 use crate::{
-    config, goal_memory::RoomReservationGoal, memory::{CreepMemory, Role, ScreepsMemory}, room::cache::RoomCache, utils::{self, get_body_cost, get_unique_id, role_to_name, under_storage_gate}
+    config, goal_memory::RoomReservationGoal, memory::{CreepMemory, Role, ScreepsMemory}, room::{cache::RoomCache, spawning::prespawn}, utils::{self, get_body_cost, get_unique_id, role_to_name, under_storage_gate}
 };
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -39,7 +41,15 @@ pub fn attain_reservation(
     let goal = memory.goals.room_reservation.get_mut(target_room).unwrap();
 
     clear_creeps(goal);
-    let current_parts = get_claim_parts(goal);
+
+    // Reservers that will be gone by the time a new one shows up dont count.
+    let replacing = goal
+        .creeps_assigned
+        .iter()
+        .filter(|name| replacement_due(name, &memory.creeps))
+        .cloned()
+        .collect::<Vec<_>>();
+    let current_parts = get_claim_parts(goal, &replacing);
 
     if let Some(remote_room_memory) = memory.remote_rooms.get(target_room) {
         if remote_room_memory.under_attack {
@@ -57,7 +67,7 @@ pub fn attain_reservation(
         return;
     }
 
-    if (current_parts < 2 && goal.accessible_reservation_spots >= 1) && goal.creeps_assigned.len() < goal.accessible_reservation_spots as usize {
+    if (current_parts < 2 && goal.accessible_reservation_spots >= 1) && goal.creeps_assigned.len() - replacing.len() < goal.accessible_reservation_spots as usize {
         let new_creep = spawn_creep(goal, cache);
         if let Some(new) = new_creep {
             goal.creeps_assigned.push(new);
@@ -79,10 +89,14 @@ pub fn attain_reservation(
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
-pub fn get_claim_parts(goal: &RoomReservationGoal) -> u8 {
+pub fn get_claim_parts(goal: &RoomReservationGoal, skip: &[String]) -> u8 {
     let mut count = 0;
 
     for creep in &goal.creeps_assigned {
+        if skip.contains(creep) {
+            continue;
+        }
+
         let creep = game::creeps().get(creep.to_string()).unwrap();
 
         for part in creep.body() {
@@ -95,6 +109,16 @@ pub fn get_claim_parts(goal: &RoomReservationGoal) -> u8 {
     count
 }
 
+fn replacement_due(name: &str, creeps: &HashMap<String, CreepMemory>) -> bool {
+    let Some(creep) = game::creeps().get(name.to_string()) else {
+        return false;
+    };
+
+    // The replacement will be about the same size.
+    let body = creep.body().iter().map(|part| part.part()).collect::<Vec<_>>();
+    prespawn::needs_replacement(name, creeps, prespawn::spawn_time(&body))
+}
+
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn spawn_creep(goal: &RoomReservationGoal, cache: &mut RoomCache) -> Option<String> {
     let room = utils::find_closest_owned_room(&goal.reservation_target, cache, Some(3));
@@ -177,7 +201,7 @@ pub fn spawn_creep(goal: &RoomReservationGoal, cache: &mut RoomCache) -> Option<
 
         // if we have one claim part, its doing nothing.
         // So we can bump the priority to assist the 1 part creep
-        if get_claim_parts(goal) == 1 {
+        if get_claim_parts(goal, &[]) == 1 {
             priority *= 1.5;
         }
 
@@ -200,24 +224,17 @@ pub fn spawn_creep(goal: &RoomReservationGoal, cache: &mut RoomCache) -> Option<
             }
         }
 
-        let req = cache.spawning.create_room_spawn_request(
+        let req = cache.spawning.create_global_spawn_request(
             Role::Reserver,
             body,
             priority,
             cost,
-            best_spawned,
+            goal.reservation_target,
             Some(creep_memory),
             None,
             Some(name.clone()),
         );
-        if let Some(reqs) = cache.spawning.room_spawn_queue.get_mut(&best_spawned) {
-            reqs.push(req);
-        } else {
-            cache
-                .spawning
-                .room_spawn_queue
-                .insert(best_spawned, vec![req]);
-        }
+        cache.spawning.global_spawn_queue.push(req);
 
         return Some(name)
     }
@@ -0,0 +1,109 @@
+use log::info;
+use screeps::{game, MapTextStyle, MapVisual, Part, Position, RoomCoordinate, RoomName};
+
+use crate::{
+    constants::CREEP_LIFETIME,
+    goal_memory::SourceKeeperGoal,
+    memory::{CreepMemory, Role, ScreepsMemory},
+    room::cache::RoomCache,
+    utils,
+};
+
+use super::{goal_creep_alive, goal_creep_pending, keep_goal_creep, prune_requested, goal_spawn_request};
+
+// Enough to out-trade a keeper one on one, and still heal up between spawns.
+const ATTACK_PARTS: usize = 19;
+const HEAL_PARTS: usize = 6;
+const MOVE_PARTS: usize = 25;
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_goal(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    let cloned_goals = memory.goals.source_keeper.clone();
+
+    for goal_room in cloned_goals.keys() {
+        achieve_goal(goal_room, memory, cache);
+    }
+}
+
+// Is there a killer out in the room to keep our harvesters alive.
+pub fn keepers_handled(memory: &ScreepsMemory, keeper_room: &RoomName) -> bool {
+    memory.goals.source_keeper.get(keeper_room).is_some_and(|goal| {
+        goal.creeps_assigned
+            .iter()
+            .any(|name| game::creeps().get(name.to_string()).is_some_and(|creep| !creep.spawning()))
+    })
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn achieve_goal(target_room: &RoomName, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    if !memory.remote_rooms.contains_key(target_room) {
+        info!("[SOURCE KEEPERS] {} is no longer a remote, dropping it", target_room);
+        memory.goals.source_keeper.remove(target_room);
+        return;
+    }
+
+    let goal = memory.goals.source_keeper.get_mut(target_room).unwrap();
+
+    let requested = goal.requested.clone();
+    goal.creeps_assigned.retain(|name| keep_goal_creep(name, &requested));
+
+    let names = goal.creeps_assigned.iter().collect::<Vec<_>>();
+    prune_requested(&mut goal.requested, &names);
+
+    let pos = Position::new(RoomCoordinate::new(15).unwrap(), RoomCoordinate::new(45).unwrap(), *target_room);
+    MapVisual::text(pos, format!("🗡️: {}", goal.creeps_assigned.len()), MapTextStyle::default().color("#ff0000").font_size(7.0));
+
+    let mut body = vec![Part::Move; MOVE_PARTS];
+    body.extend(vec![Part::Attack; ATTACK_PARTS]);
+    body.extend(vec![Part::Heal; HEAL_PARTS]);
+
+    // Already waiting on a replacement, keep asking for it.
+    if let Some(pending) = goal.creeps_assigned.iter().find(|name| goal_creep_pending(name, &goal.requested)).cloned() {
+        push_request(cache, goal, body, pending);
+        return;
+    }
+
+    // Get the replacement out before the current one dies, so the keepers never get a free spawn.
+    let travel_time = utils::calc_room_distance(target_room, &goal.responsible_room, true) as u32 * 50;
+    let spawn_time = (ATTACK_PARTS + HEAL_PARTS + MOVE_PARTS) as u32 * 3;
+
+    // Nobody out at all is its own case, dont lean on all() being true for nothing.
+    let living = goal.creeps_assigned.iter().filter(|name| goal_creep_alive(name)).collect::<Vec<_>>();
+    let needs_replacement = living.is_empty()
+        || living.iter().all(|name| {
+            game::creeps()
+                .get(name.to_string())
+                .is_some_and(|creep| creep.ticks_to_live().unwrap_or(CREEP_LIFETIME) < travel_time + spawn_time)
+        });
+
+    if !needs_replacement {
+        return;
+    }
+
+    let cost = utils::get_body_cost(&body);
+
+    if let Some(responsible_cache) = cache.rooms.get(&goal.responsible_room) {
+        if responsible_cache.room.energy_capacity_available() < cost {
+            return;
+        }
+    } else {
+        return;
+    }
+
+    let name = format!("{}-{}-{}", utils::role_to_name(Role::KeeperKiller), goal.responsible_room, utils::get_unique_id());
+
+    goal.creeps_assigned.push(name.clone());
+    push_request(cache, goal, body, name);
+}
+
+fn push_request(cache: &mut RoomCache, goal: &mut SourceKeeperGoal, body: Vec<Part>, name: String) {
+    let creep_memory = CreepMemory {
+        role: Role::KeeperKiller,
+        owning_room: goal.responsible_room,
+        target_room: Some(goal.keeper_room),
+        ..CreepMemory::default()
+    };
+
+    let req = goal_spawn_request(cache, &mut goal.requested, body, 20.0, goal.keeper_room, creep_memory, name);
+    cache.spawning.global_spawn_queue.push(req);
+}
@@ -5,4 +5,5 @@ pub mod ally;
 pub mod goals;
 pub mod setters;
 pub mod global;
-pub mod safemode;
\ No newline at end of file
+pub mod safemode;
+pub mod nuke_planner;
\ No newline at end of file
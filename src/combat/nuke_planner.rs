@@ -0,0 +1,144 @@
+use log::info;
+use screeps::{game, ResourceType, RoomPosition, RoomXY};
+
+use crate::{
+    allies,
+    config::{NUKE_MAX_SCOUT_AGE, NUKE_MIN_HATE},
+    constants::{nuke_structure_weight, NUKER_GHODIUM_CAPACITY, NUKER_RANGE, NUKE_LAND_TIME},
+    memory::{EnemyPlayer, NukeLaunch, ScoutedStructure, ScreepsMemory},
+    room::cache::RoomCache,
+    traits::intents_tracking::StructureNukerExtensionsTracking,
+    utils,
+};
+
+// Center tile takes 10M, everything within 2 takes 5M, so the middle counts double.
+const NUKE_BLAST_RANGE: u8 = 2;
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_nuke_planner(memory: &mut ScreepsMemory, cache: &RoomCache) {
+    // Hang onto launches a little past landing, so follow-ups can see what happened.
+    memory.nuke_launches.retain(|launch| launch.impact_tick + 1000 > game::time());
+
+    if game::time() % 100 != 0 {
+        return;
+    }
+
+    let mut enemies = nuke_enemies(memory);
+    enemies.sort_by(|a, b| b.hate.partial_cmp(&a.hate).unwrap_or(std::cmp::Ordering::Equal));
+
+    let targets = enemies
+        .iter()
+        .flat_map(|enemy| enemy.owned_rooms.iter().copied())
+        .collect::<Vec<_>>();
+
+    if targets.is_empty() {
+        return;
+    }
+
+    let mut launches = Vec::new();
+
+    for room_name in &cache.my_rooms {
+        let nuker = if let Some(nuker) = cache.rooms.get(room_name).and_then(|room_cache| room_cache.structures.nuker.clone()) {
+            nuker
+        } else {
+            continue;
+        };
+
+        if !nuker_ready(&nuker) {
+            continue;
+        }
+
+        // Highest hate first, so the first room in range with something worth hitting wins.
+        let target = targets.iter().find_map(|target_room| {
+            if utils::calc_room_distance(room_name, target_room, true) as u32 > NUKER_RANGE {
+                return None;
+            }
+
+            let already_targeted = memory
+                .nuke_launches
+                .iter()
+                .chain(launches.iter())
+                .filter(|launch| launch.target_room == *target_room && launch.impact_tick > game::time())
+                .map(|launch| launch.target_pos)
+                .collect::<Vec<_>>();
+
+            // Dont aim off old intel, the observers will get us a fresh look soon enough.
+            let scouted = memory.scouted_rooms.get(target_room)?;
+            if game::time().saturating_sub(scouted.last_scouted) > NUKE_MAX_SCOUT_AGE {
+                return None;
+            }
+
+            let structures = scouted.structures.as_ref()?;
+            let (pos, score) = best_impact_point(structures, &already_targeted)?;
+
+            if score == 0 {
+                return None;
+            }
+
+            Some((*target_room, pos, score))
+        });
+
+        if let Some((target_room, target_pos, score)) = target {
+            let position = RoomPosition::new(target_pos.x.u8(), target_pos.y.u8(), target_room);
+
+            if nuker.ITlaunch_nuke(&position).is_ok() {
+                info!(
+                    "[NUKES] Room {} launched a nuke at {} {:?}, scoring {}, landing in {} ticks",
+                    room_name, target_room, target_pos, score, NUKE_LAND_TIME
+                );
+
+                launches.push(NukeLaunch {
+                    from_room: *room_name,
+                    target_room,
+                    target_pos,
+                    launch_tick: game::time(),
+                    impact_tick: game::time() + NUKE_LAND_TIME,
+                });
+            }
+        }
+    }
+
+    memory.nuke_launches.extend(launches);
+}
+
+// Everyone who has earned a nuke.
+pub fn nuke_enemies(memory: &ScreepsMemory) -> Vec<&EnemyPlayer> {
+    memory
+        .enemy_players
+        .values()
+        .filter(|enemy| enemy.hate >= NUKE_MIN_HATE && !allies::is_ally(&enemy.username, None))
+        .collect()
+}
+
+pub fn nuker_ready(nuker: &screeps::StructureNuker) -> bool {
+    nuker.cooldown() == 0
+        && nuker.store().get_free_capacity(Some(ResourceType::Energy)) == 0
+        && nuker.store().get_used_capacity(Some(ResourceType::Ghodium)) >= NUKER_GHODIUM_CAPACITY
+}
+
+// Try every known structure as ground zero, and take whatever hurts the most.
+// Anything inside the blast of a nuke we already have on the way is as good as gone, so it doesnt count again.
+pub fn best_impact_point(structures: &[ScoutedStructure], already_targeted: &[RoomXY]) -> Option<(RoomXY, u32)> {
+    let remaining = structures
+        .iter()
+        .filter(|structure| !already_targeted.iter().any(|pos| structure.pos.get_range_to(*pos) <= NUKE_BLAST_RANGE))
+        .collect::<Vec<_>>();
+
+    remaining
+        .iter()
+        .map(|center| center.pos)
+        .map(|pos| {
+            let score = remaining
+                .iter()
+                .filter(|structure| structure.pos.get_range_to(pos) <= NUKE_BLAST_RANGE)
+                .map(|structure| {
+                    let weight = nuke_structure_weight(structure.structure_type);
+
+                    if structure.pos == pos { weight * 2 } else { weight }
+                })
+                .sum::<u32>();
+
+            (pos, score)
+        })
+        .max_by_key(|(_, score)| *score)
+}
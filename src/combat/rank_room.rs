@@ -1,7 +1,8 @@
-use screeps::{find, game, HasPosition, OwnedStructureProperties, Position, Room, RoomXY, StructureObject};
+use screeps::{find, game, HasPosition, OwnedStructureProperties, PortalDestination, Position, Room, RoomXY, StructureObject, StructureProperties};
 
 use crate::{
-    memory::{EnemyPlayer, ScoutedRoom, ScoutedSource, ScreepsMemory}, room::cache::CachedRoom, traits::position::PositionExtensions, utils
+    constants::NUKE_WORTHY_STRUCTURES,
+    memory::{EnemyPlayer, ScoutedDeposit, ScoutedPortal, ScoutedPowerBank, ScoutedRoom, ScoutedSource, ScoutedStructure, ScreepsMemory}, room::cache::CachedRoom, traits::{position::PositionExtensions, room::RoomType}, utils
 };
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -67,9 +68,86 @@ pub fn scout_room(room: &Room, memory: &mut ScreepsMemory, cached_room: &mut Cac
 
     let xy = cached_room.structures.controller.as_ref().map(|controller| controller.pos().xy());
 
+    // Power banks only spawn in highways, no point looking anywhere else.
+    let room_type = utils::room_type(&room_name);
+    let power_bank = if room_type == RoomType::Highway || room_type == RoomType::Intersection {
+        room.find(find::STRUCTURES, None).into_iter().find_map(|structure| {
+            if let StructureObject::StructurePowerBank(bank) = structure {
+                Some(ScoutedPowerBank {
+                    pos: bank.pos().xy(),
+                    amount: bank.power(),
+                    hits: bank.hits(),
+                    decay_tick: game::time() + bank.ticks_to_decay(),
+                    free_spots: bank.pos().get_accessible_positions_around(1).len() as u8,
+                })
+            } else {
+                None
+            }
+        })
+    } else {
+        None
+    };
+
+    let deposits = if room_type == RoomType::Highway || room_type == RoomType::Intersection {
+        let deposits = room
+            .find(find::DEPOSITS, None)
+            .iter()
+            .map(|deposit| ScoutedDeposit {
+                pos: deposit.pos().xy(),
+                deposit_type: deposit.deposit_type(),
+                last_cooldown: deposit.last_cooldown(),
+                decay_tick: game::time() + deposit.ticks_to_decay(),
+                free_spots: deposit.pos().get_accessible_positions_around(1).len() as u8,
+            })
+            .collect::<Vec<_>>();
+
+        if deposits.is_empty() {
+            None
+        } else {
+            Some(deposits)
+        }
+    } else {
+        None
+    };
+
+    let structures = if owner.is_some() {
+        let structures = room
+            .find(find::HOSTILE_STRUCTURES, None)
+            .iter()
+            .filter(|structure| NUKE_WORTHY_STRUCTURES.contains(&structure.structure_type()))
+            .map(|structure| ScoutedStructure {
+                pos: structure.pos().xy(),
+                structure_type: structure.structure_type(),
+            })
+            .collect::<Vec<_>>();
+
+        Some(structures)
+    } else {
+        None
+    };
+
+    let portals = room
+        .find(find::STRUCTURES, None)
+        .into_iter()
+        .filter_map(|structure| {
+            if let StructureObject::StructurePortal(portal) = structure {
+                if let PortalDestination::InterShard(destination) = portal.destination() {
+                    return Some(ScoutedPortal {
+                        pos: portal.pos().xy(),
+                        destination_room: destination.room(),
+                        destination_shard: destination.shard(),
+                    });
+                }
+            }
+
+            None
+        })
+        .collect::<Vec<_>>();
+    let portals = if portals.is_empty() { None } else { Some(portals) };
+
     let scouted_room = ScoutedRoom {
         name: room_name,
-        room_type: utils::room_type(&room_name),
+        room_type,
         rcl: room_rcl,
         owner: owner.clone(),
         source_keepers: Some(keeper_lairs),
@@ -79,6 +157,10 @@ pub fn scout_room(room: &Room, memory: &mut ScreepsMemory, cached_room: &mut Cac
         sources: sources.clone(),
         controller: xy,
         mineral: mineral_id,
+        power_bank,
+        deposits,
+        structures,
+        portals,
         last_scouted: game::time(),
     };
 
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use log::info;
+use screeps::game;
+
+use crate::{
+    combat::{goals::deposit::deposit_profitable, setters::highway_responsible_room},
+    config::DEPOSIT_MAX_DISTANCE,
+    goal_memory::DepositGoal,
+    memory::ScreepsMemory,
+    room::cache::RoomCache,
+};
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn determine_deposit_needs(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    if game::time() % 10 != 0 {
+        return;
+    }
+
+    let candidates = memory
+        .scouted_rooms
+        .iter()
+        .filter(|(room_name, _)| !memory.goals.deposit.contains_key(room_name))
+        .filter_map(|(room_name, scouted)| Some((*room_name, scouted.deposits.clone()?)))
+        .filter(|(_, deposits)| !deposits.is_empty())
+        .collect::<Vec<_>>();
+
+    for (room_name, deposits) in candidates {
+        let Some((responsible_room, distance)) = highway_responsible_room(cache, &room_name, 7, DEPOSIT_MAX_DISTANCE, |room| {
+            memory.goals.deposit.values().any(|goal| goal.responsible_room == *room)
+        }) else {
+            continue;
+        };
+
+        // Go for the one thats been mined the least.
+        let deposit = deposits
+            .iter()
+            .filter(|d| d.decay_tick > game::time() + distance * 50 && d.free_spots > 0)
+            .filter(|d| deposit_profitable(d.last_cooldown, distance))
+            .min_by_key(|d| d.last_cooldown);
+
+        if let Some(deposit) = deposit {
+            info!(
+                "[DEPOSIT] Room {} is going after {:?} in {}, cooldown {}",
+                responsible_room, deposit.deposit_type, room_name, deposit.last_cooldown
+            );
+
+            let goal = DepositGoal {
+                deposit_room: room_name,
+                deposit_pos: deposit.pos,
+                deposit_type: deposit.deposit_type,
+                responsible_room,
+
+                last_cooldown: deposit.last_cooldown,
+                decay_tick: deposit.decay_tick,
+                free_spots: deposit.free_spots,
+
+                harvesters: Vec::new(),
+                haulers: Vec::new(),
+
+                requested: HashMap::new(),
+            };
+
+            memory.goals.deposit.insert(room_name, goal);
+        }
+    }
+}
@@ -1,4 +1,40 @@
+use screeps::RoomName;
+
+use crate::{room::cache::RoomCache, utils};
+
 pub mod remote_defense;
 pub mod remote_reservation;
 pub mod remote_invader_cleanup;
-pub mod room_claim;
\ No newline at end of file
+pub mod room_claim;
+pub mod power_bank;
+pub mod deposit;
+pub mod source_keeper;
+
+// Power banks and deposits both get run by the closest owned room, if its close enough
+// and has the storage to back it. Returns that room and how far off the target is.
+pub fn highway_responsible_room(
+    cache: &RoomCache,
+    target_room: &RoomName,
+    min_rcl: u8,
+    max_distance: u32,
+    busy: impl Fn(&RoomName) -> bool,
+) -> Option<(RoomName, u32)> {
+    let responsible_room = utils::find_closest_owned_room(target_room, cache, Some(min_rcl))?;
+
+    // One at a time per room, these eat a lot of spawn time.
+    if busy(&responsible_room) {
+        return None;
+    }
+
+    let distance = utils::calc_room_distance(target_room, &responsible_room, true) as u32;
+    if distance > max_distance {
+        return None;
+    }
+
+    let room_cache = cache.rooms.get(&responsible_room)?;
+    if utils::under_storage_gate(room_cache, 1.0) || room_cache.structures.storage.is_none() {
+        return None;
+    }
+
+    Some((responsible_room, distance))
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use log::info;
+use screeps::{game, ResourceType};
+
+use crate::{
+    combat::{
+        goals::power_bank::{estimate_break_ticks, pair_count},
+        setters::highway_responsible_room,
+    },
+    config::{POWER_BANK_MAX_DISTANCE, POWER_BANK_MIN_AMOUNT},
+    goal_memory::PowerBankGoal,
+    memory::ScreepsMemory,
+    room::cache::RoomCache,
+};
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn determine_power_bank_needs(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    if game::time() % 10 != 0 {
+        return;
+    }
+
+    let candidates = memory
+        .scouted_rooms
+        .iter()
+        .filter(|(room_name, _)| !memory.goals.power_bank.contains_key(room_name))
+        .filter_map(|(room_name, scouted)| Some((*room_name, scouted.power_bank.clone()?)))
+        .filter(|(_, bank)| bank.amount >= POWER_BANK_MIN_AMOUNT && bank.decay_tick > game::time() && bank.free_spots > 0)
+        .collect::<Vec<_>>();
+
+    for (room_name, bank) in candidates {
+        let Some((responsible_room, distance)) = highway_responsible_room(cache, &room_name, 8, POWER_BANK_MAX_DISTANCE, |room| {
+            memory.goals.power_bank.values().any(|g| g.responsible_room == *room)
+        }) else {
+            continue;
+        };
+
+        if cache
+            .rooms
+            .get(&responsible_room)
+            .and_then(|room_cache| room_cache.structures.storage.as_ref())
+            .map_or(0, |storage| storage.store().get_used_capacity(Some(ResourceType::Energy)))
+            < 100000
+        {
+            continue;
+        }
+
+        // Spawning, walking there and then chewing through it has to fit in what it has left.
+        let travel_ticks = distance * 50;
+        let spawn_ticks = pair_count(bank.free_spots) * 300;
+        let break_ticks = estimate_break_ticks(bank.hits, pair_count(bank.free_spots));
+        let ticks_left = bank.decay_tick - game::time();
+
+        if spawn_ticks + travel_ticks + break_ticks + 200 > ticks_left {
+            continue;
+        }
+
+        info!(
+            "[POWER BANK] Room {} is going after {} power in {}, {} ticks left",
+            responsible_room, bank.amount, room_name, ticks_left
+        );
+
+        let goal = PowerBankGoal {
+            bank_room: room_name,
+            bank_pos: bank.pos,
+            responsible_room,
+
+            amount: bank.amount,
+            hits: bank.hits,
+            decay_tick: bank.decay_tick,
+            free_spots: bank.free_spots,
+
+            pairs: Vec::new(),
+            haulers: Vec::new(),
+            haulers_spawned: false,
+            bank_broken: false,
+
+            requested: HashMap::new(),
+        };
+
+        memory.goals.power_bank.insert(room_name, goal);
+    }
+}
@@ -14,7 +14,7 @@ pub fn determine_remote_defense_needs(cache: &mut RoomCache, memory: &mut Screep
                 continue;
             }
 
-            let hostile_creeps = &remote_cache.creeps.enemy_creeps.iter().filter(|c| c.body().iter().any(|p| HOSTILE_PARTS.contains(&p.part())) && !allies::is_ally(&c.owner().username(), Some(*remote_name))).collect::<Vec<_>>();
+            let hostile_creeps = &remote_cache.creeps.enemy_creeps.iter().filter(|c| c.body().iter().any(|p| HOSTILE_PARTS.contains(&p.part())) && !allies::is_ally(&c.owner().username(), Some(*remote_name)) && c.owner().username() != constants::SOURCE_KEEPER_USERNAME).collect::<Vec<_>>();
 
             if hostile_creeps.is_empty() {
                 continue;
@@ -24,6 +24,11 @@ pub fn determine_reservations(memory: &mut ScreepsMemory, cache: &mut RoomCache)
         }
         let room = room.unwrap();
 
+        // Keeper rooms have nothing to reserve.
+        if room.controller().is_none() {
+            continue;
+        }
+
         // TODO: Make this spawn a dismantler, that way we can remove the wall
         // blocking it, and then claim it. I hate people that wall off controllers.
         let accessible_reservation_points = room
@@ -1,6 +1,11 @@
+use js_sys::JsString;
+use log::info;
 use screeps::{game, HasPosition, OwnedStructureProperties};
 
-use crate::{goal_memory::RoomClaimGoal, memory::ScreepsMemory, room::cache::RoomCache, traits::intents_tracking::FlagExtensionsTracking};
+use crate::{goal_memory::{ClaimTarget, RoomClaimGoal}, inter_shard, memory::ScreepsMemory, room::cache::RoomCache, traits::intents_tracking::FlagExtensionsTracking, utils};
+
+// Claimers only live 600 ticks, and still need to find a room on the other side.
+const SHARD_CLAIM_MAX_DISTANCE: i32 = 6;
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn determine_room_claim_needs(memory: &mut ScreepsMemory, _cache: &mut RoomCache) {
@@ -15,16 +20,73 @@ pub fn determine_room_claim_needs(memory: &mut ScreepsMemory, _cache: &mut RoomC
                 }
             }
 
-            if memory.goals.room_claim.contains_key(&room_name) {
+            if memory.goals.room_claim.contains_key(&ClaimTarget::local(room_name)) {
                 continue;
             }
 
             let goal = RoomClaimGoal {
                 claim_target: room_name,
                 creeps_assigned: Vec::new(),
+                target_shard: None,
+            };
+
+            memory.goals.room_claim.insert(ClaimTarget::local(room_name), goal);
+        }
+    }
+}
+// If a neighbouring shard has nothing of ours on it, send a claimer through a portal to start a colony.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn determine_shard_claim_needs(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    if game::time() % 100 != 0 || !memory.goals.room_claim.is_empty() || memory.expansion.is_some() {
+        return;
+    }
+
+    // GCL is shared between shards, so count everything we own.
+    let mut total_rooms = memory.rooms.len();
+    let mut empty_shards = Vec::new();
+
+    for shard in inter_shard::neighbour_shards(memory) {
+        let shard_rooms = inter_shard::read_remote(&shard).map_or(0, |data| data.rooms.len());
+        total_rooms += shard_rooms;
+
+        // No point in a colony on a shard we dont give any CPU to.
+        let has_cpu = game::cpu::shard_limits().get(JsString::from(shard.as_str())).is_some_and(|limit| limit > 0);
+
+        if shard_rooms == 0 && has_cpu {
+            empty_shards.push(shard);
+        }
+    }
+
+    if total_rooms >= game::gcl::level() as usize {
+        return;
+    }
+
+    for shard in empty_shards {
+        let portal = inter_shard::portals_to(memory, &shard)
+            .into_iter()
+            .filter_map(|(pos, destination)| {
+                let responsible_room = utils::find_closest_owned_room(&pos.room_name(), cache, Some(4))?;
+                let distance = utils::calc_room_distance(&pos.room_name(), &responsible_room, true);
+
+                if distance > SHARD_CLAIM_MAX_DISTANCE {
+                    return None;
+                }
+
+                Some((destination, distance))
+            })
+            .min_by_key(|(_, distance)| *distance);
+
+        if let Some((destination, _)) = portal {
+            info!("[INTER SHARD] Starting a colony on {}, heading through the portal to {}", shard, destination);
+
+            let goal = RoomClaimGoal {
+                claim_target: destination,
+                creeps_assigned: Vec::new(),
+                target_shard: Some(shard.clone()),
             };
 
-            memory.goals.room_claim.insert(room_name, goal);
+            memory.goals.room_claim.insert(ClaimTarget::on_shard(shard, destination), goal);
+            return;
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+use log::info;
+
+use crate::{
+    goal_memory::SourceKeeperGoal,
+    memory::ScreepsMemory,
+    room::{cache::RoomCache, source_keepers::is_keeper_room},
+};
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn determine_source_keeper_needs(memory: &mut ScreepsMemory, _cache: &mut RoomCache) {
+    for remote in memory.remote_rooms.values() {
+        if memory.goals.source_keeper.contains_key(&remote.name) || !is_keeper_room(&remote.name) {
+            continue;
+        }
+
+        info!("[SOURCE KEEPERS] {} is mining keeper room {}, sending a killer", remote.owner, remote.name);
+
+        let goal = SourceKeeperGoal {
+            keeper_room: remote.name,
+            responsible_room: remote.owner,
+            creeps_assigned: Vec::new(),
+            requested: HashMap::new(),
+        };
+
+        memory.goals.source_keeper.insert(remote.name, goal);
+    }
+}
@@ -16,6 +16,91 @@ pub const MIN_CLAIM_DISTANCE: u32 = 2;
 pub const RESERVATION_GOAL_THRESHOLD: u32 = 4000;
 pub const ROOM_ENERGY_STOCKPILE: u32 = 20000;
 
+// Global spawn requests go to rooms at most this far from where the creep is going.
+pub const GLOBAL_SPAWN_MAX_DISTANCE: u32 = 10;
+// How many rooms further we would rather go than wait on a room to fill up.
+pub const GLOBAL_SPAWN_WAIT_PENALTY: u32 = 3;
+
+// Only creeps this expensive are worth walking over to a spawn to renew.
+pub const RENEW_MIN_BODY_COST: u32 = 1500;
+// Creeps start heading to the spawn under this TTL, if they are close enough.
+pub const RENEW_TTL_THRESHOLD: u32 = 300;
+pub const RENEW_MAX_RANGE: u32 = 15;
+
+// CPU a room gets to score its haulers against its orders, past this the rest wait a tick.
+pub const HAULER_MATCHING_CPU_BUDGET: f64 = 3.0;
+// Rough size of the assignment solve (haulers squared times slots), past this haulers get matched greedily.
+pub const HAULER_ASSIGNMENT_MAX_WORK: usize = 250_000;
+// Haul tasks chain on orders within this range of the last stop, up to this many stops in total.
+// Pickups take this as how far out of their way home they go instead, so they can chain across rooms.
+pub const HAUL_ROUTE_STOP_RANGE: u32 = 8;
+pub const HAUL_ROUTE_MAX_STOPS: usize = 4;
+
+// How far outside the rampart perimeter has to stay from what it protects.
+// Three keeps ranged attackers off the base, the controller and sources just need their workers covered.
+pub const RAMPART_BASE_MARGIN: u8 = 3;
+pub const RAMPART_CONTROLLER_MARGIN: u8 = 1;
+pub const RAMPART_SOURCE_MARGIN: u8 = 1;
+
+// While there are lost structures to put back, builders spawn ahead of most things, and at least this big.
+pub const REBUILD_BUILDER_PRIORITY: f64 = 12.0;
+pub const REBUILD_MIN_WORK_PARTS: f32 = 6.0;
+
+// Power banks smaller than this, or further than this many rooms, arent worth the trip.
+pub const POWER_BANK_MIN_AMOUNT: u32 = 2000;
+pub const POWER_BANK_MAX_DISTANCE: u32 = 5;
+// Goals name their creeps up front, a name that still hasnt spawned after this long gets dropped.
+pub const GOAL_SPAWN_TIMEOUT: u32 = 500;
+
+// Deposits get slower the more you mine them, past this cooldown we give up.
+pub const DEPOSIT_MAX_COOLDOWN: u32 = 100;
+pub const DEPOSIT_MAX_DISTANCE: u32 = 4;
+
+// Keeper rooms need a killer on top of the usual remote creeps, so wait until we can afford it.
+pub const SK_MINING_RCL: u8 = 7;
+pub const SK_REMOTES_PER_ROOM: usize = 1;
+
+// How much of each compound we want stocked before moving on to the next.
+pub const LAB_COMPOUND_TARGET: u32 = 3000;
+// How much of each reagent we need to bother starting a reaction.
+pub const LAB_REAGENT_MINIMUM: u32 = 1000;
+
+// How many bars, batteries and commodities we want stocked.
+pub const FACTORY_PRODUCT_TARGET: u32 = 5000;
+// Energy in storage over this gets compressed into batteries.
+pub const FACTORY_ENERGY_SURPLUS: u32 = 150000;
+// Minerals over this get compressed into bars.
+pub const FACTORY_MINERAL_SURPLUS: u32 = 30000;
+
+// How often we look at the market, grabbing every order isnt cheap.
+pub const MARKET_INTERVAL: u32 = 100;
+pub const MARKET_MAX_DEAL_AMOUNT: u32 = 10000;
+// Stock over these gets sold off.
+pub const MARKET_SELL_ENERGY_THRESHOLD: u32 = 400000;
+pub const MARKET_SELL_MINERAL_THRESHOLD: u32 = 60000;
+// Dont sell for less than this fraction of the average price, after energy costs.
+pub const MARKET_SELL_PRICE_FLOOR: f64 = 0.8;
+// What we top reagents back up to when buying.
+pub const MARKET_BUY_TARGET: u32 = 3000;
+// Dont pay more than this much over the average, or this much flat.
+pub const MARKET_BUY_PRICE_MARKUP: f64 = 1.2;
+pub const MARKET_MAX_BUY_PRICE: f64 = 50.0;
+// Keep a cushion of credits around, we dont want to spend it all on minerals.
+pub const MARKET_MIN_CREDITS: f64 = 10000.0;
+
+// Rooms ask the terminal network for energy below the first, and offer it above the second.
+pub const TERMINAL_ENERGY_NEED: u32 = 50000;
+pub const TERMINAL_ENERGY_OFFER: u32 = 150000;
+// Same for base minerals, but only rooms running labs ask for them.
+pub const TERMINAL_MINERAL_NEED: u32 = 3000;
+pub const TERMINAL_MINERAL_OFFER: u32 = 10000;
+// Lab made stuff, labs stop at LAB_COMPOUND_TARGET so the offer has to sit under that or nothing ever moves.
+pub const TERMINAL_COMPOUND_NEED: u32 = 1000;
+pub const TERMINAL_COMPOUND_OFFER: u32 = 2000;
+pub const TERMINAL_MAX_SEND: u32 = 10000;
+// How much of an offered resource we keep loaded in the terminal.
+pub const TERMINAL_STAGE_AMOUNT: u32 = 10000;
+
 pub fn REMOTES_FOR_RCL(room_cache: &CachedRoom) -> u8 {
     if utils::under_storage_gate(room_cache, 1.0) && room_cache.rcl >= 6 {
         return 7;
@@ -95,6 +180,11 @@ pub const HATE_CREEP_HEAL_WEIGHT: f32 = -0.5;
 pub const HATE_DECAY_PERCENTEAGE: f32 = 0.99999;
 pub const TICKS_BEFORE_DECAY: u32 = 500;
 
+// How much someone has to annoy us before they get nuked.
+pub const NUKE_MIN_HATE: f32 = 500.0;
+// Scouting older than this is too stale to aim a nuke with, observers get those rooms looked at first.
+pub const NUKE_MAX_SCOUT_AGE: u32 = 1000;
+
 pub const CREEP_SONG: [&str; 16] = [
     "Days", "never", "finished",
     "mastas", "got", "me", "workin",
@@ -2,7 +2,7 @@
 use std::sync::OnceLock;
 
 use enum_map::{enum_map, Enum, EnumMap};
-use screeps::{Part, ResourceType};
+use screeps::{Part, ResourceType, StructureType};
 
 // navigator.clipboard.writeText(`$TO_COPY`);
 // Hmmm, I want to optimize this so it automatically works.
@@ -22,6 +22,7 @@ pub const WALKABLE_STRUCTURES: [screeps::StructureType; 3] = [
 ];
 
 pub const INVADER_USERNAME: &str = "Invader";
+pub const SOURCE_KEEPER_USERNAME: &str = "Source Keeper";
 pub const MMO_SHARD_NAMES: [&str; 4] = ["shard0", "shard1", "shard2", "shard3"];
 pub const MAX_BUCKET: i32 = 10000;
 
@@ -102,6 +103,176 @@ pub const ITERABLE_RESOURCES: [ResourceType; 57] = [
     ResourceType::Battery,
 ];
 
+pub const LINK_CAPACITY: u32 = 800;
+pub const LINK_LOSS_RATIO: f32 = 0.03;
+pub const NUKER_RANGE: u32 = 10;
+pub const OBSERVER_RANGE: u32 = 10;
+pub const NUKE_LAND_TIME: u32 = 50000;
+pub const NUKER_GHODIUM_CAPACITY: u32 = 5000;
+
+// What we care about in enemy rooms, and how much we want it gone.
+pub const NUKE_WORTHY_STRUCTURES: [StructureType; 8] = [
+    StructureType::Spawn,
+    StructureType::Storage,
+    StructureType::Terminal,
+    StructureType::Tower,
+    StructureType::Lab,
+    StructureType::Factory,
+    StructureType::PowerSpawn,
+    StructureType::Nuker,
+];
+
+pub fn nuke_structure_weight(structure_type: StructureType) -> u32 {
+    match structure_type {
+        StructureType::Spawn => 10,
+        StructureType::Storage => 8,
+        StructureType::Terminal => 8,
+        StructureType::Tower => 6,
+        StructureType::Factory => 5,
+        StructureType::PowerSpawn => 5,
+        StructureType::Nuker => 5,
+        StructureType::Lab => 3,
+        _ => 0,
+    }
+}
+
+pub const BASE_MINERALS: [ResourceType; 7] = [
+    ResourceType::Hydrogen,
+    ResourceType::Oxygen,
+    ResourceType::Utrium,
+    ResourceType::Keanium,
+    ResourceType::Lemergium,
+    ResourceType::Zynthium,
+    ResourceType::Catalyst,
+];
+
+// The order we want to make compounds in, first one we have
+// the reagents for (and need more of) gets made.
+pub const LAB_REACTION_PRIORITY: [ResourceType; 28] = [
+    ResourceType::CatalyzedGhodiumAlkalide,
+    ResourceType::CatalyzedLemergiumAlkalide,
+    ResourceType::CatalyzedZynthiumAlkalide,
+    ResourceType::CatalyzedKeaniumAlkalide,
+    ResourceType::CatalyzedUtriumAcid,
+    ResourceType::CatalyzedZynthiumAcid,
+    ResourceType::CatalyzedGhodiumAcid,
+
+    ResourceType::GhodiumAlkalide,
+    ResourceType::LemergiumAlkalide,
+    ResourceType::ZynthiumAlkalide,
+    ResourceType::KeaniumAlkalide,
+    ResourceType::UtriumAcid,
+    ResourceType::ZynthiumAcid,
+    ResourceType::GhodiumAcid,
+
+    ResourceType::GhodiumOxide,
+    ResourceType::LemergiumOxide,
+    ResourceType::ZynthiumOxide,
+    ResourceType::KeaniumOxide,
+    ResourceType::UtriumHydride,
+    ResourceType::ZynthiumHydride,
+    ResourceType::GhodiumHydride,
+    ResourceType::LemergiumHydride,
+    ResourceType::UtriumOxide,
+    ResourceType::KeaniumHydride,
+
+    ResourceType::Ghodium,
+    ResourceType::ZynthiumKeanite,
+    ResourceType::UtriumLemergite,
+    ResourceType::Hydroxide,
+];
+
+// The compounds that boost a part, best first.
+// Work is the upgrade boost, nothing else we boost uses work.
+pub fn boost_compounds_for_part(part: Part) -> &'static [ResourceType] {
+    match part {
+        Part::Attack => &[
+            ResourceType::CatalyzedUtriumAcid,
+            ResourceType::UtriumAcid,
+            ResourceType::UtriumHydride,
+        ],
+        Part::RangedAttack => &[
+            ResourceType::CatalyzedKeaniumAlkalide,
+            ResourceType::KeaniumAlkalide,
+            ResourceType::KeaniumOxide,
+        ],
+        Part::Heal => &[
+            ResourceType::CatalyzedLemergiumAlkalide,
+            ResourceType::LemergiumAlkalide,
+            ResourceType::LemergiumOxide,
+        ],
+        Part::Move => &[
+            ResourceType::CatalyzedZynthiumAlkalide,
+            ResourceType::ZynthiumAlkalide,
+            ResourceType::ZynthiumOxide,
+        ],
+        Part::Tough => &[
+            ResourceType::CatalyzedGhodiumAlkalide,
+            ResourceType::GhodiumAlkalide,
+            ResourceType::GhodiumOxide,
+        ],
+        Part::Work => &[
+            ResourceType::CatalyzedGhodiumAcid,
+            ResourceType::GhodiumAcid,
+            ResourceType::GhodiumHydride,
+        ],
+        _ => &[],
+    }
+}
+
+// The level 0 factory recipes we bother with: compression, decompression
+// and the first tier of commodities. (amount produced, components)
+pub fn factory_recipe(product: ResourceType) -> Option<(u32, &'static [(ResourceType, u32)])> {
+    let recipe: (u32, &'static [(ResourceType, u32)]) = match product {
+        ResourceType::Battery => (50, &[(ResourceType::Energy, 600)]),
+        ResourceType::UtriumBar => (100, &[(ResourceType::Utrium, 500), (ResourceType::Energy, 200)]),
+        ResourceType::LemergiumBar => (100, &[(ResourceType::Lemergium, 500), (ResourceType::Energy, 200)]),
+        ResourceType::ZynthiumBar => (100, &[(ResourceType::Zynthium, 500), (ResourceType::Energy, 200)]),
+        ResourceType::KeaniumBar => (100, &[(ResourceType::Keanium, 500), (ResourceType::Energy, 200)]),
+        ResourceType::GhodiumMelt => (100, &[(ResourceType::Ghodium, 500), (ResourceType::Energy, 200)]),
+        ResourceType::Oxidant => (100, &[(ResourceType::Oxygen, 500), (ResourceType::Energy, 200)]),
+        ResourceType::Reductant => (100, &[(ResourceType::Hydrogen, 500), (ResourceType::Energy, 200)]),
+        ResourceType::Purifier => (100, &[(ResourceType::Catalyst, 500), (ResourceType::Energy, 200)]),
+
+        ResourceType::Energy => (500, &[(ResourceType::Battery, 50)]),
+        ResourceType::Utrium => (500, &[(ResourceType::UtriumBar, 100), (ResourceType::Energy, 200)]),
+        ResourceType::Lemergium => (500, &[(ResourceType::LemergiumBar, 100), (ResourceType::Energy, 200)]),
+        ResourceType::Zynthium => (500, &[(ResourceType::ZynthiumBar, 100), (ResourceType::Energy, 200)]),
+        ResourceType::Keanium => (500, &[(ResourceType::KeaniumBar, 100), (ResourceType::Energy, 200)]),
+        ResourceType::Ghodium => (500, &[(ResourceType::GhodiumMelt, 100), (ResourceType::Energy, 200)]),
+        ResourceType::Oxygen => (500, &[(ResourceType::Oxidant, 100), (ResourceType::Energy, 200)]),
+        ResourceType::Hydrogen => (500, &[(ResourceType::Reductant, 100), (ResourceType::Energy, 200)]),
+        ResourceType::Catalyst => (500, &[(ResourceType::Purifier, 100), (ResourceType::Energy, 200)]),
+
+        ResourceType::Wire => (20, &[(ResourceType::UtriumBar, 20), (ResourceType::Silicon, 100), (ResourceType::Energy, 40)]),
+        ResourceType::Cell => (20, &[(ResourceType::LemergiumBar, 20), (ResourceType::Biomass, 100), (ResourceType::Energy, 40)]),
+        ResourceType::Alloy => (20, &[(ResourceType::ZynthiumBar, 20), (ResourceType::Metal, 100), (ResourceType::Energy, 40)]),
+        ResourceType::Condensate => (20, &[(ResourceType::KeaniumBar, 20), (ResourceType::Mist, 100), (ResourceType::Energy, 40)]),
+        _ => return None,
+    };
+
+    Some(recipe)
+}
+
+// Minerals we compress into bars when we have too much of them.
+pub const FACTORY_COMPRESSIONS: [(ResourceType, ResourceType); 8] = [
+    (ResourceType::Utrium, ResourceType::UtriumBar),
+    (ResourceType::Lemergium, ResourceType::LemergiumBar),
+    (ResourceType::Zynthium, ResourceType::ZynthiumBar),
+    (ResourceType::Keanium, ResourceType::KeaniumBar),
+    (ResourceType::Ghodium, ResourceType::GhodiumMelt),
+    (ResourceType::Oxygen, ResourceType::Oxidant),
+    (ResourceType::Hydrogen, ResourceType::Reductant),
+    (ResourceType::Catalyst, ResourceType::Purifier),
+];
+
+pub const FACTORY_COMMODITIES: [ResourceType; 4] = [
+    ResourceType::Wire,
+    ResourceType::Cell,
+    ResourceType::Alloy,
+    ResourceType::Condensate,
+];
+
 pub const PATHFINDER_MAX_ROOMS: u32 = 64;
 
 pub const WORLD_SIZE: u8 = 255;
@@ -118,6 +289,7 @@ pub static HOSTILE_PARTS: [Part; 4] = [Part::Attack, Part::RangedAttack, Part::H
 
 pub static CLAIM_LIFETIME: u32 = 600;
 pub static CREEP_LIFETIME: u32 = 1500;
+pub static CREEP_SPAWN_TIME: u32 = 3;
 
 pub fn part_attack_weight(part: &Part) -> u32 {
     match part {
@@ -1,4 +1,4 @@
-use screeps::{game, HasPosition};
+use screeps::{game, HasPosition, SharedCreepProperties};
 
 use crate::{memory::ScreepsMemory, movement::move_target::MoveOptions, room::cache::RoomCache};
 
@@ -35,6 +35,11 @@ pub fn run_duos(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
             }
         }
 
+        // Let them get boosted before we start dragging them around.
+        let boosting = gcreeps.iter().any(|c| memory.creeps.get(&c.name()).map_or(false, |m| m.boosts.is_some()));
+        if boosting {
+            continue;
+        }
 
         let dest = game::flags().get("duoPoint".to_string()).unwrap().pos();
         let range = 1;
@@ -1,12 +1,63 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
-use screeps::{Part, RoomName};
-use serde::{Deserialize, Serialize};
+use screeps::{Part, ResourceType, RoomName, RoomXY};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// Room names repeat across shards, so claims are keyed by both. None is this shard.
+// Stored as "shard/room", or just the room for this shard, so older memory still loads.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClaimTarget {
+    pub shard: Option<String>,
+    pub room: RoomName,
+}
+
+impl ClaimTarget {
+    pub fn local(room: RoomName) -> Self {
+        Self { shard: None, room }
+    }
+
+    pub fn on_shard(shard: String, room: RoomName) -> Self {
+        Self { shard: Some(shard), room }
+    }
+}
+
+impl fmt::Display for ClaimTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.shard {
+            Some(shard) => write!(f, "{}/{}", shard, self.room),
+            None => write!(f, "{}", self.room),
+        }
+    }
+}
+
+impl Serialize for ClaimTarget {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ClaimTarget {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let key = String::deserialize(deserializer)?;
+
+        let (shard, room) = match key.split_once('/') {
+            Some((shard, room)) => (Some(shard.to_string()), room),
+            None => (None, key.as_str()),
+        };
+
+        let room = RoomName::new(room).map_err(serde::de::Error::custom)?;
+
+        Ok(Self { shard, room })
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RoomClaimGoal {
     pub claim_target: RoomName,
     pub creeps_assigned: Vec<String>,
+    // Set if the room is on another shard, the claim target is then where the portal drops us off.
+    #[serde(default)]
+    pub target_shard: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -37,6 +88,58 @@ pub struct RemoteInvaderCleanup {
     pub destroyed_core: bool,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PowerBankGoal {
+    pub bank_room: RoomName,
+    pub bank_pos: RoomXY,
+    pub responsible_room: RoomName,
+
+    pub amount: u32,
+    pub hits: u32,
+    pub decay_tick: u32,
+    pub free_spots: u8,
+
+    // Attacker, healer.
+    pub pairs: Vec<(String, String)>,
+    pub haulers: Vec<String>,
+    pub haulers_spawned: bool,
+    pub bank_broken: bool,
+
+    // Names we asked to spawn, and when we first asked.
+    #[serde(default)]
+    pub requested: HashMap<String, u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DepositGoal {
+    pub deposit_room: RoomName,
+    pub deposit_pos: RoomXY,
+    pub deposit_type: ResourceType,
+    pub responsible_room: RoomName,
+
+    pub last_cooldown: u32,
+    pub decay_tick: u32,
+    pub free_spots: u8,
+
+    pub harvesters: Vec<String>,
+    pub haulers: Vec<String>,
+
+    // Names we asked to spawn, and when we first asked.
+    #[serde(default)]
+    pub requested: HashMap<String, u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SourceKeeperGoal {
+    pub keeper_room: RoomName,
+    pub responsible_room: RoomName,
+    pub creeps_assigned: Vec<String>,
+
+    // Names we asked to spawn, and when we first asked.
+    #[serde(default)]
+    pub requested: HashMap<String, u32>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AttackingCreep {
     pub creep_name: String,
@@ -49,10 +152,17 @@ pub struct AttackingCreep {
 structstruck::strike! {
     #[strikethrough[derive(Serialize, Deserialize, Debug, Clone, Default)]]
     pub struct GoalMemory {
-        pub room_claim: HashMap<RoomName, RoomClaimGoal>,
+        pub room_claim: HashMap<ClaimTarget, RoomClaimGoal>,
         pub room_reservation: HashMap<RoomName, RoomReservationGoal>,
 
         pub remote_defense: HashMap<RoomName, RemoteDefenseGoal>,
         pub remote_invader_cleanup: HashMap<RoomName, RemoteInvaderCleanup>,
+
+        #[serde(default)]
+        pub power_bank: HashMap<RoomName, PowerBankGoal>,
+        #[serde(default)]
+        pub deposit: HashMap<RoomName, DepositGoal>,
+        #[serde(default)]
+        pub source_keeper: HashMap<RoomName, SourceKeeperGoal>,
     }
 }
\ No newline at end of file
@@ -1,23 +1,57 @@
 use std::collections::HashMap;
 
-use screeps::{game, HasPosition, Position, RawObjectId};
+use screeps::{game, HasPosition, Position, RawObjectId, ResourceType};
 
 #[derive(Debug, Clone)]
 pub struct HeapHaulingReservation {
     pub target_id: RawObjectId,
+    pub resource: ResourceType,
     pub creeps_assigned: Vec<String>,
     pub order_amount: i32,
     pub reserved_amount: i32,
+    // What each assigned creep holds, so dropping one gives back exactly its share.
+    pub reserved_by: HashMap<String, i32>,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct HeapHaulingCache {
-    pub reserved_orders: HashMap<RawObjectId, HeapHaulingReservation>
+    // Keyed by resource too, so one hauler taking energy from a terminal doesnt hide the orders for whats next to it.
+    pub reserved_orders: HashMap<(RawObjectId, ResourceType), HeapHaulingReservation>
 
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl HeapHaulingReservation {
+    pub fn new(target_id: RawObjectId, resource: ResourceType, order_amount: i32) -> Self {
+        HeapHaulingReservation {
+            target_id,
+            resource,
+            creeps_assigned: Vec::new(),
+            order_amount,
+            reserved_amount: 0,
+            reserved_by: HashMap::new(),
+        }
+    }
+
+    pub fn reserve(&mut self, creep_name: String, amount: i32) {
+        self.reserved_amount += amount;
+        *self.reserved_by.entry(creep_name.clone()).or_insert(0) += amount;
+
+        if !self.creeps_assigned.contains(&creep_name) {
+            self.creeps_assigned.push(creep_name);
+        }
+    }
+
+    // Takes the creeps share back off the order, returns how much that was.
+    pub fn release(&mut self, creep_name: &str) -> i32 {
+        let amount = self.reserved_by.remove(creep_name).unwrap_or(0);
+
+        self.reserved_amount -= amount;
+        self.creeps_assigned.retain(|name| name != creep_name);
+
+        amount
+    }
+
     pub fn get_target_position(&self) -> Option<Position> {
         let target = game::get_object_by_id_erased(&self.target_id);
 
@@ -1,6 +1,20 @@
-use screeps::{ObjectId, Source};
+use std::collections::HashSet;
+
+use screeps::{ObjectId, RoomXY, Source, StructureType};
 
 #[derive(Debug, Clone, Default)]
 pub struct HeapRoom {
     pub sources: Vec<ObjectId<Source>>,
-}
\ No newline at end of file
+
+    // Structure hash the rebuild queue was last checked against.
+    pub structure_hash: u64,
+    // Set once the queue has been picked back up from memory after a reset.
+    pub rebuild_loaded: bool,
+    // Planned structures we have seen standing, so a loss can be told apart from something not built yet.
+    pub built: HashSet<(RoomXY, StructureType)>,
+    // Lost structures waiting to be put back, most important first. Mirrors RoomMemory.rebuild_queue.
+    pub rebuild_queue: Vec<(RoomXY, StructureType)>,
+
+    // Credits from market deals, picked up by the next stats pass since the market runs after this ticks.
+    pub trade_credits: f64,
+}
@@ -6,9 +6,9 @@ use crate::compression::compressed_matrix::CompressedMatrix;
 use hauling::HeapHaulingCache;
 use heap_creep::HeapCreep;
 use heap_room::HeapRoom;
-use screeps::{game, Position, RoomName};
+use screeps::{game, Position, ResourceType, RoomName};
 
-use crate::memory::ScreepsMemory;
+use crate::{inter_shard::InterShardData, memory::ScreepsMemory};
 
 pub mod heap_creep;
 pub mod hauling;
@@ -67,6 +67,11 @@ pub struct GlobalHeapCache {
     pub cachable_positions: Mutex<HashMap<RoomName, Vec<Position>>>,
     pub needs_cachable_position_generation: Mutex<Vec<RoomName>>,
 
+    // Average market price, and the tick we last looked it up.
+    pub market_prices: Mutex<HashMap<ResourceType, (u32, Option<f64>)>>,
+    // Other shards InterShardMemory, parsed at most once a tick.
+    pub remote_shards: Mutex<HashMap<String, (u32, Option<InterShardData>)>>,
+
     pub creep_say: Mutex<bool>,
     pub heap_lifetime: Mutex<u32>,
     pub unique_id: Mutex<u128>,
@@ -90,6 +95,9 @@ impl GlobalHeapCache {
             cachable_positions: Mutex::new(HashMap::new()),
             needs_cachable_position_generation: Mutex::new(Vec::new()),
 
+            market_prices: Mutex::new(HashMap::new()),
+            remote_shards: Mutex::new(HashMap::new()),
+
             creep_say: Mutex::new(true),
             heap_lifetime: Mutex::new(0),
             unique_id: Mutex::new(game::time() as u128),
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+
+use js_sys::JsString;
+use log::info;
+use screeps::{game, inter_shard_memory, Creep, HasPosition, Position, RoomName, SharedCreepProperties, StructureType};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    combat::goals::room_claim::place_spawn_site,
+    goal_memory::ClaimTarget,
+    constants::{CREEP_LIFETIME, MMO_SHARD_NAMES},
+    heap,
+    memory::{CreepMemory, ScreepsMemory},
+    movement::move_target::MoveOptions,
+    room::cache::RoomCache,
+    traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking, position::RoomXYExtensions},
+};
+
+// What each shard tells the others, stored in its local InterShardMemory.
+structstruck::strike! {
+    #[strikethrough[derive(Serialize, Deserialize, Debug, Clone)]]
+    #[derive(Default)]
+    pub struct InterShardData {
+        #[serde(default)]
+        pub rooms: Vec<pub struct ShardRoom {
+            pub name: RoomName,
+            pub rcl: u8,
+            pub has_spawn: bool,
+        }>,
+        // Creeps on their way through a portal, keyed by name.
+        #[serde(default)]
+        pub creeps: HashMap<String, pub struct InterShardCreep {
+            pub memory: CreepMemory,
+            // On the sending shards clock, only used to clear it out.
+            pub sent_tick: u32,
+        }>,
+    }
+}
+
+pub fn read_local() -> InterShardData {
+    inter_shard_memory::get_local()
+        .and_then(|data| serde_json::from_str(&String::from(data)).ok())
+        .unwrap_or_default()
+}
+
+// Other shards only change their side between our ticks, so parse each one once a tick at most.
+pub fn read_remote(shard: &str) -> Option<InterShardData> {
+    let mut remote_shards = heap().remote_shards.lock().unwrap();
+
+    if let Some((tick, data)) = remote_shards.get(shard) {
+        if *tick == game::time() {
+            return data.clone();
+        }
+    }
+
+    let data = inter_shard_memory::get_remote(&JsString::from(shard)).and_then(|data| serde_json::from_str(&String::from(data)).ok());
+    remote_shards.insert(shard.to_string(), (game::time(), data.clone()));
+
+    data
+}
+
+// Setting it isnt free, so skip it if nothing changed.
+fn write_local(data: &InterShardData) {
+    if let Ok(serialized) = serde_json::to_string(data) {
+        if inter_shard_memory::get_local().is_some_and(|current| String::from(current) == serialized) {
+            return;
+        }
+
+        inter_shard_memory::set_local(&JsString::from(serialized));
+    }
+}
+
+// Every shard we know a portal to, plus the MMO shards, since a fresh shard wont know any portals yet.
+pub fn neighbour_shards(memory: &ScreepsMemory) -> Vec<String> {
+    let current_shard = game::shard::name();
+
+    let mut shards = MMO_SHARD_NAMES.iter().map(|shard| shard.to_string()).collect::<Vec<_>>();
+
+    for scouted in memory.scouted_rooms.values() {
+        for portal in scouted.portals.iter().flatten() {
+            if !shards.contains(&portal.destination_shard) {
+                shards.push(portal.destination_shard.clone());
+            }
+        }
+    }
+
+    shards.retain(|shard| *shard != current_shard);
+    shards
+}
+
+// Portals we have scouted that lead to the shard, as (portal position, destination room).
+pub fn portals_to(memory: &ScreepsMemory, shard: &str) -> Vec<(Position, RoomName)> {
+    memory
+        .scouted_rooms
+        .iter()
+        .flat_map(|(room_name, scouted)| {
+            scouted
+                .portals
+                .iter()
+                .flatten()
+                .filter(|portal| portal.destination_shard == shard)
+                .map(|portal| (portal.pos.as_position(room_name), portal.destination_room))
+        })
+        .collect()
+}
+
+// Give memory to creeps that just came through a portal.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn receive_creeps(memory: &mut ScreepsMemory) {
+    let arrivals = game::creeps()
+        .keys()
+        .filter(|name| !memory.creeps.contains_key(name))
+        .collect::<Vec<_>>();
+
+    if arrivals.is_empty() {
+        return;
+    }
+
+    for shard in neighbour_shards(memory) {
+        let Some(remote) = read_remote(&shard) else {
+            continue;
+        };
+
+        for name in &arrivals {
+            let Some(handoff) = remote.creeps.get(name) else {
+                continue;
+            };
+
+            let Some(creep) = game::creeps().get(name.to_string()) else {
+                continue;
+            };
+
+            let current_room = creep.room().unwrap().name();
+
+            // Our old home doesnt exist on this shard, so this is the only room we know is visible.
+            let mut creep_memory = handoff.memory.clone();
+            creep_memory.owning_room = current_room;
+            creep_memory.target_shard = None;
+            creep_memory.path = None;
+            creep_memory.hauling_task = None;
+
+            info!("[INTER SHARD] {} arrived from {} in {}", name, shard, current_room);
+
+            memory.create_creep(&current_room, name, creep_memory);
+        }
+    }
+}
+
+// Tell the other shards what we own, and clear out creeps that have long since arrived.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn publish_shard_data(memory: &ScreepsMemory, cache: &RoomCache) {
+    if game::time() % 10 != 0 {
+        return;
+    }
+
+    let mut data = read_local();
+
+    data.rooms = memory
+        .rooms
+        .keys()
+        .filter_map(|room_name| {
+            let room_cache = cache.rooms.get(room_name)?;
+
+            Some(ShardRoom {
+                name: *room_name,
+                rcl: room_cache.rcl,
+                has_spawn: !room_cache.structures.spawns.is_empty(),
+            })
+        })
+        .collect();
+
+    data.creeps.retain(|_, handoff| handoff.sent_tick + CREEP_LIFETIME > game::time());
+
+    write_local(&data);
+}
+
+// Walk the creep to a portal to its target shard. Returns true if the creep is busy travelling.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn travel_to_shard(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomCache) -> bool {
+    let Some(creep_memory) = memory.creeps.get(&creep.name()) else {
+        return false;
+    };
+
+    let Some(target_shard) = creep_memory.target_shard.clone() else {
+        return false;
+    };
+
+    if target_shard == game::shard::name() {
+        memory.creeps.get_mut(&creep.name()).unwrap().target_shard = None;
+        return false;
+    }
+
+    let portal = portals_to(memory, &target_shard)
+        .into_iter()
+        .min_by_key(|(pos, _)| pos.get_range_to(creep.pos()) + (pos.room_name() != creep.pos().room_name()) as u32 * 50)
+        .map(|(pos, _)| pos);
+
+    let Some(portal) = portal else {
+        creep.bsay("NO PRTL", false);
+        return true;
+    };
+
+    if creep.pos().is_near_to(portal) {
+        // Hand off now, once we step through this shard loses the creep.
+        // Only the first tick by the portal, the other side only needs it the once.
+        let mut data = read_local();
+        if !data.creeps.contains_key(&creep.name()) {
+            data.creeps.insert(
+                creep.name(),
+                InterShardCreep {
+                    memory: memory.creeps.get(&creep.name()).unwrap().clone(),
+                    sent_tick: game::time(),
+                },
+            );
+            write_local(&data);
+        }
+
+        if let Some(direction) = creep.pos().get_direction_to(portal) {
+            let _ = creep.ITmove_direction(direction);
+        }
+
+        creep.bsay("🌀", false);
+        return true;
+    }
+
+    let room_cache = cache.rooms.get_mut(&creep.room().unwrap().name()).unwrap();
+    creep.better_move_to(memory, room_cache, portal, 1, MoveOptions::default().avoid_enemies(true));
+
+    true
+}
+
+// Colonies started from another shard have no claim goal here, so put their spawn down ourselves.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn place_colony_spawns(memory: &ScreepsMemory, cache: &RoomCache) {
+    if game::time() % 10 != 0 || game::cpu::bucket() < 2500 {
+        return;
+    }
+
+    for room_name in &cache.my_rooms {
+        if memory.goals.room_claim.contains_key(&ClaimTarget::local(*room_name)) {
+            continue;
+        }
+
+        let Some(room_cache) = cache.rooms.get(room_name) else {
+            continue;
+        };
+
+        let has_spawn = !room_cache.structures.spawns.is_empty()
+            || room_cache
+                .structures
+                .construction_sites
+                .iter()
+                .any(|site| site.structure_type() == StructureType::Spawn);
+
+        if !has_spawn {
+            info!("[INTER SHARD] {} has no spawn, placing one", room_name);
+            place_spawn_site(room_name, &room_cache.room, room_cache);
+        }
+    }
+}
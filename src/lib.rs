@@ -8,28 +8,30 @@ use std::{
     sync::{Mutex, Once, OnceLock},
 };
 
-use combat::{ally::Allies, global::run_global_goal_setters, goals::run_goal_handlers, hate_handler::decay_hate};
+use combat::{ally::Allies, global::run_global_goal_setters, goals::run_goal_handlers, hate_handler::decay_hate, nuke_planner::run_nuke_planner};
 use constants::{MAX_BUCKET, MMO_SHARD_NAMES};
 use formation::formations::run_formations;
 use heap_cache::GlobalHeapCache;
+use inter_shard::{place_colony_spawns, publish_shard_data, receive_creeps};
 use log::*;
 use memory::Role;
 use movement::caching::generate_pathing_targets;
 use profiling::timing::{INTENTS_USED, PATHFIND_CPU, SUBTRACT_INTENTS};
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use room::{
-    cache::{hauling, traffic, RoomCache}, democracy::start_government, expansion::{attempt_expansion, can_expand}, spawning::spawn_manager::{self, run_spawning, SpawnManager}, visuals::visualise_scouted_rooms
+    cache::{hauling, terminals::run_terminal_network, traffic, RoomCache}, democracy::start_government, expansion::{attempt_expansion, can_expand}, market::run_market, observer::run_observers, power_creeps::{manage_power_creeps, run_power_creeps}, spawning::{renewal::renew_creeps, spawn_manager::{self, run_spawning, SpawnManager}}, visuals::visualise_scouted_rooms
 };
-use screeps::{find, game::{self}, OwnedStructureProperties};
+use screeps::{find, game::{self}, HasPosition, OwnedStructureProperties, RoomName};
 use traits::{creep::CreepExtensions, intents_tracking::{
     ConstructionExtensionsTracking, CreepExtensionsTracking, StructureControllerExtensionsTracking,
     StructureObjectTracking,
 }};
 use wasm_bindgen::prelude::*;
 
-use crate::{memory::ScreepsMemory, traits::room::RoomExtensions};
-
+use crate::{memory::{BuildingPlannerPlan, ScreepsMemory}, room::planning::room::building_planner, traits::room::RoomExtensions};
 
+// Only the game needs the fixed heap, the offline tools run on the system allocator.
+#[cfg(target_arch = "wasm32")]
 #[global_allocator]
 static ALLOCATOR: talc::Talck<talc::locking::AssumeUnlockable, talc::ClaimOnOom> = {
     static mut MEMORY: [u8; 0x1F000000] = [0; 0x1F000000];
@@ -43,6 +45,7 @@ mod config;
 mod constants;
 mod goal_memory;
 mod heap_cache;
+mod inter_shard;
 mod logging;
 mod memory;
 mod movement;
@@ -53,6 +56,9 @@ mod formation;
 mod utils;
 mod compression;
 
+// Pure enough to run outside the game, used by the offline planner binary.
+pub use room::planning::room::base_plan;
+
 static INITIALIZED: Once = Once::new();
 pub static CLEAN_PROFILE: Mutex<bool> = Mutex::new(true);
 
@@ -135,6 +141,9 @@ pub fn game_loop() {
 
     memory.stats.cpu.pathfinding = 0.0;
 
+    // Before anything runs, so creeps that just came through a portal get their memory back.
+    receive_creeps(&mut memory);
+
     let pre_room_cpu = game::cpu::get_used();
     for room in game::rooms().values() {
         if game::cpu::bucket() < 100 && game::cpu::get_used() > game::cpu::limit() as f64 * 0.5 {
@@ -261,13 +270,28 @@ pub fn game_loop() {
     run_global_goal_setters(&mut memory, &mut cache);
     run_goal_handlers(&mut memory, &mut cache);
     run_formations(&mut memory, &mut cache);
+    run_nuke_planner(&mut memory, &cache);
+    run_observers(&memory, &cache);
+
+    manage_power_creeps(&mut memory, &cache);
+    run_power_creeps(&mut memory, &mut cache);
+
+    publish_shard_data(&memory, &cache);
+    place_colony_spawns(&memory, &cache);
 
     let pre_spawn_cpu = game::cpu::get_used();
     if game::cpu::bucket() > 100 {
         run_spawning(&mut memory, &mut cache);
+        renew_creeps(&mut memory, &cache);
     }
     memory.stats.cpu.spawning = game::cpu::get_used() - pre_spawn_cpu;
 
+    let pre_market_cpu = game::cpu::get_used();
+    run_market(&mut memory, &mut cache);
+    memory.stats.cpu.market = game::cpu::get_used() - pre_market_cpu;
+
+    run_terminal_network(&mut cache);
+
     if game::time() % 100 == 0 {
         memory.filter_old_creeps();
 
@@ -446,9 +470,12 @@ pub fn game_loop() {
     );
     info!("  Bucket: {}", game::cpu::bucket());
     info!("  Heap: {:.2}% ({:.2} mb)", used, ((heap.total_heap_size() as f64 + heap.externally_allocated_size() as f64) / 1024.0 / 1024.0));
-    info!("  Allocated now: {:?} bytes / {:?} bytes", ALLOCATOR.lock().get_counters().allocated_bytes, ALLOCATOR.lock().get_counters().available_bytes);
-    //ALLOCATOR.lock().get_counters().
-    info!("  Allocated now: {:.2} mb / {:.2} mb", (ALLOCATOR.lock().get_counters().allocated_bytes as f64 / 1024.0 / 1024.0), (ALLOCATOR.lock().get_counters().available_bytes as f64 / 1024.0 / 1024.0));
+    #[cfg(target_arch = "wasm32")]
+    {
+        info!("  Allocated now: {:?} bytes / {:?} bytes", ALLOCATOR.lock().get_counters().allocated_bytes, ALLOCATOR.lock().get_counters().available_bytes);
+        //ALLOCATOR.lock().get_counters().
+        info!("  Allocated now: {:.2} mb / {:.2} mb", (ALLOCATOR.lock().get_counters().allocated_bytes as f64 / 1024.0 / 1024.0), (ALLOCATOR.lock().get_counters().available_bytes as f64 / 1024.0 / 1024.0));
+    }
     info!("  Time since last reset: {}", heap_lifetime);
     *heap_lifetime += 1;
 
@@ -534,8 +561,11 @@ pub fn set_stats(memory: &mut ScreepsMemory) {
     stats.memory_usage.total = 2 * 1000000;
     stats.memory_usage.used = get_memory_usage_bytes();
 
-    stats.heap_usage.total = ALLOCATOR.lock().get_counters().available_bytes as u32;
-    stats.heap_usage.used = ALLOCATOR.lock().get_counters().allocated_bytes as u32;
+    #[cfg(target_arch = "wasm32")]
+    {
+        stats.heap_usage.total = ALLOCATOR.lock().get_counters().available_bytes as u32;
+        stats.heap_usage.used = ALLOCATOR.lock().get_counters().allocated_bytes as u32;
+    }
 
     stats.cpu.used = game::cpu::get_used();
     stats.cpu.bucket = game::cpu::bucket();
@@ -623,6 +653,69 @@ pub fn wipe_scouting_data() {
     memory.write_memory();
 }
 
+#[wasm_bindgen(js_name = export_plan)]
+pub fn export_plan(room_name: String) -> String {
+    let memory = heap().memory.lock().unwrap();
+
+    let Some(room_memory) = RoomName::new(&room_name).ok().and_then(|name| memory.rooms.get(&name)) else {
+        return format!("No room memory for {}", room_name);
+    };
+
+    let plan = building_planner::export_room_plan(room_memory);
+    serde_json::to_string(&plan).unwrap_or_else(|e| format!("Failed to export plan for {}: {}", room_name, e))
+}
+
+#[wasm_bindgen(js_name = import_plan)]
+pub fn import_plan(room_name: String, plan: String) -> String {
+    let mut memory = heap().memory.lock().unwrap();
+
+    let Some((name, room_memory)) = RoomName::new(&room_name).ok().and_then(|name| memory.rooms.get_mut(&name).map(|room_memory| (name, room_memory))) else {
+        return format!("No room memory for {}", room_name);
+    };
+
+    let existing_spawns = game::rooms()
+        .get(name)
+        .map(|room| room.find(find::MY_SPAWNS, None).iter().map(|spawn| spawn.pos().xy()).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    match serde_json::from_str::<BuildingPlannerPlan>(&plan) {
+        Ok(plan) => {
+            let (spawn_center, storage_center) = match building_planner::plan_centers(&plan, &existing_spawns) {
+                Ok(centers) => centers,
+                Err(e) => return format!("Rejected plan for {}: {}", room_name, e),
+            };
+
+            room_memory.spawn_center = spawn_center;
+            if let Some(storage_center) = storage_center {
+                room_memory.storage_center = storage_center;
+            }
+
+            room_memory.imported_plan = Some(plan);
+            // Get construction going off the new plan next time round.
+            room_memory.planned = false;
+        }
+        Err(e) => return format!("Failed to parse plan for {}: {}", room_name, e),
+    }
+
+    memory.write_memory();
+    format!("Imported plan for {}", room_name)
+}
+
+#[wasm_bindgen(js_name = clear_imported_plan)]
+pub fn clear_imported_plan(room_name: String) -> String {
+    let mut memory = heap().memory.lock().unwrap();
+
+    let Some(room_memory) = RoomName::new(&room_name).ok().and_then(|name| memory.rooms.get_mut(&name)) else {
+        return format!("No room memory for {}", room_name);
+    };
+
+    room_memory.imported_plan = None;
+    room_memory.planned = false;
+
+    memory.write_memory();
+    format!("Cleared imported plan for {}, back to the base planner", room_name)
+}
+
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn just_reset() -> bool {
     if game::time() == 0 {
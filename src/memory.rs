@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use enum_map::{enum_map, Enum, EnumMap};
 use log::error;
-use screeps::{game, ObjectId, RawObjectId, ResourceType, RoomName, RoomXY, Source, Structure, StructureContainer, StructureLink};
+use screeps::{game, ObjectId, RawObjectId, ResourceType, RoomName, RoomXY, Source, Structure, StructureContainer, StructureLab, StructureLink, StructureType};
 use serde::{Deserialize, Serialize};
 
 use js_sys::JsString;
@@ -53,10 +53,18 @@ pub enum Role {
     Reserver,
     RemoteDefender,
     InvaderCoreCleaner,
+    KeeperKiller,
 
     InvaderDuoAttacker,
     InvaderDuoHealer,
 
+    PowerBankAttacker,
+    PowerBankHealer,
+    PowerBankHauler,
+
+    DepositHarvester,
+    DepositHauler,
+
     #[cfg(feature = "season1")]
     Season1Digger,
     #[cfg(feature = "season1")]
@@ -94,10 +102,18 @@ pub fn iter_roles() -> Vec<Role> {
         Role::Reserver,
         Role::RemoteDefender,
         Role::InvaderCoreCleaner,
+        Role::KeeperKiller,
 
         Role::InvaderDuoAttacker,
         Role::InvaderDuoHealer,
 
+        Role::PowerBankAttacker,
+        Role::PowerBankHealer,
+        Role::PowerBankHauler,
+
+        Role::DepositHarvester,
+        Role::DepositHauler,
+
         Role::Recycler,
         Role::GiftBasket,
     ]
@@ -159,6 +175,17 @@ pub struct CreepMemory{
         #[serde(skip_serializing_if = "Option::is_none")]
         #[serde(rename = "4")]
         pub amount: Option<u32>,
+        // Where to go after this one, same haul type and resource as the task itself.
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        #[serde(rename = "5")]
+        pub next_stops: Vec<pub struct CreepHaulStop {
+            #[serde(rename = "0")]
+            pub target_id: RawObjectId,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            #[serde(rename = "1")]
+            pub amount: Option<u32>,
+        }>,
     }>,
 
     // Role specific memory ----------
@@ -174,6 +201,26 @@ pub struct CreepMemory{
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "21")]
     pub target_room: Option<RoomName>,
+
+    // Compounds we still need to get boosted with before running our role.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "22")]
+    pub boosts: Option<Vec<ResourceType>>,
+
+    // The shard the creep is headed to, it hands its memory off when it steps through the portal.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "23")]
+    pub target_shard: Option<String>,
+
+    // Ticks it took to get from the spawn to work, so the replacement can be spawned in time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "24")]
+    pub travel_time: Option<u32>,
+
+    // Headed to a spawn to get renewed, until it is topped back up.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "25")]
+    pub renewing: Option<bool>,
 }
 }
 
@@ -201,17 +248,73 @@ pub struct RoomMemory{
     #[serde(skip_serializing_if = "Option::is_none")]
     pub skippy_planner: Option<pub struct SkippyMem {
         pub step: u8,
+        // Scratch space while planning, cleared out once the layout is done.
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Vec::is_empty")]
         pub map: Vec<char>,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "HashMap::is_empty")]
         pub source_fills: HashMap<ObjectId<Source>, HashMap<i32, i32>>,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "HashMap::is_empty")]
         pub controller_fill: HashMap<i32, i32>,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "HashMap::is_empty")]
         pub orth_wall_fill: HashMap<i32, i32>,
 
         pub stamp_index: Option<i32>,
         pub source_labs: [i32; 2],
         pub core: i32,
         pub planned: bool,
+
+        // Center tile of each stamp, in stamp_list order. Missing ones didnt fit.
+        #[serde(default)]
+        pub stamp_centers: Vec<i32>,
+        // The finished plan, one char per tile, same mappings as the map.
+        #[serde(default)]
+        pub layout: String,
+        #[serde(default)]
+        pub ramparts: Vec<u16>,
+    }>,
+
+    // A hand made plan in the community building planner format, set from the console.
+    // Takes over from the base planner while it's set.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub imported_plan: Option<pub struct BuildingPlannerPlan {
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub name: Option<String>,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub shard: Option<String>,
+        #[serde(default)]
+        pub rcl: u8,
+        pub buildings: HashMap<StructureType, pub struct BuildingPlannerPositions {
+            pub pos: Vec<pub struct BuildingPlannerPos {
+                pub x: u8,
+                pub y: u8,
+            }>,
+        }>,
     }>,
 
+    #[serde(default)]
+    pub labs: LabMemory,
+    #[serde(default)]
+    pub factory: FactoryMemory,
+
+    #[serde(default)]
+    pub incoming_nukes: Vec<pub struct IncomingNuke {
+        pub pos: RoomXY,
+        pub impact_tick: u32,
+        pub launch_room: RoomName,
+    }>,
+
+    // Lost structures waiting to be put back, the heap works off a copy of this so it survives a reset.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rebuild_queue: Vec<(RoomXY, StructureType)>,
+
     pub avg_spawn_expense: f64,
     pub income: u32,
     pub expense: u32,
@@ -220,6 +323,36 @@ pub struct RoomMemory{
 }
 }
 
+// What the labs in a room are currently doing.
+structstruck::strike! {
+    #[strikethrough[derive(Serialize, Deserialize, Debug, Clone, Default)]]
+    pub struct LabMemory {
+        // The compound we are currently making
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub target: Option<ResourceType>,
+
+        pub input_labs: Vec<ObjectId<StructureLab>>,
+        pub output_labs: Vec<ObjectId<StructureLab>>,
+
+        pub last_target_check: u32,
+
+        // Labs held back from reactions so creeps can boost from them.
+        #[serde(default)]
+        pub boost_labs: HashMap<ResourceType, ObjectId<StructureLab>>,
+    }
+}
+
+// What the factory in a room is currently producing.
+structstruck::strike! {
+    #[strikethrough[derive(Serialize, Deserialize, Debug, Clone, Default)]]
+    pub struct FactoryMemory {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub target: Option<ResourceType>,
+
+        pub last_target_check: u32,
+    }
+}
+
 // Remote Room memory
 structstruck::strike! {
     #[strikethrough[derive(Serialize, Deserialize, Debug, Clone)]]
@@ -286,6 +419,35 @@ structstruck::strike! {
         pub source_keepers: Option<Vec<RoomXY>>,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub mineral: Option<ResourceType>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub power_bank: Option<pub struct ScoutedPowerBank {
+            pub pos: RoomXY,
+            pub amount: u32,
+            pub hits: u32,
+            pub decay_tick: u32,
+            pub free_spots: u8,
+        }>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub deposits: Option<Vec<pub struct ScoutedDeposit {
+            pub pos: RoomXY,
+            pub deposit_type: ResourceType,
+            pub last_cooldown: u32,
+            pub decay_tick: u32,
+            pub free_spots: u8,
+        }>>,
+        // Only kept for rooms other players own, so we know what we are looking at.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub structures: Option<Vec<pub struct ScoutedStructure {
+            pub pos: RoomXY,
+            pub structure_type: StructureType,
+        }>>,
+        // Only portals to other shards, in-shard ones arent worth the memory.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub portals: Option<Vec<pub struct ScoutedPortal {
+            pub pos: RoomXY,
+            pub destination_room: RoomName,
+            pub destination_shard: String,
+        }>>,
         pub last_scouted: u32,
     }
 }
@@ -310,6 +472,16 @@ structstruck::strike! {
             pub cpu_unlocks: u32,
             pub access_keys: u32,
             pub pixels: u32,
+
+            // Running totals since the stats were made.
+            #[serde(default)]
+            pub deals: u32,
+            #[serde(default)]
+            pub income: f64,
+            #[serde(default)]
+            pub spending: f64,
+            #[serde(default)]
+            pub fees: u32,
         },
 
         pub memory_usage: pub struct MemoryStats {
@@ -366,6 +538,12 @@ structstruck::strike! {
                 pub hauling_orders: f64,
                 pub links: f64,
                 pub towers: f64,
+                #[serde(default)]
+                pub labs: f64,
+                #[serde(default)]
+                pub factory: f64,
+                #[serde(default)]
+                pub power: f64,
                 pub remotes: f64
             },
 
@@ -383,6 +561,8 @@ structstruck::strike! {
                 pub income_power: u32,
                 pub income_trading: u32,
                 pub income_other: u32,
+                #[serde(default)]
+                pub income_credits: f64,
 
                 pub deposited_energy: u32,
                 pub deposited_minerals: u32,
@@ -391,6 +571,11 @@ structstruck::strike! {
                 pub spending_upgrading: u32,
                 pub spending_construction: u32,
                 pub spending_repair: u32,
+                #[serde(default)]
+                pub spending_power: u32,
+
+                #[serde(default)]
+                pub processed_power: u32,
             },
         }>
     }
@@ -410,6 +595,10 @@ structstruck::strike! {
         pub rooms: HashMap<RoomName, RoomMemory>,
         pub remote_rooms: HashMap<RoomName, RemoteRoomMemory>,
         pub creeps: HashMap<String, CreepMemory>,
+        #[serde(default)]
+        pub power_creeps: HashMap<String, pub struct PowerCreepMemory {
+            pub home_room: RoomName,
+        }>,
         pub formations: FormationMemory,
 
         pub goals: GoalMemory,
@@ -422,6 +611,16 @@ structstruck::strike! {
         #[serde(default)]
         pub adoption_queue: HashMap<Role, Vec<String>>,
 
+        // Nukes we have sent, so anything following up can be timed to the landing.
+        #[serde(default)]
+        pub nuke_launches: Vec<pub struct NukeLaunch {
+            pub from_room: RoomName,
+            pub target_room: RoomName,
+            pub target_pos: RoomXY,
+            pub launch_tick: u32,
+            pub impact_tick: u32,
+        }>,
+
         pub stats: StatsData,
     }
 }
@@ -446,11 +645,13 @@ impl ScreepsMemory {
                 rooms: HashMap::new(),
                 remote_rooms: HashMap::new(),
                 creeps: HashMap::new(),
+                power_creeps: HashMap::new(),
                 formations: FormationMemory::default(),
                 goals: GoalMemory::default(),
                 expansion: None,
 
                 adoption_queue: HashMap::new(),
+                nuke_launches: Vec::new(),
 
                 enemy_players: HashMap::new(),
                 scouted_rooms: HashMap::new(),
@@ -484,11 +685,13 @@ impl ScreepsMemory {
                         rooms: HashMap::new(),
                         remote_rooms: HashMap::new(),
                         creeps: HashMap::new(),
+                        power_creeps: HashMap::new(),
                         formations: FormationMemory::default(),
                         goals: GoalMemory::default(),
                         expansion: None,
 
                         adoption_queue: HashMap::new(),
+                        nuke_launches: Vec::new(),
 
                         enemy_players: HashMap::new(),
                         scouted_rooms: HashMap::new(),
@@ -563,6 +766,10 @@ impl Default for CreepMemory {
             scout_target: None,
             hauling_task: None,
             is_recycling: None,
+            boosts: None,
+            target_shard: None,
+            travel_time: None,
+            renewing: None,
         }
     }
 }
@@ -1,17 +1,43 @@
-use std::{cmp, collections::HashMap};
+use std::{cmp, collections::{HashMap, HashSet}};
 
 use log::info;
 use screeps::{
-    game, CircleStyle, Creep, HasId, HasPosition, Part, Position, RawObjectId, ResourceType, RoomName, SharedCreepProperties, StructureProperties, StructureType, TextStyle
+    game, CircleStyle, Creep, HasId, HasPosition, Position, RawObjectId, ResourceType, RoomName, SharedCreepProperties, StructureProperties, StructureType, TextStyle
 };
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    heap, heap_cache::hauling::HeapHaulingReservation, memory::{CreepHaulTask, Role, ScreepsMemory}, room::creeps::local::hauler::execute_order, utils::{self, name_to_role, scale_haul_priority}
+    config::{HAULER_ASSIGNMENT_MAX_WORK, HAULER_MATCHING_CPU_BUDGET, HAUL_ROUTE_MAX_STOPS, HAUL_ROUTE_STOP_RANGE}, heap, heap_cache::hauling::{HeapHaulingCache, HeapHaulingReservation}, memory::{CreepHaulStop, CreepHaulTask, Role, ScreepsMemory}, room::creeps::local::hauler::execute_order, utils::{self, name_to_role, scale_haul_priority}
 };
 
 use super::{CachedRoom, RoomCache};
 
+// Cost for a hauler and order that cant be paired, the solver never picks these over a real match.
+const UNMATCHABLE: f64 = 1e9;
+// How much a completely empty trip counts against an order, in priority points.
+const UNDERFILL_PENALTY: f32 = 20.0;
+
+// What a hauler has on it, read once per tick for matching.
+struct HaulerStore {
+    pos: Position,
+    capacity: u32,
+    used: u32,
+    carrying: HashMap<ResourceType, u32>,
+}
+
+impl HaulerStore {
+    fn new(creep: &Creep, resources: &HashSet<ResourceType>) -> Self {
+        let store = creep.store();
+
+        Self {
+            pos: creep.pos(),
+            capacity: store.get_capacity(None),
+            used: store.get_used_capacity(None),
+            carrying: resources.iter().map(|resource| (*resource, store.get_used_capacity(Some(*resource)))).collect(),
+        }
+    }
+}
+
 // Order creation ------
 
 // Priorities are 1:1 now.
@@ -116,7 +142,7 @@ pub struct HaulingCache {
 
     pub wanting_orders: Vec<HaulTaskRequest>,
 
-    orders_matched_to_creeps: HashMap<u32, (String, f32)>,
+    creeps_matched_to_orders: HashMap<String, (u32, f32)>,
 
     iterator_salt: u32,
 }
@@ -130,7 +156,7 @@ impl HaulingCache {
             haulers: Vec::new(),
             wanting_orders: Vec::new(),
 
-            orders_matched_to_creeps: HashMap::new(),
+            creeps_matched_to_orders: HashMap::new(),
 
             reserved_order_distances: HashMap::new(),
             iterator_salt: 0,
@@ -192,7 +218,7 @@ impl HaulingCache {
         {
             // We want to drop this lock ASAP.
             let lock = heap().hauling.lock().unwrap();
-            if let Some(existing_order) = lock.reserved_orders.get(&order.target) {
+            if let Some(existing_order) = lock.reserved_orders.get(&(order.target, order.resource.unwrap_or(ResourceType::Energy))) {
                 let reserved_amt = existing_order.reserved_amount;
 
                 if game::time() % 10 == 0 {
@@ -277,7 +303,7 @@ pub fn match_haulers(room_cache: &mut RoomCache, memory: &mut ScreepsMemory, roo
             .creeps
             .creeps_of_role(Role::BaseHauler);
 
-        let mut orders = cache.hauling.new_orders.clone();
+        let orders = cache.hauling.new_orders.clone();
 
         // CPU saver, dont execute if theres no haulers
         if cache.hauling.wanting_orders.is_empty() {
@@ -285,170 +311,197 @@ pub fn match_haulers(room_cache: &mut RoomCache, memory: &mut ScreepsMemory, roo
             return;
         }
 
-        // Where the magic happens, each hauler runs through each order
-        for hauler in cache.hauling.wanting_orders.iter() {
-            let game_creep = game::creeps().get(hauler.creep_name.to_string()).unwrap();
+        let haulers = cache
+            .hauling
+            .wanting_orders
+            .iter()
+            .filter_map(|hauler| Some((hauler, game::creeps().get(hauler.creep_name.to_string())?)))
+            .collect::<Vec<_>>();
+
+        if haulers.is_empty() {
+            return;
+        }
+
+        let order_list = orders.values().collect::<Vec<_>>();
+        let order_positions = order_list.iter().map(|order| order.get_target_position()).collect::<Vec<_>>();
+
+        // Read every store once up front, hitting the JS side per hauler per slot adds up fast.
+        let order_resources = order_list
+            .iter()
+            .map(|order| order.resource.unwrap_or(ResourceType::Energy))
+            .collect::<HashSet<_>>();
+        let stores = haulers
+            .iter()
+            .map(|(_, creep)| HaulerStore::new(creep, &order_resources))
+            .collect::<Vec<_>>();
+
+        // Big orders get a slot per hauler it takes to clear them, small ones just the one.
+        // That way the solver wont send three haulers after 50 energy.
+        let average_capacity = (stores.iter().map(|store| store.capacity).sum::<u32>() / haulers.len() as u32).max(1);
+
+        let mut slots = Vec::new();
+        for (order_index, order) in order_list.iter().enumerate() {
+            let amount = order.amount.unwrap_or(average_capacity);
+            let slot_count = amount.div_ceil(average_capacity).clamp(1, haulers.len() as u32);
+
+            for slot in 0..slot_count {
+                slots.push((order_index, amount - cmp::min(amount, slot * average_capacity)));
+            }
+        }
 
-            let mut top_scoring_order = None;
-            let mut top_score = f32::MAX;
+        // Every hauler against every slot. If we run out of CPU, whoever is left waits a tick.
+        let mut costs = Vec::new();
+        for ((hauler, _), store) in haulers.iter().zip(stores.iter()) {
+            if game::cpu::get_used() - starting_cpu > HAULER_MATCHING_CPU_BUDGET {
+                info!("  [HAULING] Room {} hit the matching CPU budget, {} haulers wait a tick", room_name, haulers.len() - costs.len());
+                break;
+            }
 
             let role = name_to_role(&hauler.creep_name);
 
-            for order in orders.values() {
-                // Dont let haulers pull from storage if there is a base hauler
-                // Just to avoid them getting stuck.
-                if order.target_type == Some(StructureType::Storage)
-                    && hauler.haul_type.contains(&HaulingType::Offer)
-                    && role == Some(Role::Hauler)
-                    && base_hauler_count >= 1
-                {
-                    continue;
-                }
+            let row = slots
+                .iter()
+                .map(|(order_index, remaining)| {
+                    let order = order_list[*order_index];
+
+                    // Dont let haulers pull energy from storage if there is a base hauler
+                    // Just to avoid them getting stuck. Minerals are fine, base haulers dont touch those.
+                    if order.target_type == Some(StructureType::Storage)
+                        && order.resource.unwrap_or(ResourceType::Energy) == ResourceType::Energy
+                        && hauler.haul_type.contains(&HaulingType::Offer)
+                        && role == Some(Role::Hauler)
+                        && base_hauler_count >= 1
+                    {
+                        return UNMATCHABLE;
+                    }
 
-                if hauler.maintain_room.is_some() {
-                    if let Some(target_pos) = order.get_target_position() {
-                        if target_pos.room_name() != hauler.maintain_room.unwrap() {
-                            continue;
+                    if let (Some(maintain_room), Some(target_pos)) = (hauler.maintain_room, order_positions[*order_index]) {
+                        if target_pos.room_name() != maintain_room {
+                            return UNMATCHABLE;
                         }
                     }
-                }
 
-                // Check if the haul type actually matches what we want
-                // Duh...
-                if !hauler.haul_type.contains(&order.haul_type) {
-                    continue;
-                }
+                    // Check if the haul type actually matches what we want
+                    // Duh...
+                    if !hauler.haul_type.contains(&order.haul_type) {
+                        return UNMATCHABLE;
+                    }
+
+                    // If the order is for a specific resource, only match it if the hauler can carry it
+                    // If the order doesnt contain a resource, assume energy.
+                    if let (Some(resource_type), Some(order_resource)) = (hauler.resource_type, order.resource) {
+                        if order_resource != resource_type {
+                            return UNMATCHABLE;
+                        }
+                    }
 
-                // If the order is for a specific resource, only match it if the hauler can carry it
-                // If the order doesnt contain a resource, assume energy.
-                if let Some(resource_type) = hauler.resource_type {
-                    if order.resource.is_some() && order.resource.unwrap() != resource_type {
-                        continue;
+                    // If the order is reserved, and the amount is reserved is
+                    // greater than the amount of the order, skip it, as we dont want over-hauling.
+                    if let Some(reserved) = heap_hauling.reserved_orders.get(&(order.target, order.resource.unwrap_or(ResourceType::Energy))) {
+                        if reserved.creeps_assigned.contains(&hauler.creep_name)
+                            || reserved.reserved_amount >= order.amount.unwrap_or(0) as i32
+                        {
+                            return UNMATCHABLE;
+                        }
                     }
-                }
 
-                // If the order is reserved, and the amount is reserved is
-                // greater than the amount of the order, skip it, as we dont want over-hauling.
-                if let Some(reserved) = heap_hauling.reserved_orders.get(&order.target) {
-                    if reserved.creeps_assigned.contains(&hauler.creep_name)
-                        || reserved.reserved_amount >= order.amount.unwrap() as i32
+                    // Changed how some things work. The order can "Not calculate distance"
+                    // So that way, we can have orders that are just "highest priority" and not
+                    // based on distance, for say, remotes.
+                    let Some(mut score) = score_couple(order, store.pos, order_positions[*order_index]) else {
+                        return UNMATCHABLE;
+                    };
+
+                    // We dont want the hauler transferring to the storage
+                    // if the base hauler doesnt exist, its to influence sending
+                    // to extensions and upgrading. Treating it more of a "last resort"
+                    if order.target_type == Some(StructureType::Storage)
+                        && hauler.haul_type.contains(&HaulingType::Transfer)
+                        && role == Some(Role::Hauler)
+                        && base_hauler_count == 0
                     {
-                        continue;
+                        score += 1000.0;
                     }
-                }
 
-                // Changed how some things work. The order can "Not calculate distance"
-                // So that way, we can have orders that are just "highest priority" and not
-                // based on distance, for say, remotes.
-
-                // This function scores based off of
-                // priority + distance
-                // and if its the lowest, we take it.
-                // As its the highest priority, and the closest.
-                let mut score = score_couple(order, &game_creep);
-
-                // We dont want the hauler transferring to the storage
-                // if the base hauler doesnt exist, its to influence sending
-                // to extensions and upgrading. Treating it more of a "last resort"
-                if order.target_type == Some(StructureType::Storage)
-                    && hauler.haul_type.contains(&HaulingType::Transfer)
-                    && role == Some(Role::Hauler)
-                    && base_hauler_count == 0
-                {
-                    score += 1000.0;
-                }
+                    // Prefer trips that fill (or empty) the hauler, over ones that barely move anything.
+                    let resource = order.resource.unwrap_or(ResourceType::Energy);
+                    let (carried, capacity) = if order.haul_type == HaulingType::Transfer {
+                        (store.carrying.get(&resource).copied().unwrap_or(0), store.used)
+                    } else {
+                        (store.capacity.saturating_sub(store.used), store.capacity)
+                    };
 
-                if score < top_score {
-                    top_scoring_order = Some(order.clone());
-                    top_score = score;
-                }
-            }
+                    let moved = cmp::min(carried, *remaining);
+                    if capacity > 0 {
+                        score += (1.0 - moved as f32 / capacity as f32) * UNDERFILL_PENALTY;
+                    }
 
-            // If we have a match, we do stuffs!
-            if let Some(top_scorer) = top_scoring_order {
-                let _responsible_creep = cache.hauling.orders_matched_to_creeps.get(&top_scorer.id);
+                    score as f64
+                })
+                .collect::<Vec<_>>();
 
-                // If we are a better match than the other creep, we take it.
-                // Fuck you other creep! This shit is mine!
-                // Yes, we lose one tick on the other creep, but if its more worth it, then its fine.
-                /*if let Some((responsible_creep, score)) = responsible_creep {
-                    info!("Has responsible.");
-                    continue;
+            costs.push(row);
+        }
 
-                    if *responsible_creep == hauler.creep_name {
-                        continue;
-                    }
+        // The solver wants at least as many slots as haulers, pad it out with ones nobody takes.
+        for row in costs.iter_mut() {
+            while row.len() < haulers.len() {
+                row.push(UNMATCHABLE);
+            }
+        }
 
-                    if top_score < *score {
-
-                        info!("Switching tasks.");
-                        let matched_order = (hauler.creep_name.to_string(), top_score);
-
-                        cache
-                            .hauling
-                            .orders_matched_to_creeps
-                            .insert(top_scorer.id, matched_order);
-
-                        let order = orders.get_mut(&top_scorer.id).unwrap();
-
-                        // If we are going to take it, decrease the resource amount by how much were going to take
-                        // That way, all of our haulers dont rush for a single source at once.
-                        if order.haul_type == HaulingType::Pickup {
-                            let pickup_amount = cmp::min(
-                                order.amount.unwrap_or(0) as i32,
-                                game_creep.store().get_free_capacity(Some(
-                                    order.resource.unwrap_or(ResourceType::Energy),
-                                )),
-                            );
-                            let new_amount = order.amount.unwrap_or(0) as i32 - pickup_amount;
-
-                            if new_amount < 0 {
-                                if order.target_type != Some(StructureType::Storage) {
-                                    orders.remove(&top_scorer.id);
-                                }
-                            } else {
-                                order.amount = Some(new_amount as u32);
-                            }
-                        }
+        // The solver is rows squared times columns, and the budget above only covers building the rows.
+        // Too big a problem, or no CPU left for it, and we take the cheapest free slot per hauler instead.
+        let solver_work = costs.len() * costs.len() * costs.first().map_or(0, |row| row.len());
+        let assignment = if solver_work > HAULER_ASSIGNMENT_MAX_WORK || game::cpu::get_used() - starting_cpu > HAULER_MATCHING_CPU_BUDGET {
+            info!("  [HAULING] Room {} matching {} haulers greedily", room_name, costs.len());
 
-                        matched_creeps.push(hauler.creep_name.to_string());
-                    }
-                } else */{
-                    let matched_order = (hauler.creep_name.to_string(), top_score);
-
-                    cache
-                        .hauling
-                        .orders_matched_to_creeps
-                        .insert(top_scorer.id, matched_order);
-
-                    let order = orders.get_mut(&top_scorer.id).unwrap();
-
-                    // If we are going to take it, decrease the resource amount by how much were going to take
-                    // That way, all of our haulers dont rush for a single source at once.
-                    if order.haul_type == HaulingType::Pickup {
-                        let pickup_amount = cmp::min(
-                            order.amount.unwrap_or(0) as i32,
-                            game_creep.store().get_free_capacity(Some(
-                                order.resource.unwrap_or(ResourceType::Energy),
-                            )),
-                        );
-                        let new_amount = order.amount.unwrap_or(0) as i32 - pickup_amount;
-
-                        if new_amount <= 0 {
-                            orders.remove(&top_scorer.id);
-                        } else {
-                            order.amount = Some(new_amount as u32);
-                        }
-                    }
+            greedy_assignment(&costs)
+        } else {
+            min_cost_assignment(&costs)
+        };
 
-                    matched_creeps.push(hauler.creep_name.to_string());
-                }
+        for (hauler_index, slot_index) in assignment.into_iter().enumerate() {
+            let Some(slot_index) = slot_index else {
+                continue;
+            };
+
+            let score = costs[hauler_index][slot_index];
+            if score >= UNMATCHABLE || slot_index >= slots.len() {
+                continue;
             }
+
+            let creep_name = haulers[hauler_index].0.creep_name.to_string();
+            let order = order_list[slots[slot_index].0];
+
+            cache
+                .hauling
+                .creeps_matched_to_orders
+                .insert(creep_name.clone(), (order.id, score as f32));
+
+            matched_creeps.push(creep_name);
         }
 
+        // Orders that already have a hauler this tick, routes wont chain onto these.
+        let route_candidates = order_list.iter().copied().zip(order_positions.iter().copied()).collect::<Vec<_>>();
+        let mut routed = cache
+            .hauling
+            .creeps_matched_to_orders
+            .values()
+            .filter_map(|(order_id, _)| Some(orders.get(order_id)?.target))
+            .collect::<Vec<_>>();
+
+        // Where pickups end up, routes chain along the way there.
+        let home = cache
+            .structures
+            .storage
+            .as_ref()
+            .map(|storage| storage.pos())
+            .or_else(|| cache.storage_center.map(|xy| Position::new(xy.x, xy.y, *room_name)));
+
         // For the matched creeps, we assign their tasks in memory
         // This is also where we reserve the orders, so other haulers dont take them.
-        for (order_id, (creep, _score)) in cache.hauling.orders_matched_to_creeps.clone().iter() {
+        for (creep, (order_id, _score)) in cache.hauling.creeps_matched_to_orders.clone().iter() {
             let creep_memory = memory.creeps.get_mut(creep).unwrap();
             let creep = game::creeps().get(creep.to_string()).unwrap();
 
@@ -479,6 +532,17 @@ pub fn match_haulers(room_cache: &mut RoomCache, memory: &mut ScreepsMemory, roo
                 ResourceType::Energy
             };
 
+            // Whatever the first stop leaves over, room for pickups or resources for transfers.
+            let route_budget = if order.haul_type == HaulingType::Transfer {
+                let carried = creep.store().get_used_capacity(Some(resource));
+                carried.saturating_sub(order.amount.unwrap_or(carried))
+            } else {
+                let free = carry_capacity.max(0) as u32;
+                free.saturating_sub(order.amount.unwrap_or(free))
+            };
+
+            let next_stops = plan_route(order, route_budget, &route_candidates, &mut routed, &heap_hauling, home);
+
             // Haul task, for memory.
             let haul_task = CreepHaulTask {
                 target_id: order.target,
@@ -486,27 +550,33 @@ pub fn match_haulers(room_cache: &mut RoomCache, memory: &mut ScreepsMemory, roo
                 resource,
                 amount: order.amount,
                 haul_type: order.haul_type,
+                next_stops,
             };
 
+            // The rest of the route is ours too, reserve it so nobody else heads over.
+            for stop in haul_task.next_stops.iter() {
+                let order_amount = route_candidates
+                    .iter()
+                    .find(|(candidate, _)| candidate.target == stop.target_id)
+                    .and_then(|(candidate, _)| candidate.amount)
+                    .unwrap_or(0) as i32;
+
+                heap_hauling
+                    .reserved_orders
+                    .entry((stop.target_id, resource))
+                    .or_insert_with(|| HeapHaulingReservation::new(stop.target_id, resource, order_amount))
+                    .reserve(creep.name(), stop.amount.unwrap_or(0) as i32);
+            }
+
             // If the target is not a storage, we reserve the order
             if order.target_type != Some(StructureType::Storage) || order.target_type.is_none() {
                 // If the order is already reserved, we add the creep to the list of creeps assigned to it
                 // Then increment the reserved amount.
-                if let Some(reserved_order) = heap_hauling.reserved_orders.get_mut(&order.target) {
-                    reserved_order.reserved_amount += carry_capacity as i32;
-                    reserved_order.creeps_assigned.push(creep.name());
-                } else {
-                    // If the order is not reserved, we reserve it.
-                    heap_hauling.reserved_orders.insert(
-                        order.target,
-                        HeapHaulingReservation {
-                            target_id: order.target,
-                            reserved_amount: carry_capacity as i32,
-                            creeps_assigned: vec![creep.name()],
-                            order_amount: order.amount.unwrap_or(0) as i32,
-                        },
-                    );
-                }
+                heap_hauling
+                    .reserved_orders
+                    .entry((order.target, resource))
+                    .or_insert_with(|| HeapHaulingReservation::new(order.target, resource, order.amount.unwrap_or(0) as i32))
+                    .reserve(creep.name(), carry_capacity as i32);
             }
 
             // Set it in memory, so the creep can execute it.
@@ -538,18 +608,204 @@ pub fn match_haulers(room_cache: &mut RoomCache, memory: &mut ScreepsMemory, roo
     );
 }
 
+// Chain orders of the same kind onto a task, nearest to the last stop first, while the creep
+// has room (or resources) left for them. Only looks in the room of the first stop.
+fn plan_route(
+    first: &RoomHaulingOrder,
+    mut budget: u32,
+    candidates: &[(&RoomHaulingOrder, Option<Position>)],
+    routed: &mut Vec<RawObjectId>,
+    heap_hauling: &HeapHaulingCache,
+    home: Option<Position>,
+) -> Vec<CreepHaulStop> {
+    let mut stops = Vec::new();
+
+    // Storage takes anything, its where a route ends, not somewhere to stop along the way.
+    if first.target_type == Some(StructureType::Storage) || first.amount.is_none() {
+        return stops;
+    }
+
+    let Some(mut last_pos) = candidates.iter().find(|(order, _)| order.id == first.id).and_then(|(_, pos)| *pos) else {
+        return stops;
+    };
+
+    let resource = first.resource.unwrap_or(ResourceType::Energy);
+
+    while budget > 0 && stops.len() + 1 < HAUL_ROUTE_MAX_STOPS {
+        let next = candidates
+            .iter()
+            .filter_map(|(order, pos)| {
+                let pos = (*pos)?;
+
+                if order.haul_type != first.haul_type
+                    || order.resource.unwrap_or(ResourceType::Energy) != resource
+                    || order.target_type == Some(StructureType::Storage)
+                    || order.target == first.target
+                    || routed.contains(&order.target)
+                {
+                    return None;
+                }
+
+                let range = last_pos.get_range_to(pos);
+                let in_reach = match home {
+                    // Pickups are headed home after, so anything thats barely a detour on the way counts,
+                    // whatever room its in. Thats what picks up remote containers on the trip back.
+                    Some(home) if first.haul_type != HaulingType::Transfer => {
+                        let detour = (range + pos.get_range_to(home)).saturating_sub(last_pos.get_range_to(home));
+
+                        detour <= HAUL_ROUTE_STOP_RANGE
+                    }
+                    _ => pos.room_name() == last_pos.room_name() && range <= HAUL_ROUTE_STOP_RANGE,
+                };
+                if !in_reach {
+                    return None;
+                }
+
+                // Whats left once everyone already headed there takes their share.
+                let reserved = heap_hauling
+                    .reserved_orders
+                    .get(&(order.target, resource))
+                    .map_or(0, |reservation| reservation.reserved_amount.max(0) as u32);
+                let available = order.amount?.saturating_sub(reserved);
+                if available == 0 {
+                    return None;
+                }
+
+                Some((order.target, pos, range, available))
+            })
+            .min_by_key(|(_, _, range, _)| *range);
+
+        let Some((target, pos, _, available)) = next else {
+            break;
+        };
+
+        let amount = available.min(budget);
+        budget -= amount;
+        last_pos = pos;
+
+        routed.push(target);
+        stops.push(CreepHaulStop {
+            target_id: target,
+            amount: Some(amount),
+        });
+    }
+
+    stops
+}
+
+// Priority + distance, lowest is best. The position is looked up once per order by the caller.
 //#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
-pub fn score_couple(order: &RoomHaulingOrder, creep: &Creep) -> f32 {
+pub fn score_couple(order: &RoomHaulingOrder, creep_pos: Position, order_pos: Option<Position>) -> Option<f32> {
     if order.no_distance_calc {
-        order.priority
+        Some(order.priority)
     } else {
-        let creep_pos = creep.pos();
-        let order_pos = order.get_target_position().unwrap();
+        let distance = creep_pos.get_range_to(order_pos?);
+
+        Some(order.priority + distance as f32)
+    }
+}
+
+// Each hauler in turn takes the cheapest slot nobody has yet. Not as good as the solver, but linear.
+fn greedy_assignment(costs: &[Vec<f64>]) -> Vec<Option<usize>> {
+    let mut taken = vec![false; costs.first().map_or(0, |row| row.len())];
+    let mut assignment = Vec::with_capacity(costs.len());
+
+    for row in costs {
+        let best = row
+            .iter()
+            .enumerate()
+            .filter(|(column, cost)| !taken[*column] && **cost < UNMATCHABLE)
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(cmp::Ordering::Equal))
+            .map(|(column, _)| column);
+
+        if let Some(column) = best {
+            taken[column] = true;
+        }
+
+        assignment.push(best);
+    }
+
+    assignment
+}
+
+// Hungarian algorithm, rows are haulers and columns are order slots, there has to be at least as many columns.
+// Returns the column each row got.
+fn min_cost_assignment(costs: &[Vec<f64>]) -> Vec<Option<usize>> {
+    let rows = costs.len();
+    if rows == 0 {
+        return Vec::new();
+    }
+
+    let columns = costs[0].len();
+
+    let mut row_potential = vec![0.0; rows + 1];
+    let mut column_potential = vec![0.0; columns + 1];
+    // Which row each column is assigned to, 0 being none. Everything is 1 indexed, 0 is the scratch column.
+    let mut assigned = vec![0; columns + 1];
+    let mut way = vec![0; columns + 1];
+
+    for row in 1..=rows {
+        assigned[0] = row;
+
+        let mut current_column = 0;
+        let mut min_value = vec![f64::INFINITY; columns + 1];
+        let mut used = vec![false; columns + 1];
+
+        loop {
+            used[current_column] = true;
+
+            let current_row = assigned[current_column];
+            let mut delta = f64::INFINITY;
+            let mut next_column = 0;
+
+            for column in 1..=columns {
+                if used[column] {
+                    continue;
+                }
+
+                let reduced = costs[current_row - 1][column - 1] - row_potential[current_row] - column_potential[column];
+                if reduced < min_value[column] {
+                    min_value[column] = reduced;
+                    way[column] = current_column;
+                }
+
+                if min_value[column] < delta {
+                    delta = min_value[column];
+                    next_column = column;
+                }
+            }
+
+            for (column, is_used) in used.iter().enumerate() {
+                if *is_used {
+                    row_potential[assigned[column]] += delta;
+                    column_potential[column] -= delta;
+                } else {
+                    min_value[column] -= delta;
+                }
+            }
+
+            current_column = next_column;
+            if assigned[current_column] == 0 {
+                break;
+            }
+        }
 
-        let distance = creep_pos.get_range_to(order_pos);
+        // Walk back along the augmenting path.
+        while current_column != 0 {
+            let previous_column = way[current_column];
+            assigned[current_column] = assigned[previous_column];
+            current_column = previous_column;
+        }
+    }
 
-        order.priority + distance as f32
+    let mut assignment = vec![None; rows];
+    for (column, row) in assigned.iter().enumerate().skip(1) {
+        if *row != 0 {
+            assignment[row - 1] = Some(column - 1);
+        }
     }
+
+    assignment
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -562,11 +818,13 @@ pub fn clean_heap_hauling(memory: &mut ScreepsMemory) {
         let game_obj = game::get_object_by_id_erased(&hauling_order.target_id);
         let mut removed = Vec::new();
         // Remove creeps that are dead or have no task assigned
-        // Then add them to the ^^^^^ removed list, so we can calculate carry parts.
+        // Then add them to the ^^^^^ removed list, so we can give back what they reserved.
         hauling_order.creeps_assigned.retain(|creep| {
             if let Some(creep_memory) = memory.creeps.get_mut(creep) {
                 if let Some(task_id) = creep_memory.hauling_task.as_ref() {
-                    let res = task_id.target_id == hauling_order.target_id;
+                    let res = task_id.resource == hauling_order.resource
+                        && (task_id.target_id == hauling_order.target_id
+                            || task_id.next_stops.iter().any(|stop| stop.target_id == hauling_order.target_id));
 
                     if !res {
                         removed.push(creep.to_string());
@@ -587,34 +845,23 @@ pub fn clean_heap_hauling(memory: &mut ScreepsMemory) {
             }
         });
 
-        // Calculate the amount of resources the creeps can carry
-        let mut carry_total = 0;
+        // Give back exactly what each of them had reserved, route stops only hold part of a load.
         for creep in removed {
-            if let Some(game_creep) = game::creeps().get(creep) {
-                carry_total += game_creep
-                    .body()
-                    .iter()
-                    .filter(|part| part.part() == Part::Carry)
-                    .count()
-                    * 50;
-            } else {
-                carry_total += 200;
-            }
+            hauling_order.release(&creep);
         }
 
-        hauling_order.reserved_amount -= carry_total as i32;
         if hauling_order.reserved_amount <= 0
             || hauling_order.reserved_amount > hauling_order.order_amount
             || game_obj.is_none()
             || hauling_order.creeps_assigned.is_empty()
         {
-            to_delete.push(hauling_order.target_id);
+            to_delete.push((hauling_order.target_id, hauling_order.resource));
         }
     }
 
     // Delete these marked orders
-    for target in to_delete {
-        lock.reserved_orders.remove(&target);
+    for key in to_delete {
+        lock.reserved_orders.remove(&key);
     }
 }
 
@@ -627,6 +874,20 @@ impl CreepHaulTask {
 
         Some(target.unwrap().pos())
     }
+
+    // The task for the next stop on the route, if there is one.
+    pub fn next_task(&self) -> Option<CreepHaulTask> {
+        let (next, rest) = self.next_stops.split_first()?;
+
+        Some(CreepHaulTask {
+            target_id: next.target_id,
+            haul_type: self.haul_type,
+            priority: self.priority,
+            resource: self.resource,
+            amount: next.amount,
+            next_stops: rest.to_vec(),
+        })
+    }
 }
 
 impl RoomHaulingOrder {
@@ -639,6 +900,44 @@ impl RoomHaulingOrder {
     }
 }
 
+// Make a withdraw order on whichever of storage and terminal holds more of the resource.
+// Returns how much we ordered, so the caller can make the matching transfer.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn haul_from_stock(room_cache: &mut CachedRoom, resource: ResourceType, amount: u32, priority: f32) -> u32 {
+    let storage_amount = room_cache
+        .structures
+        .storage
+        .as_ref()
+        .map_or(0, |s| s.store().get_used_capacity(Some(resource)));
+    let terminal_amount = room_cache
+        .structures
+        .terminal
+        .as_ref()
+        .map_or(0, |t| t.store().get_used_capacity(Some(resource)));
+
+    let (source_id, source_type, available) = match (&room_cache.structures.terminal, &room_cache.structures.storage) {
+        (Some(terminal), _) if terminal_amount > storage_amount => (terminal.raw_id(), terminal.structure_type(), terminal_amount),
+        (_, Some(storage)) => (storage.raw_id(), storage.structure_type(), storage_amount),
+        _ => return 0,
+    };
+
+    let amount = amount.min(available);
+    if amount == 0 {
+        return 0;
+    }
+
+    room_cache.hauling.create_order(
+        source_id,
+        Some(source_type),
+        Some(resource),
+        Some(amount),
+        priority,
+        HaulingType::Withdraw,
+    );
+
+    amount
+}
+
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn haul_spawn(room_cache: &mut CachedRoom) {
     let has_ff = room_cache
@@ -819,3 +1118,67 @@ pub fn haul_extensions(room_cache: &mut CachedRoom) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total_cost(costs: &[Vec<f64>], assignment: &[Option<usize>]) -> f64 {
+        assignment
+            .iter()
+            .enumerate()
+            .filter_map(|(row, column)| Some(costs[row][(*column)?]))
+            .sum()
+    }
+
+    #[test]
+    fn beats_greedy() {
+        // Greedy hands the first hauler its cheapest slot and leaves the second with a terrible one.
+        let costs = vec![vec![1.0, 2.0], vec![2.0, 100.0]];
+
+        let assignment = min_cost_assignment(&costs);
+
+        assert_eq!(assignment, vec![Some(1), Some(0)]);
+        assert!(total_cost(&costs, &assignment) < total_cost(&costs, &greedy_assignment(&costs)));
+    }
+
+    #[test]
+    fn more_slots_than_haulers() {
+        let costs = vec![
+            vec![5.0, 1.0, UNMATCHABLE, UNMATCHABLE],
+            vec![UNMATCHABLE, UNMATCHABLE, UNMATCHABLE, UNMATCHABLE],
+            vec![1.0, UNMATCHABLE, 3.0, UNMATCHABLE],
+        ];
+
+        let assignment = min_cost_assignment(&costs);
+
+        assert_eq!(assignment[0], Some(1));
+        assert_eq!(assignment[2], Some(0));
+        // The hauler nothing suits still lands somewhere, but only on a slot the caller throws away.
+        assert!(costs[1][assignment[1].unwrap()] >= UNMATCHABLE);
+    }
+
+    #[test]
+    fn padded_slots_go_unmatched() {
+        // One real slot, padded out to a column per hauler like match_haulers does.
+        let costs = vec![
+            vec![4.0, UNMATCHABLE, UNMATCHABLE],
+            vec![2.0, UNMATCHABLE, UNMATCHABLE],
+            vec![3.0, UNMATCHABLE, UNMATCHABLE],
+        ];
+
+        let assignment = min_cost_assignment(&costs);
+        let matched = assignment
+            .iter()
+            .enumerate()
+            .filter(|(row, column)| column.is_some_and(|column| costs[*row][column] < UNMATCHABLE))
+            .collect::<Vec<_>>();
+
+        assert_eq!(matched, vec![(1, &Some(0))]);
+    }
+
+    #[test]
+    fn no_haulers() {
+        assert!(min_cost_assignment(&[]).is_empty());
+    }
+}
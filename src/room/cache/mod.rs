@@ -111,6 +111,11 @@ pub struct CachedRoom {
     pub room_heap_cache: HeapRoom,
     pub stats: StatsCache,
 
+    // How much damage each tile is going to take from incoming nukes, and when the first one lands.
+    pub nuke_damage: HashMap<RoomXY, (u32, u32)>,
+    // Ramparts we can get up in time, and the hits they need.
+    pub nuke_ramparts: HashMap<RoomXY, u32>,
+
     pub _creep_checked_relay: HashMap<String, bool>,
 }
 
@@ -167,6 +172,9 @@ impl CachedRoom {
             room_heap_cache: room_heap,
             stats,
 
+            nuke_damage: HashMap::new(),
+            nuke_ramparts: HashMap::new(),
+
             storage_status,
             _creep_checked_relay: HashMap::new(),
         };
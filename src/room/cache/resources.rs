@@ -11,6 +11,7 @@ use screeps::ScoreContainer;
 use crate::{
     heap_cache::heap_room::HeapRoom,
     memory::{Role, ScreepsMemory},
+    room::source_keepers,
     traits::position::PositionExtensions,
     utils::{self, scale_haul_priority},
 };
@@ -33,6 +34,8 @@ pub struct CachedSource {
     pub csites: Vec<ConstructionSite>,
 
     lowest_ttl: u32,
+    // How long the creep with the lowest TTL took to get here, if it has made it yet.
+    lowest_ttl_travel_time: Option<u32>,
 }
 
 #[derive(Debug, Clone)]
@@ -149,6 +152,7 @@ impl RoomResourceCache {
                 csites,
 
                 lowest_ttl: u32::MAX,
+                lowest_ttl_travel_time: None,
             };
 
             self.sources.push(constructed_source);
@@ -204,12 +208,15 @@ impl CachedSource {
     }
 
     pub fn can_replace_creep(&self, dist: Position, room: &Room) -> bool {
-        let range_to_source = self.source.pos().get_range_to(dist);
+        // Fall back on the straight line distance until the creep has made the trip once.
+        let travel_time = self
+            .lowest_ttl_travel_time
+            .unwrap_or_else(|| self.source.pos().get_range_to(dist));
         let max_parts = self.max_work_parts;
         let spawn_time = 3 * max_parts as u32;
         let lowest_ttl = self.lowest_ttl;
 
-        if (range_to_source + spawn_time > lowest_ttl) && self.work_part_count <= max_parts {
+        if (travel_time + spawn_time > lowest_ttl) && self.work_part_count <= max_parts {
             if self.creeps.len() > self.calculate_mining_spots(room).into() {
                 return false;
             }
@@ -237,7 +244,7 @@ impl CachedSource {
         available_spots
     }
 
-    pub fn add_creep(&mut self, creep: &Creep) {
+    pub fn add_creep(&mut self, creep: &Creep, travel_time: Option<u32>) {
         self.work_part_count += creep
             .body()
             .iter()
@@ -248,6 +255,7 @@ impl CachedSource {
         if let Some(ttl) = creep.ticks_to_live() {
             if ttl < self.lowest_ttl {
                 self.lowest_ttl = ttl;
+                self.lowest_ttl_travel_time = travel_time;
             }
         }
     }
@@ -334,6 +342,11 @@ pub fn haul_remotes(launching_room: &Room, memory: &mut ScreepsMemory, cache: &m
         let owning_room = cache.rooms.get_mut(&launching_room.name()).unwrap();
 
         for resource in &cached_room.resources.dropped_energy {
+            // Dont send haulers into a keeper.
+            if source_keepers::keeper_threat(&cached_room, resource.pos()).is_some() {
+                continue;
+            }
+
             let amount = resource.amount();
 
             owning_room.stats.energy.dropped += amount;
@@ -358,6 +371,10 @@ pub fn haul_remotes(launching_room: &Room, memory: &mut ScreepsMemory, cache: &m
             .as_ref()
             .unwrap()
         {
+            if source_keepers::keeper_threat(&cached_room, container.pos()).is_some() {
+                continue;
+            }
+
             owning_room.resources.total_energy += container.store().get_used_capacity(None);
             owning_room.resources.energy_in_storing_structures +=
                 container.store().get_used_capacity(None);
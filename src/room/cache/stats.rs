@@ -24,8 +24,13 @@ pub struct StatsCache {
     pub cpu_hauling_orders: f64,
     pub cpu_links: f64,
     pub cpu_towers: f64,
+    pub cpu_labs: f64,
+    pub cpu_factory: f64,
+    pub cpu_power: f64,
     pub cpu_remotes: f64,
 
+    pub income_credits: f64,
+
     pub energy: EnergyStats,
 }
 
@@ -53,6 +58,9 @@ pub struct EnergyStats {
     pub spending_upgrading: u32,
     pub spending_construction: u32,
     pub spending_repair: u32,
+    pub spending_power: u32,
+
+    pub processed_power: u32,
 }
 
 impl StatsCache {
@@ -94,6 +102,7 @@ impl StatsCache {
             room_stats.economy.income_minerals = self.energy.income_minerals;
             room_stats.economy.income_trading = self.energy.income_trading;
             room_stats.economy.income_other = self.energy.income_other;
+            room_stats.economy.income_credits = self.income_credits;
 
             room_stats.economy.deposited_energy = self.energy.deposited_energy;
             room_stats.economy.deposited_minerals = self.energy.deposited_minerals;
@@ -102,6 +111,9 @@ impl StatsCache {
             room_stats.economy.spending_upgrading = self.energy.spending_upgrading;
             room_stats.economy.spending_construction = self.energy.spending_construction;
             room_stats.economy.spending_repair = self.energy.spending_repair;
+            room_stats.economy.spending_power = self.energy.spending_power;
+
+            room_stats.economy.processed_power = self.energy.processed_power;
 
             room_stats.cpu_used = cpu_used;
             room_stats.cpu.cache = self.cpu_cache;
@@ -110,6 +122,9 @@ impl StatsCache {
             room_stats.cpu.hauling_orders = self.cpu_hauling_orders;
             room_stats.cpu.links = self.cpu_links;
             room_stats.cpu.towers = self.cpu_towers;
+            room_stats.cpu.labs = self.cpu_labs;
+            room_stats.cpu.factory = self.cpu_factory;
+            room_stats.cpu.power = self.cpu_power;
 
             room_stats.cpu_usage_by_role.clone_from(&self.cpu_usage_by_role);
             room_stats.creeps_by_role.clone_from(&self.creeps_by_role);
@@ -129,6 +144,7 @@ impl StatsCache {
                 income_power: self.energy.income_power,
                 income_trading: self.energy.income_trading,
                 income_other: self.energy.income_other,
+                income_credits: self.income_credits,
 
                 deposited_energy: self.energy.deposited_energy,
                 deposited_minerals: self.energy.deposited_minerals,
@@ -137,6 +153,9 @@ impl StatsCache {
                 spending_upgrading: self.energy.spending_upgrading,
                 spending_construction: self.energy.spending_construction,
                 spending_repair: self.energy.spending_repair,
+                spending_power: self.energy.spending_power,
+
+                processed_power: self.energy.processed_power,
             };
 
             let cpu_stats = RoomCPUStats {
@@ -146,6 +165,9 @@ impl StatsCache {
                 links: self.cpu_links,
                 remotes: self.cpu_remotes,
                 towers: self.cpu_towers,
+                labs: self.cpu_labs,
+                factory: self.cpu_factory,
+                power: self.cpu_power,
             };
 
             let stats = RoomStats {
@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
 
 use log::info;
 use screeps::{
@@ -46,6 +49,9 @@ pub struct CachedRoomLinks {
     pub fast_filler: Option<StructureLink>,
     pub source: Option<Vec<StructureLink>>,
     pub storage: Option<StructureLink>,
+    pub lab: Option<StructureLink>,
+    // Anything we couldnt place, like links on the room edge for remote haulers.
+    pub other: Option<Vec<StructureLink>>,
 }
 
 impl CachedRoomLinks {
@@ -55,6 +61,8 @@ impl CachedRoomLinks {
             fast_filler: None,
             source: None,
             storage: None,
+            lab: None,
+            other: None,
         }
     }
 }
@@ -95,6 +103,9 @@ pub struct RoomStructureCache {
 
     pub structures_at_pos: HashMap<RoomXY, Vec<StructureType>>,
     pub csites_at_pos: HashMap<RoomXY, Vec<StructureType>>,
+    // Every structure in the room, ours or not, hashed by where it is and what it is.
+    // Cheap way to tell when something got built or lost, even both in the same tick.
+    pub structure_hash: u64,
 
     pub construction_sites: Vec<ConstructionSite>,
     pub inactive_structures: Vec<StructureObject>,
@@ -147,6 +158,7 @@ impl RoomStructureCache {
 
             structures_at_pos: HashMap::new(),
             csites_at_pos: HashMap::new(),
+            structure_hash: 0,
 
             extensions: HashMap::new(),
             tombstones: None,
@@ -549,11 +561,19 @@ impl RoomStructureCache {
 
         // TODO:
         // Roads decay every 1k ticks, and containers every 500 (100 in remotes), so we can probably cut down what we are iterating
+        self.structure_hash = 0;
         for structure in all_structures {
             let ty = structure.structure_type();
+            let xy = structure.pos().xy();
+
+            // Added up so the order find gives them back in doesnt matter.
+            let mut hasher = DefaultHasher::new();
+            (xy, ty).hash(&mut hasher);
+            self.structure_hash = self.structure_hash.wrapping_add(hasher.finish());
+
             let entry = self
                 .structures_at_pos
-                .entry(structure.pos().xy())
+                .entry(xy)
                 .or_default();
             if !entry.contains(&ty) {
                 entry.push(ty);
@@ -622,7 +642,9 @@ impl RoomStructureCache {
         let mut controller = None;
         let mut fast_filler = None;
         let mut storage = None;
+        let mut lab = None;
         let mut link_sources = Vec::new();
+        let mut other_links = Vec::new();
 
         // TODO:
         // Do I use a find call?
@@ -634,21 +656,26 @@ impl RoomStructureCache {
         //let sources = sources.iter().map(|s| game::get_object_by_id_typed(s).unwrap()).collect::<Vec<Source>>();
 
         for link in self.links.values() {
+            let mut classified = false;
+
             if let Some(room_controller) = &self.controller {
                 if link.pos().in_range_to(room_controller.pos(), 2) {
                     controller = Some(link.clone());
+                    classified = true;
                 }
             }
 
             if let Some(spawn) = self.spawns.values().next() {
                 if link.pos().in_range_to(spawn.pos(), 1) {
-                    fast_filler = Some(link.clone())
+                    fast_filler = Some(link.clone());
+                    classified = true;
                 }
             }
 
             if let Some(sstorage) = &self.storage {
                 if link.pos().in_range_to(sstorage.pos(), 2) {
                     storage = Some(link.clone());
+                    classified = true;
                 }
             }
 
@@ -656,8 +683,19 @@ impl RoomStructureCache {
                 if link.pos().in_range_to(source.source.pos(), 2) {
                     source.link = Some(link.clone());
                     link_sources.push(link.clone());
+                    classified = true;
                 }
             }
+
+            if classified {
+                continue;
+            }
+
+            if self.labs.values().any(|room_lab| link.pos().in_range_to(room_lab.pos(), 2)) {
+                lab = Some(link.clone());
+            } else {
+                other_links.push(link.clone());
+            }
         }
 
         let link_sources = if link_sources.is_empty() {
@@ -666,11 +704,19 @@ impl RoomStructureCache {
             Some(link_sources)
         };
 
+        let other_links = if other_links.is_empty() {
+            None
+        } else {
+            Some(other_links)
+        };
+
         let classified = CachedRoomLinks {
             controller,
             fast_filler,
             source: link_sources,
             storage,
+            lab,
+            other: other_links,
         };
 
         self.classified_links = Some(classified);
@@ -1,6 +1,23 @@
 use std::collections::HashMap;
 
-use screeps::{ResourceType, RoomName};
+use log::info;
+use screeps::{HasId, ResourceType, RoomName, StructureProperties};
+
+use crate::{
+    config::{
+        LAB_REAGENT_MINIMUM, TERMINAL_COMPOUND_NEED, TERMINAL_COMPOUND_OFFER, TERMINAL_ENERGY_NEED,
+        TERMINAL_ENERGY_OFFER, TERMINAL_MAX_SEND, TERMINAL_MINERAL_NEED, TERMINAL_MINERAL_OFFER,
+        TERMINAL_STAGE_AMOUNT,
+    },
+    constants::{BASE_MINERALS, ITERABLE_RESOURCES, LAB_REACTION_PRIORITY},
+    traits::intents_tracking::StructureTerminalExtensionsTracking,
+    utils::{self, get_room_stock},
+};
+
+use super::{
+    hauling::{haul_from_stock, HaulingPriority, HaulingType},
+    CachedRoom, RoomCache,
+};
 
 pub struct TerminalCache {
     pub needs_by_resource: HashMap<ResourceType, Vec<RoomName>>,
@@ -8,6 +25,9 @@ pub struct TerminalCache {
 
     pub offers_by_resource: HashMap<ResourceType, Vec<RoomName>>,
     pub offers_by_room: HashMap<RoomName, Vec<ResourceType>>,
+
+    // Terminals that already sent or dealt this tick.
+    pub busy_terminals: Vec<RoomName>,
 }
 
 impl TerminalCache {
@@ -18,6 +38,8 @@ impl TerminalCache {
 
             offers_by_resource: HashMap::new(),
             offers_by_room: HashMap::new(),
+
+            busy_terminals: Vec::new(),
         }
     }
 
@@ -64,4 +86,185 @@ impl TerminalCache {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+// What a room wants to have on hand, below the first it asks the rest of the empire,
+// above the second it offers the extra up.
+fn resource_targets(room_cache: &CachedRoom, resource: ResourceType) -> Option<(u32, u32)> {
+    if resource == ResourceType::Energy {
+        return Some((TERMINAL_ENERGY_NEED, TERMINAL_ENERGY_OFFER));
+    }
+
+    if BASE_MINERALS.contains(&resource) {
+        // Rooms without labs dont have a use for them, so never ask.
+        if room_cache.structures.labs.len() < 3 {
+            return Some((0, TERMINAL_MINERAL_OFFER));
+        }
+
+        return Some((TERMINAL_MINERAL_NEED, TERMINAL_MINERAL_OFFER));
+    }
+
+    if resource.reaction_components().is_some() {
+        // Nothing to react or boost with, so its all spare.
+        if room_cache.structures.labs.is_empty() {
+            return Some((0, 0));
+        }
+
+        // Reagents feed the next tier up, ask for enough to get a reaction going.
+        let reagent = LAB_REACTION_PRIORITY
+            .iter()
+            .any(|compound| compound.reaction_components().is_some_and(|components| components.contains(&resource)));
+
+        if reagent {
+            if room_cache.structures.labs.len() < 3 {
+                return Some((0, TERMINAL_COMPOUND_OFFER));
+            }
+
+            return Some((LAB_REAGENT_MINIMUM, TERMINAL_COMPOUND_OFFER));
+        }
+
+        // Boosts, any room with a lab can use them.
+        return Some((TERMINAL_COMPOUND_NEED, TERMINAL_COMPOUND_OFFER));
+    }
+
+    None
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn declare_terminal_needs(room_cache: &mut CachedRoom, terminals: &mut TerminalCache) {
+    let terminal = if let Some(terminal) = &room_cache.structures.terminal {
+        terminal.clone()
+    } else {
+        return;
+    };
+
+    let room_name = room_cache.room.name();
+
+    let mut needs = Vec::new();
+    let mut offers = Vec::new();
+
+    for resource in ITERABLE_RESOURCES {
+        let (need, offer) = if let Some(targets) = resource_targets(room_cache, resource) {
+            targets
+        } else {
+            continue;
+        };
+
+        let stock = get_room_stock(room_cache, resource);
+
+        if stock < need {
+            needs.push(resource);
+        } else if stock > offer {
+            offers.push(resource);
+
+            // Get some of it into the terminal, so theres something to send.
+            let in_terminal = terminal.store().get_used_capacity(Some(resource));
+            let in_storage = room_cache
+                .structures
+                .storage
+                .as_ref()
+                .map_or(0, |s| s.store().get_used_capacity(Some(resource)));
+
+            if in_terminal < TERMINAL_STAGE_AMOUNT && in_storage > in_terminal {
+                let wanted = (TERMINAL_STAGE_AMOUNT - in_terminal).min(stock - offer);
+                let priority = HaulingPriority::Minerals as u32 as f32;
+
+                let amount = haul_from_stock(room_cache, resource, wanted, priority);
+                if amount > 0 {
+                    room_cache.hauling.create_order(
+                        terminal.raw_id(),
+                        Some(terminal.structure_type()),
+                        Some(resource),
+                        Some(amount),
+                        priority,
+                        HaulingType::Transfer,
+                    );
+                }
+            }
+        }
+    }
+
+    if !needs.is_empty() {
+        terminals.mark_needs_resource(room_name, needs);
+    }
+
+    if !offers.is_empty() {
+        terminals.mark_offer_resource(room_name, offers);
+    }
+}
+
+// Match up the needs and offers declared this tick, cheapest send first.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_terminal_network(cache: &mut RoomCache) {
+    let needs_by_resource = cache.terminals.needs_by_resource.clone();
+
+    for (resource, needers) in needs_by_resource {
+        let offerers = if let Some(offerers) = cache.terminals.offers_by_resource.get(&resource) {
+            offerers.clone()
+        } else {
+            continue;
+        };
+
+        for needer in needers {
+            let (need, _) = if let Some(targets) = cache.rooms.get(&needer).and_then(|room_cache| resource_targets(room_cache, resource)) {
+                targets
+            } else {
+                continue;
+            };
+
+            let missing = need.saturating_sub(get_room_stock(&cache.rooms[&needer], resource));
+
+            let best = offerers
+                .iter()
+                .filter(|offerer| **offerer != needer && !cache.terminals.busy_terminals.contains(offerer))
+                .filter_map(|offerer| {
+                    let room_cache = cache.rooms.get(offerer)?;
+                    let terminal = room_cache.structures.terminal.as_ref()?;
+
+                    if terminal.cooldown() > 0 {
+                        return None;
+                    }
+
+                    let (_, offer) = resource_targets(room_cache, resource)?;
+                    let surplus = get_room_stock(room_cache, resource).saturating_sub(offer);
+
+                    let terminal_energy = terminal.store().get_used_capacity(Some(ResourceType::Energy));
+                    let mut amount = missing
+                        .min(surplus)
+                        .min(terminal.store().get_used_capacity(Some(resource)))
+                        .min(TERMINAL_MAX_SEND);
+
+                    let mut cost = utils::calc_terminal_cost(amount, offerer, &needer);
+
+                    // When sending energy, the fee comes out of the same pile.
+                    if resource == ResourceType::Energy && amount + cost > terminal_energy {
+                        amount = amount.saturating_sub(amount + cost - terminal_energy);
+                        cost = utils::calc_terminal_cost(amount, offerer, &needer);
+                    }
+
+                    if amount == 0 || cost > terminal_energy {
+                        return None;
+                    }
+
+                    Some((*offerer, terminal.clone(), amount, cost))
+                })
+                .min_by(|(_, _, a_amount, a_cost), (_, _, b_amount, b_cost)| {
+                    let a = *a_cost as f32 / *a_amount as f32;
+                    let b = *b_cost as f32 / *b_amount as f32;
+
+                    a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+                });
+
+            if let Some((offerer, terminal, amount, cost)) = best {
+                if terminal.ITsend(resource, amount, needer, None).is_ok() {
+                    info!(
+                        "  [TERMINALS] Sent {} {:?} from {} to {} for {} energy",
+                        amount, resource, offerer, needer, cost
+                    );
+
+                    cache.terminals.busy_terminals.push(offerer);
+                }
+            }
+        }
+    }
+}
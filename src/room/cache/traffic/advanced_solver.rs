@@ -1,13 +1,13 @@
 use std::collections::HashMap;
 
-use screeps::{game, Creep, HasPosition, MaybeHasId, ObjectId};
+use screeps::RawObjectId;
 
-use crate::{room::cache::CachedRoom, traits::creep::CreepExtensions};
+use crate::room::cache::CachedRoom;
 
-use super::assign_creep_to_coordinate;
+use super::{assign_creep_to_coordinate, current_coord, possible_moves};
 
-pub fn solve_traffic_advanced(creeps_with_movement_intent: &Vec<ObjectId<Creep>>, room_cache: &mut CachedRoom) {
-    let mut visited_creeps: HashMap<ObjectId<Creep>, bool> = HashMap::new();
+pub fn solve_traffic_advanced(creeps_with_movement_intent: &Vec<RawObjectId>, room_cache: &mut CachedRoom) {
+    let mut visited_creeps: HashMap<RawObjectId, bool> = HashMap::new();
 
     loop {
         let mut found = false;
@@ -24,14 +24,13 @@ pub fn solve_traffic_advanced(creeps_with_movement_intent: &Vec<ObjectId<Creep>>
             }
             room_cache.traffic.matched_coord.remove(creep_id);
 
-            let creep = game::get_object_by_id_typed(creep_id).unwrap();
-
-            if depth_first_search(&creep, room_cache, &mut visited_creeps, Some(0)) > 0 {
+            if depth_first_search(*creep_id, room_cache, &mut visited_creeps, Some(0)) > 0 {
                 found = true;
                 continue;
             }
 
-            assign_creep_to_coordinate(&creep, room_cache, creep.pos().xy());
+            let coord = current_coord(creep_id, room_cache);
+            assign_creep_to_coordinate(*creep_id, room_cache, coord);
         }
 
         if !found {
@@ -40,11 +39,10 @@ pub fn solve_traffic_advanced(creeps_with_movement_intent: &Vec<ObjectId<Creep>>
     }
 }
 
-fn depth_first_search(creep: &Creep, room_cache: &mut CachedRoom, visited: &mut HashMap<ObjectId<Creep>, bool>, current_score: Option<i64>) -> i64 {
-    let creep_id = creep.try_id().unwrap();
+fn depth_first_search(creep_id: RawObjectId, room_cache: &mut CachedRoom, visited: &mut HashMap<RawObjectId, bool>, current_score: Option<i64>) -> i64 {
     *visited.entry(creep_id).or_insert(true) = true;
 
-    let possible = creep.get_possible_moves_traffic(room_cache);
+    let possible = possible_moves(&creep_id, room_cache);
 
     for coord in possible {
         let mut score = current_score.unwrap_or(0);
@@ -57,7 +55,7 @@ fn depth_first_search(creep: &Creep, room_cache: &mut CachedRoom, visited: &mut
 
         if occupying.is_none() {
             if score > 0 {
-                assign_creep_to_coordinate(creep, room_cache, coord);
+                assign_creep_to_coordinate(creep_id, room_cache, coord);
             }
 
             return score;
@@ -69,9 +67,9 @@ fn depth_first_search(creep: &Creep, room_cache: &mut CachedRoom, visited: &mut
                 score -= 1;
             }
 
-            let result = depth_first_search(&game::get_object_by_id_typed(&occupying).unwrap(), room_cache, visited, Some(score));
+            let result = depth_first_search(occupying, room_cache, visited, Some(score));
             if result > 0 {
-                assign_creep_to_coordinate(creep, room_cache, coord);
+                assign_creep_to_coordinate(creep_id, room_cache, coord);
                 return result;
             }
         }
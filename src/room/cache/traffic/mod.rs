@@ -1,6 +1,6 @@
-use screeps::{game, Creep, HasPosition, MaybeHasId, ObjectId, Position, RoomCoordinate, RoomXY, SharedCreepProperties};
+use screeps::{game, Creep, HasPosition, MaybeHasId, ObjectId, Position, RawObjectId, RoomCoordinate, RoomXY, SharedCreepProperties, Terrain};
 
-use crate::{heap, memory::ScreepsMemory, traits::intents_tracking::CreepExtensionsTracking};
+use crate::{heap, memory::ScreepsMemory, traits::{creep::CreepExtensions, intents_tracking::{CreepExtensionsTracking, PowerCreepExtensionsTracking}}};
 
 use super::CachedRoom;
 
@@ -26,7 +26,7 @@ pub fn run_movement(room_cache: &mut CachedRoom, memory: &mut ScreepsMemory) ->
 
     let creep_names: Vec<String> = room_cache.creeps.creeps_in_room.keys().cloned().collect();
     // Just save some CPU, not much, but CPU is CPU
-    if creep_names.is_empty() { return 0; }
+    if creep_names.is_empty() && room_cache.traffic.power_creeps.is_empty() { return 0; }
 
     assign_coordinates(&creep_names, room_cache, &mut creeps_with_movement_intent);
     if creeps_with_movement_intent.is_empty() { return 0; }
@@ -50,7 +50,19 @@ fn run_non_room_traffic(room_cache: &mut CachedRoom) -> u32 {
     let mut i = 0;
 
     for (creep, matched_coord) in room_cache.traffic.intended_move.clone() {
-        let creep = game::get_object_by_id_typed(&creep).unwrap();
+        if let Some(power_creep) = room_cache.traffic.power_creeps.get(&creep) {
+            let position = Position::new(matched_coord.x, matched_coord.y, room_cache.room.name());
+
+            if let Some(direction) = power_creep.pos().get_direction_to(position) {
+                if power_creep.ITmove_direction(direction).is_ok() {
+                    i += 1;
+                }
+            }
+
+            continue;
+        }
+
+        let creep = game::get_object_by_id_typed(&ObjectId::<Creep>::from(creep)).unwrap();
 
         if matched_coord == creep.pos().xy() {
             continue;
@@ -83,7 +95,7 @@ fn run_non_room_traffic(room_cache: &mut CachedRoom) -> u32 {
 fn move_creeps(creep_names: &Vec<String>, room_cache: &mut CachedRoom) {
     for creep_name in creep_names {
         let creep = game::creeps().get(creep_name.to_string()).unwrap();
-        let matched_coord = room_cache.traffic.matched_coord.get(&creep.try_id().unwrap());
+        let matched_coord = room_cache.traffic.matched_coord.get(&creep.try_raw_id().unwrap());
 
         if matched_coord.is_none() || *matched_coord.unwrap() == creep.pos().xy() {
             continue;
@@ -110,25 +122,85 @@ fn move_creeps(creep_names: &Vec<String>, room_cache: &mut CachedRoom) {
             }
         }
     }
+
+    for (id, power_creep) in room_cache.traffic.power_creeps.clone() {
+        let matched_coord = room_cache.traffic.matched_coord.get(&id);
+
+        if matched_coord.is_none() || *matched_coord.unwrap() == power_creep.pos().xy() {
+            continue;
+        }
+
+        let position = Position::new(matched_coord.unwrap().x, matched_coord.unwrap().y, room_cache.room.name());
+
+        if let Some(direction) = power_creep.pos().get_direction_to(position) {
+            if power_creep.ITmove_direction(direction).is_ok() {
+                room_cache.traffic.move_intents += 1;
+            }
+        }
+    }
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
-fn assign_coordinates(creep_names: &Vec<String>, room_cache: &mut CachedRoom, creeps_with_movement_intent: &mut Vec<ObjectId<Creep>>) {
+fn assign_coordinates(creep_names: &Vec<String>, room_cache: &mut CachedRoom, creeps_with_movement_intent: &mut Vec<RawObjectId>) {
     for creep_name in creep_names {
         let creep = game::creeps().get(creep_name.to_string()).unwrap();
 
-        assign_creep_to_coordinate(&creep, room_cache, creep.pos().into());
+        assign_creep_to_coordinate(creep.try_raw_id().unwrap(), room_cache, creep.pos().into());
+
+        if room_cache.traffic.intended_move.contains_key(&creep.try_raw_id().unwrap()) {
+            creeps_with_movement_intent.push(creep.try_raw_id().unwrap());
+        }
+    }
+
+    for (id, power_creep) in room_cache.traffic.power_creeps.clone() {
+        assign_creep_to_coordinate(id, room_cache, power_creep.pos().xy());
 
-        if room_cache.traffic.intended_move.contains_key(&creep.try_id().unwrap()) {
-            creeps_with_movement_intent.push(creep.try_id().unwrap());
+        if room_cache.traffic.intended_move.contains_key(&id) {
+            creeps_with_movement_intent.push(id);
         }
     }
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
-pub fn assign_creep_to_coordinate(creep: &Creep, room_cache: &mut CachedRoom, coord: RoomXY) {
+pub fn assign_creep_to_coordinate(creep: RawObjectId, room_cache: &mut CachedRoom, coord: RoomXY) {
     let packed_coord = coord;
 
-    room_cache.traffic.matched_coord.insert(creep.try_id().unwrap(), packed_coord);
-    room_cache.traffic.movement_map.insert(packed_coord, creep.try_id().unwrap());
-}
\ No newline at end of file
+    room_cache.traffic.matched_coord.insert(creep, packed_coord);
+    room_cache.traffic.movement_map.insert(packed_coord, creep);
+}
+
+pub fn current_coord(id: &RawObjectId, room_cache: &CachedRoom) -> RoomXY {
+    if let Some(power_creep) = room_cache.traffic.power_creeps.get(id) {
+        return power_creep.pos().xy();
+    }
+
+    game::get_object_by_id_typed(&ObjectId::<Creep>::from(*id)).unwrap().pos().xy()
+}
+
+pub fn possible_moves(id: &RawObjectId, room_cache: &mut CachedRoom) -> Vec<RoomXY> {
+    if room_cache.traffic.power_creeps.contains_key(id) {
+        return power_creep_possible_moves(id, room_cache);
+    }
+
+    game::get_object_by_id_typed(&ObjectId::<Creep>::from(*id)).unwrap().get_possible_moves_traffic(room_cache)
+}
+
+// Power creeps never get tired, but they are usually stood in range of something they are powering,
+// so they only ever go where they asked to or stay put. Creeps can still swap with them.
+fn power_creep_possible_moves(id: &RawObjectId, room_cache: &mut CachedRoom) -> Vec<RoomXY> {
+    if let Some(cached) = room_cache.traffic.cached_ops.get(id) {
+        return cached.clone();
+    }
+
+    let mut possible_moves = Vec::new();
+
+    if let Some(intended) = room_cache.traffic.intended_move.get(id) {
+        if room_cache.structures.terrain.get_xy(*intended) != Terrain::Wall {
+            possible_moves.push(*intended);
+        }
+    }
+    possible_moves.push(room_cache.traffic.power_creeps[id].pos().xy());
+
+    room_cache.traffic.cached_ops.insert(*id, possible_moves.clone());
+    possible_moves
+}
@@ -1,17 +1,13 @@
 #![allow(non_snake_case)]use std::collections::HashMap;
 
-use screeps::{
-    game, Creep, HasPosition, MaybeHasId, ObjectId
-};
+use screeps::RawObjectId;
 
-use super::{assign_creep_to_coordinate, CachedRoom};
-use crate::traits::creep::CreepExtensions;
+use super::{assign_creep_to_coordinate, current_coord, possible_moves, CachedRoom};
 
-pub fn solve_traffic_simple(creeps_with_movement_intent: &Vec<ObjectId<Creep>>, room_cache: &mut CachedRoom) {
+pub fn solve_traffic_simple(creeps_with_movement_intent: &Vec<RawObjectId>, room_cache: &mut CachedRoom) {
     let mut visited_creeps = HashMap::new();
 
     for creep_id in creeps_with_movement_intent {
-        let creep = game::get_object_by_id_typed(creep_id).unwrap();
         if room_cache.traffic.matched_coord.get(creep_id) == room_cache.traffic.intended_move.get(creep_id) {
             continue;
         }
@@ -23,19 +19,20 @@ pub fn solve_traffic_simple(creeps_with_movement_intent: &Vec<ObjectId<Creep>>,
         }
         room_cache.traffic.matched_coord.remove(creep_id);
 
-        if depth_first_searh(&creep, room_cache, &mut visited_creeps, Some(0)) > 0 {
+        if depth_first_searh(*creep_id, room_cache, &mut visited_creeps, Some(0)) > 0 {
             continue;
         }
 
-        assign_creep_to_coordinate(&creep, room_cache, creep.pos().xy());
+        let coord = current_coord(creep_id, room_cache);
+        assign_creep_to_coordinate(*creep_id, room_cache, coord);
     }
 }
 
-fn depth_first_searh(creep: &Creep, room_cache: &mut CachedRoom, visited_creeps: &mut HashMap<ObjectId<Creep>, bool>, score: Option<i32>) -> i32 {
+fn depth_first_searh(creep_id: RawObjectId, room_cache: &mut CachedRoom, visited_creeps: &mut HashMap<RawObjectId, bool>, score: Option<i32>) -> i32 {
     let mut score = score.unwrap_or(0);
-    *visited_creeps.entry(creep.try_id().unwrap()).or_insert(true) = true;
+    *visited_creeps.entry(creep_id).or_insert(true) = true;
 
-    let possible_moves = creep.get_possible_moves_traffic(room_cache);
+    let possible_moves = possible_moves(&creep_id, room_cache);
 
     let mut empty_tiles = Vec::new();
     let mut occupied_tiles = Vec::new();
@@ -49,7 +46,7 @@ fn depth_first_searh(creep: &Creep, room_cache: &mut CachedRoom, visited_creeps:
     }
 
     for coord in possible_moves {
-        if room_cache.traffic.intended_move.contains_key(&creep.try_id().unwrap()) && *room_cache.traffic.intended_move.get(&creep.try_id().unwrap()).unwrap() == coord {
+        if room_cache.traffic.intended_move.contains_key(&creep_id) && *room_cache.traffic.intended_move.get(&creep_id).unwrap() == coord {
             score += 1;
         }
 
@@ -57,7 +54,7 @@ fn depth_first_searh(creep: &Creep, room_cache: &mut CachedRoom, visited_creeps:
 
         if occupying.is_none() {
             if score > 0 {
-                assign_creep_to_coordinate(creep, room_cache, coord)
+                assign_creep_to_coordinate(creep_id, room_cache, coord)
             }
             return score;
         }
@@ -67,10 +64,10 @@ fn depth_first_searh(creep: &Creep, room_cache: &mut CachedRoom, visited_creeps:
                 score -= 1;
             }
 
-            let result = depth_first_searh(&game::get_object_by_id_typed(occupying.unwrap()).unwrap(), room_cache, visited_creeps, Some(score));
+            let result = depth_first_searh(*occupying.unwrap(), room_cache, visited_creeps, Some(score));
 
             if result > 0 {
-                assign_creep_to_coordinate(creep, room_cache, coord);
+                assign_creep_to_coordinate(creep_id, room_cache, coord);
                 return result;
             }
         }
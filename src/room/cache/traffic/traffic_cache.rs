@@ -1,18 +1,21 @@
 use std::collections::HashMap;
 
-use screeps::{Creep, ObjectId, Position, RoomXY};
+use screeps::{Position, PowerCreep, RawObjectId, RoomXY};
 
 #[derive(Debug, Clone)]
 pub struct TrafficCache {
-    pub matched_coord: HashMap<ObjectId<Creep>, RoomXY>,
-    pub intended_move: HashMap<ObjectId<Creep>, RoomXY>,
+    pub matched_coord: HashMap<RawObjectId, RoomXY>,
+    pub intended_move: HashMap<RawObjectId, RoomXY>,
 
-    pub movement_map: HashMap<RoomXY, ObjectId<Creep>>,
+    pub movement_map: HashMap<RoomXY, RawObjectId>,
 
-    pub working_areas: HashMap<ObjectId<Creep>, (Position, u8)>,
+    pub working_areas: HashMap<RawObjectId, (Position, u8)>,
 
-    pub cached_ops: HashMap<ObjectId<Creep>, Vec<RoomXY>>,
+    pub cached_ops: HashMap<RawObjectId, Vec<RoomXY>>,
     pub move_intents: u8,
+
+    // Power creeps go through the solver same as creeps, this is just so it can tell them apart.
+    pub power_creeps: HashMap<RawObjectId, PowerCreep>,
 }
 
 impl TrafficCache {
@@ -24,6 +27,8 @@ impl TrafficCache {
             cached_ops: HashMap::new(),
             working_areas: HashMap::new(),
             move_intents: 0,
+
+            power_creeps: HashMap::new(),
         }
     }
 }
\ No newline at end of file
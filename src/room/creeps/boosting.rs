@@ -0,0 +1,79 @@
+use screeps::{Creep, HasPosition, ResourceType, SharedCreepProperties, LAB_BOOST_ENERGY, LAB_BOOST_MINERAL};
+
+use crate::{
+    constants::CREEP_LIFETIME, memory::ScreepsMemory, movement::move_target::MoveOptions, room::cache::RoomCache, traits::{creep::CreepExtensions, intents_tracking::StructureLabExtensionsTracking}
+};
+
+// Walks a freshly spawned creep through its boost labs.
+// Returns true while the creep is still busy getting boosted, so its role shouldnt run.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_boosting(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomCache) -> bool {
+    if creep.spawning() {
+        return false;
+    }
+
+    let creep_memory = if let Some(creep_memory) = memory.creeps.get_mut(&creep.name()) {
+        creep_memory
+    } else {
+        return false;
+    };
+
+    let compound = match creep_memory.boosts.as_ref().and_then(|b| b.first()) {
+        Some(compound) => *compound,
+        None => {
+            creep_memory.boosts = None;
+            return false;
+        }
+    };
+
+    // If its taking this long, the labs arent getting filled. Just go do our job.
+    if creep.ticks_to_live().unwrap_or(CREEP_LIFETIME) < CREEP_LIFETIME - 150 {
+        creep_memory.boosts = None;
+        return false;
+    }
+
+    let owning_room = creep_memory.owning_room;
+
+    let lab = memory
+        .rooms
+        .get(&owning_room)
+        .and_then(|r| r.labs.boost_labs.get(&compound))
+        .and_then(|id| cache.rooms.get(&owning_room).and_then(|c| c.structures.labs.get(id)))
+        .cloned();
+
+    let lab = if let Some(lab) = lab {
+        lab
+    } else {
+        remove_boost(memory, creep, compound);
+        return true;
+    };
+
+    if !creep.pos().is_near_to(lab.pos()) {
+        creep.bsay("🧪", false);
+
+        let current_room = creep.room().unwrap().name();
+        if let Some(room_cache) = cache.rooms.get_mut(&current_room) {
+            creep.better_move_to(memory, room_cache, lab.pos(), 1, MoveOptions::default());
+        }
+
+        return true;
+    }
+
+    if lab.store().get_used_capacity(Some(compound)) >= LAB_BOOST_MINERAL
+        && lab.store().get_used_capacity(Some(ResourceType::Energy)) >= LAB_BOOST_ENERGY
+        && lab.ITboost_creep(creep, None).is_ok()
+    {
+        creep.bsay("💪", false);
+        remove_boost(memory, creep, compound);
+    }
+
+    true
+}
+
+fn remove_boost(memory: &mut ScreepsMemory, creep: &Creep, compound: ResourceType) {
+    if let Some(creep_memory) = memory.creeps.get_mut(&creep.name()) {
+        if let Some(boosts) = creep_memory.boosts.as_mut() {
+            boosts.retain(|b| *b != compound);
+        }
+    }
+}
@@ -6,6 +6,7 @@ use crate::{
     room::{
         cache::RoomCache,
         creeps::{global::recycler::run_recycler, local::upgrader::sign_controller},
+        spawning::prespawn::record_travel_time,
     },
     traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking},
 };
@@ -45,6 +46,8 @@ pub fn run_reserver(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomC
             let controller = current_room.controller().unwrap();
 
             if creep.pos().is_near_to(controller.pos()) {
+                record_travel_time(creep, memory);
+
                 let _ = sign_controller(creep, memory, cache);
                 let _ = creep.ITreserve_controller(&controller);
             } else {
@@ -1,7 +1,7 @@
-use screeps::{game, Creep, HasPosition, OwnedStructureProperties, Position, RoomCoordinate, SharedCreepProperties};
+use screeps::{game, Creep, HasPosition, OwnedStructureProperties, Position, Room, RoomCoordinate, RoomName, SharedCreepProperties};
 
 use crate::{
-    memory::ScreepsMemory, movement::move_target::MoveOptions, room::{cache::RoomCache, creeps::local::upgrader::sign_controller}, traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking}
+    memory::ScreepsMemory, movement::move_target::MoveOptions, room::{cache::RoomCache, creeps::local::upgrader::sign_controller}, traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking, room::{RoomExtensions, RoomType}}, utils
 };
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -45,6 +45,22 @@ pub fn run_claimer(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomCa
 
         creep.better_move_to(memory, room_cache, pos, 23, MoveOptions::default().visualize_path(true).avoid_enemies(true).ignore_cache(true).path_age(15));
     } else {
+        // Colonists from another shard get dropped wherever the portal goes, so they have to find their own room.
+        let colonist = !memory.rooms.contains_key(&creep_memory.owning_room);
+        let claimable = current_room
+            .controller()
+            .is_some_and(|controller| controller.my() || (controller.owner().is_none() && controller.reservation().is_none()));
+
+        if colonist && !claimable {
+            if let Some(next_room) = find_colony_room(&current_room, memory) {
+                memory.creeps.get_mut(&creep.name()).unwrap().target_room = Some(next_room);
+            } else {
+                creep.bsay("NO ROOM", false);
+            }
+
+            return;
+        }
+
         let controller = current_room.controller().unwrap();
 
         if controller.my() {
@@ -57,3 +73,17 @@ pub fn run_claimer(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomCa
         }
     }
 }
+
+// The closest normal room that, as far as we know, nobody has taken.
+fn find_colony_room(current_room: &Room, memory: &ScreepsMemory) -> Option<RoomName> {
+    current_room
+        .get_adjacent(2)
+        .into_iter()
+        .filter(|room_name| *room_name != current_room.name() && utils::room_type(room_name) == RoomType::Normal)
+        .filter(|room_name| {
+            memory.scouted_rooms.get(room_name).map_or(true, |scouted| {
+                scouted.controller.is_some() && scouted.owner.is_none() && scouted.reserved.is_none()
+            })
+        })
+        .min_by_key(|room_name| utils::calc_room_distance(&current_room.name(), room_name, true))
+}
@@ -0,0 +1,66 @@
+use screeps::{find, Creep, HasPosition, Position, SharedCreepProperties};
+
+use crate::{
+    combat::goals::deposit::find_deposit, memory::{Role, ScreepsMemory}, movement::move_target::MoveOptions, room::cache::RoomCache, traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking}
+};
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_depositharvester(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    if creep.spawning() {
+        return;
+    }
+
+    let creep_memory = memory.creeps.get_mut(&creep.name()).unwrap();
+    let current_room = creep.room().unwrap().name();
+
+    let goal = if let Some(goal) = creep_memory.target_room.and_then(|target_room| memory.goals.deposit.get(&target_room)).cloned() {
+        goal
+    } else {
+        creep_memory.role = Role::Recycler;
+        return;
+    };
+
+    let deposit_pos = Position::new(goal.deposit_pos.x, goal.deposit_pos.y, goal.deposit_room);
+
+    if !creep.pos().is_near_to(deposit_pos) {
+        creep.bsay("⛏️", false);
+        creep.better_move_to(
+            memory,
+            cache.rooms.get_mut(&current_room).unwrap(),
+            deposit_pos,
+            1,
+            MoveOptions::default().avoid_enemies(true),
+        );
+
+        return;
+    }
+
+    let carried = creep.store().get_used_capacity(Some(goal.deposit_type));
+
+    // Hand off to whichever hauler is waiting next to us.
+    if carried > 0 {
+        let hauler = creep
+            .pos()
+            .find_in_range(find::MY_CREEPS, 1)
+            .into_iter()
+            .find(|other| {
+                other.store().get_free_capacity(None) > 0
+                    && memory.creeps.get(&other.name()).is_some_and(|m| m.role == Role::DepositHauler)
+            });
+
+        if let Some(hauler) = hauler {
+            let _ = creep.ITtransfer(&hauler, goal.deposit_type, None);
+        }
+    }
+
+    if creep.store().get_free_capacity(None) == 0 {
+        creep.bsay("⌛", false);
+        return;
+    }
+
+    if let Some(deposit) = find_deposit(&goal) {
+        if deposit.cooldown() == 0 {
+            let _ = creep.ITharvest(&deposit);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use screeps::{game, Creep, HasPosition, Position, SharedCreepProperties};
+
+use crate::{
+    memory::{Role, ScreepsMemory}, movement::move_target::MoveOptions, room::cache::RoomCache, traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking}, utils
+};
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_deposithauler(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    if creep.spawning() {
+        return;
+    }
+
+    let creep_memory = memory.creeps.get_mut(&creep.name()).unwrap();
+    let owning_room = creep_memory.owning_room;
+    let current_room = creep.room().unwrap().name();
+
+    let goal = creep_memory.target_room.and_then(|target_room| memory.goals.deposit.get(&target_room)).cloned();
+    let used = creep.store().get_used_capacity(None);
+
+    // Head home once full, or if the operation wound down with something in hand.
+    // We also have to leave with enough life to make it back.
+    let ticks_to_live = creep.ticks_to_live().unwrap_or(0);
+    let distance = goal.as_ref().map_or(0, |goal| {
+        utils::calc_room_distance(&goal.deposit_room, &owning_room, true) as u32 * 50
+    });
+
+    let going_home = used > 0
+        && (goal.is_none() || creep.store().get_free_capacity(None) == 0 || ticks_to_live <= distance + 50);
+
+    if going_home {
+        let storage = cache.rooms.get(&owning_room).and_then(|room_cache| room_cache.structures.storage.clone());
+
+        if let Some(storage) = storage {
+            if creep.pos().is_near_to(storage.pos()) {
+                let resource = utils::store_to_hashmap(&creep.store()).into_keys().next();
+                if let Some(resource) = resource {
+                    let _ = creep.ITtransfer(&storage, resource, None);
+                }
+            } else {
+                creep.bsay("🏠", false);
+                creep.better_move_to(
+                    memory,
+                    cache.rooms.get_mut(&current_room).unwrap(),
+                    storage.pos(),
+                    1,
+                    MoveOptions::default().avoid_enemies(true),
+                );
+            }
+        }
+
+        return;
+    }
+
+    let goal = if let Some(goal) = goal {
+        goal
+    } else {
+        creep_memory.role = Role::Recycler;
+        return;
+    };
+
+    // Not worth another round trip.
+    if used == 0 && ticks_to_live <= distance * 2 + 100 {
+        creep_memory.role = Role::Recycler;
+        return;
+    }
+
+    // Wait by whichever harvester we are paired with, by index.
+    let index = goal.haulers.iter().position(|name| *name == creep.name()).unwrap_or(0);
+    let harvester = goal
+        .harvesters
+        .get(index)
+        .or(goal.harvesters.first())
+        .and_then(|name| game::creeps().get(name.to_string()));
+
+    let target_pos = harvester
+        .map(|harvester| harvester.pos())
+        .unwrap_or_else(|| Position::new(goal.deposit_pos.x, goal.deposit_pos.y, goal.deposit_room));
+
+    if !creep.pos().is_near_to(target_pos) {
+        creep.bsay("🚚", false);
+        creep.better_move_to(
+            memory,
+            cache.rooms.get_mut(&current_room).unwrap(),
+            target_pos,
+            1,
+            MoveOptions::default().avoid_enemies(true),
+        );
+    }
+}
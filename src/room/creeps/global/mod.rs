@@ -3,4 +3,9 @@ pub mod unclaimer;
 pub mod recycler;
 pub mod physical_observer;
 pub mod claimer;
-pub mod expansion_builder;
\ No newline at end of file
+pub mod expansion_builder;
+pub mod power_bank_attacker;
+pub mod power_bank_healer;
+pub mod power_bank_hauler;
+pub mod deposit_harvester;
+pub mod deposit_hauler;
\ No newline at end of file
@@ -0,0 +1,62 @@
+use screeps::{game, Creep, HasPosition, Position, SharedCreepProperties};
+
+use crate::{
+    combat::goals::power_bank::find_power_bank, memory::{Role, ScreepsMemory}, movement::move_target::MoveOptions, room::cache::RoomCache, traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking, position::PositionExtensions}
+};
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_powerbankattacker(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    if creep.spawning() {
+        return;
+    }
+
+    let creep_memory = memory.creeps.get_mut(&creep.name()).unwrap();
+
+    let goal = creep_memory.target_room.and_then(|target_room| memory.goals.power_bank.get(&target_room)).cloned();
+    let goal = if let Some(goal) = goal {
+        goal
+    } else {
+        creep_memory.role = Role::Recycler;
+        return;
+    };
+
+    let Some((_, healer)) = goal.pairs.iter().find(|(attacker, _)| *attacker == creep.name()) else {
+        // Our healer died and the goal dropped us, hitting the bank alone is a good way to die.
+        creep_memory.role = Role::Recycler;
+        return;
+    };
+
+    // Wait on the healer, whether it hasnt spawned yet or is still catching up.
+    let Some(healer) = game::creeps().get(healer.to_string()) else {
+        creep.bsay("⏳", false);
+        return;
+    };
+
+    if healer.spawning() || (!healer.pos().is_near_to(creep.pos()) && !creep.pos().is_room_edge()) {
+        creep.bsay("⏳", false);
+        return;
+    }
+
+    let bank_pos = Position::new(goal.bank_pos.x, goal.bank_pos.y, goal.bank_room);
+
+    if !creep.pos().is_near_to(bank_pos) {
+        creep.bsay("⚡", false);
+        creep.better_move_to(
+            memory,
+            cache.rooms.get_mut(&creep.room().unwrap().name()).unwrap(),
+            bank_pos,
+            1,
+            MoveOptions::default().avoid_enemies(true),
+        );
+        return;
+    }
+
+    if let Some(bank) = find_power_bank(&goal.bank_room) {
+        // The bank hits back for half, let the healer catch up.
+        if creep.hits() > creep.hits_max() / 2 {
+            let _ = creep.ITattack(&bank);
+        }
+    } else if let Some(creep_memory) = memory.creeps.get_mut(&creep.name()) {
+        creep_memory.role = Role::Recycler;
+    }
+}
@@ -0,0 +1,99 @@
+use screeps::{find, Creep, HasPosition, Position, ResourceType, SharedCreepProperties};
+
+use crate::{
+    combat::goals::power_bank::{find_power_bank, power_left_in_room}, memory::{Role, ScreepsMemory}, movement::move_target::MoveOptions, room::cache::RoomCache, traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking}
+};
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_powerbankhauler(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    if creep.spawning() {
+        return;
+    }
+
+    let creep_memory = memory.creeps.get_mut(&creep.name()).unwrap();
+    let owning_room = creep_memory.owning_room;
+    let current_room = creep.room().unwrap().name();
+
+    let goal = creep_memory.target_room.and_then(|target_room| memory.goals.power_bank.get(&target_room)).cloned();
+    let carried = creep.store().get_used_capacity(Some(ResourceType::Power));
+
+    let done_collecting = goal.as_ref().map_or(true, |goal| {
+        creep.store().get_free_capacity(None) == 0
+            || (current_room == goal.bank_room && goal.bank_broken && !power_left_in_room(&goal.bank_room))
+    });
+
+    if carried > 0 && done_collecting {
+        let storage = cache.rooms.get(&owning_room).and_then(|room_cache| room_cache.structures.storage.clone());
+
+        if let Some(storage) = storage {
+            if creep.pos().is_near_to(storage.pos()) {
+                let _ = creep.ITtransfer(&storage, ResourceType::Power, None);
+            } else {
+                creep.bsay("🏠", false);
+                creep.better_move_to(
+                    memory,
+                    cache.rooms.get_mut(&current_room).unwrap(),
+                    storage.pos(),
+                    1,
+                    MoveOptions::default().avoid_enemies(true),
+                );
+            }
+        }
+
+        return;
+    }
+
+    let goal = match goal {
+        Some(goal) if !done_collecting => goal,
+        _ => {
+            creep_memory.role = Role::Recycler;
+            return;
+        }
+    };
+
+    let bank_pos = Position::new(goal.bank_pos.x, goal.bank_pos.y, goal.bank_room);
+
+    if current_room != goal.bank_room || find_power_bank(&goal.bank_room).is_some() {
+        // Hang back until it breaks, so we arent in the attackers way.
+        if creep.pos().get_range_to(bank_pos) > 4 {
+            creep.bsay("⚡", false);
+            creep.better_move_to(
+                memory,
+                cache.rooms.get_mut(&current_room).unwrap(),
+                bank_pos,
+                4,
+                MoveOptions::default().avoid_enemies(true),
+            );
+        }
+
+        return;
+    }
+
+    let room = creep.room().unwrap();
+
+    if let Some(resource) = room
+        .find(find::DROPPED_RESOURCES, None)
+        .into_iter()
+        .find(|r| r.resource_type() == ResourceType::Power)
+    {
+        if creep.pos().is_near_to(resource.pos()) {
+            let _ = creep.ITpickup(&resource);
+        } else {
+            creep.better_move_to(memory, cache.rooms.get_mut(&current_room).unwrap(), resource.pos(), 1, MoveOptions::default());
+        }
+
+        return;
+    }
+
+    if let Some(ruin) = room
+        .find(find::RUINS, None)
+        .into_iter()
+        .find(|r| r.store().get_used_capacity(Some(ResourceType::Power)) > 0)
+    {
+        if creep.pos().is_near_to(ruin.pos()) {
+            let _ = creep.ITwithdraw(&ruin, ResourceType::Power, None);
+        } else {
+            creep.better_move_to(memory, cache.rooms.get_mut(&current_room).unwrap(), ruin.pos(), 1, MoveOptions::default());
+        }
+    }
+}
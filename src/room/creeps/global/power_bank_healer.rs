@@ -0,0 +1,63 @@
+use screeps::{game, Creep, HasPosition, SharedCreepProperties};
+
+use crate::{
+    memory::{Role, ScreepsMemory}, movement::move_target::MoveOptions, room::cache::RoomCache, traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking}
+};
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_powerbankhealer(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    if creep.spawning() {
+        return;
+    }
+
+    let creep_memory = memory.creeps.get_mut(&creep.name()).unwrap();
+
+    let goal = creep_memory.target_room.and_then(|target_room| memory.goals.power_bank.get(&target_room)).cloned();
+    let attacker = goal.as_ref().and_then(|goal| {
+        goal.pairs
+            .iter()
+            .find(|(_, healer)| *healer == creep.name())
+            .and_then(|(attacker, _)| game::creeps().get(attacker.to_string()))
+    });
+
+    let attacker = if let Some(attacker) = attacker {
+        attacker
+    } else {
+        // Our attacker hasnt spawned yet, wait for it.
+        if goal.is_some_and(|goal| goal.pairs.iter().any(|(_, healer)| *healer == creep.name())) {
+            creep.bsay("⏳", false);
+            let _ = creep.ITheal(creep);
+            return;
+        }
+
+        creep_memory.role = Role::Recycler;
+        return;
+    };
+
+    if attacker.spawning() {
+        return;
+    }
+
+    if creep.pos().is_near_to(attacker.pos()) {
+        if attacker.hits() < attacker.hits_max() || creep.hits() == creep.hits_max() {
+            let _ = creep.ITheal(&attacker);
+        } else {
+            let _ = creep.ITheal(creep);
+        }
+    } else {
+        if creep.pos().get_range_to(attacker.pos()) <= 3 {
+            let _ = creep.ITranged_heal(&attacker);
+        } else if creep.hits() < creep.hits_max() {
+            let _ = creep.ITheal(creep);
+        }
+
+        creep.bsay("💉", false);
+        creep.better_move_to(
+            memory,
+            cache.rooms.get_mut(&creep.room().unwrap().name()).unwrap(),
+            attacker.pos(),
+            1,
+            MoveOptions::default().avoid_enemies(true).avoid_creeps(false).path_age(1),
+        );
+    }
+}
@@ -2,7 +2,7 @@ use rand::{rngs::StdRng, Rng, SeedableRng};
 use screeps::{game, Color, Creep, HasPosition, OwnedStructureProperties, SharedCreepProperties};
 
 use crate::{
-    config, memory::{Role, ScreepsMemory}, movement::move_target::MoveOptions, room::cache::RoomCache, traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking}, utils::get_my_username
+    config, goal_memory::ClaimTarget, memory::{Role, ScreepsMemory}, movement::move_target::MoveOptions, room::cache::RoomCache, traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking}, utils::get_my_username
 };
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -64,7 +64,7 @@ pub fn run_unclaimer(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut Room
 
                     if controller.reservation().is_none() && memory.remote_rooms.contains_key(&creep.room().unwrap().name()) {
                         let _ = creep.ITreserve_controller(&controller);
-                    } else if controller.owner().is_none() && memory.goals.room_claim.contains_key(&creep.room().unwrap().name()) {
+                    } else if controller.owner().is_none() && memory.goals.room_claim.contains_key(&ClaimTarget::local(creep.room().unwrap().name())) {
                         let _ = creep.ITclaim_controller(&controller);
                     } else {
                         let res = creep.ITattack_controller(&controller);
@@ -5,9 +5,12 @@ use screeps::{
 use crate::{
     memory::ScreepsMemory,
     movement::move_target::MoveOptions,
-    room::cache::{
-        hauling::{HaulTaskRequest, HaulingType},
-        RoomCache,
+    room::{
+        cache::{
+            hauling::{HaulTaskRequest, HaulingType},
+            RoomCache,
+        },
+        planning::room::rebuild,
     },
     traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking},
     utils::under_storage_gate,
@@ -107,12 +110,15 @@ pub fn build(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
         }
     }
 
-    let sites = if site_clone.is_empty() {
+    let mut sites = if site_clone.is_empty() {
         sites
     } else {
         site_clone
     };
 
+    // Put back what we lost before building anything new.
+    sites.sort_by_key(|site| rebuild::rebuild_site_order(room_cache, site.pos().xy(), site.structure_type()));
+
     if creep.store().get_used_capacity(Some(ResourceType::Energy)) == 0 {
         creepmem.needs_energy = Some(true);
     }
@@ -6,7 +6,7 @@ use screeps::{
 use crate::{
     memory::ScreepsMemory,
     movement::move_target::MoveOptions,
-    room::cache::{hauling::HaulingType, resources::CachedSource, CachedRoom, RoomCache},
+    room::{cache::{hauling::HaulingType, resources::CachedSource, CachedRoom, RoomCache}, spawning::prespawn::record_travel_time},
     traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking, room::RoomExtensions},
 };
 
@@ -30,7 +30,7 @@ pub fn run_harvester(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut Room
 
     let pointer_index = creep_memory.task_id.unwrap() as usize;
     let scouted_source = &mut cached_room.resources.sources[pointer_index];
-    scouted_source.add_creep(creep);
+    scouted_source.add_creep(creep, creep_memory.travel_time);
 
     let mut source = scouted_source.clone();
 
@@ -72,6 +72,8 @@ pub fn harvest_source(
         None
     } else {
         creep.set_working_area(cache, source.source.pos(), 1);
+        record_travel_time(creep, memory);
+
         if source.source.energy() == 0 {
             return None;
         }
@@ -159,6 +159,11 @@ pub fn run_hauler(
 pub fn decide_energy_need(creep: &Creep, memory: &mut ScreepsMemory, _cache: &mut RoomCache) {
     let creep_memory = memory.creeps.get_mut(&creep.name()).unwrap();
     if creep_memory.role == Role::Hauler {
+        // Mid route, the stops were planned around what we carry, dont drop them for crossing half.
+        if creep_memory.hauling_task.as_ref().is_some_and(|task| !task.next_stops.is_empty()) {
+            return;
+        }
+
         let half_capcaity = creep.store().get_capacity(None) as f32 * 0.5;
         let has_other_than_energy =
             utils::contains_other_than(&creep.store(), ResourceType::Energy);
@@ -198,7 +203,8 @@ pub fn execute_order(
     let creep_memory = memory.creeps.get_mut(&creep.name()).unwrap();
 
     if position.is_none() || target.is_none() {
-        creep_memory.hauling_task = None;
+        // Gone already, but the rest of the route might still be there.
+        creep_memory.hauling_task = order.next_task();
         creep_memory.path = None;
 
         creep.bsay("INVLD", false);
@@ -587,9 +593,17 @@ pub fn execute_order(
     if result.is_ok() {
         release_reservation(creep, room_cache, order, amount);
 
-        creep_memory.hauling_task = None;
         creep_memory.path = None;
 
+        // More stops on the route, head straight there instead of asking for a new order.
+        if route_continues(creep, order, amount) {
+            creep_memory.hauling_task = order.next_task();
+
+            return (false, Some((order.resource, amount as u32, order.haul_type)));
+        }
+
+        creep_memory.hauling_task = None;
+
         return (true, Some((order.resource, amount as u32, order.haul_type)));
     } else if result.is_err() {
         match result.err().unwrap() {
@@ -618,20 +632,36 @@ pub fn execute_order(
     (false, None)
 }
 
+// Whether the creep has anything left for the next stop once this one goes through.
+fn route_continues(creep: &Creep, order: &CreepHaulTask, amount: i32) -> bool {
+    if order.next_stops.is_empty() {
+        return false;
+    }
+
+    let left = if order.haul_type == HaulingType::Transfer {
+        creep.store().get_used_capacity(Some(order.resource)) as i32
+    } else {
+        creep.store().get_free_capacity(Some(order.resource))
+    };
+
+    left - amount > 0
+}
+
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn release_reservation(
     creep: &Creep,
     _room_cache: &mut CachedRoom,
     order: &CreepHaulTask,
-    amount_hauled: i32,
+    _amount_hauled: i32,
 ) {
     let mut heap_hauling = heap().hauling.lock().unwrap();
-    if let Some(reservation) = heap_hauling.reserved_orders.get_mut(&order.target_id) {
-        reservation.reserved_amount -= amount_hauled;
-        reservation.creeps_assigned.retain(|x| x != &creep.name());
+    let key = (order.target_id, order.resource);
+    if let Some(reservation) = heap_hauling.reserved_orders.get_mut(&key) {
+        // Were done with this target, so all of our share goes, not just what we hauled.
+        reservation.release(&creep.name());
 
         if reservation.reserved_amount <= 0 || reservation.creeps_assigned.is_empty() {
-            heap_hauling.reserved_orders.remove(&order.target_id);
+            heap_hauling.reserved_orders.remove(&key);
         }
     }
 }
@@ -648,7 +678,7 @@ pub fn _check_relay(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomC
     let creep_moving_to = room_cache
         .traffic
         .intended_move
-        .get(&creep.try_id().unwrap());
+        .get(&creep.try_raw_id().unwrap());
     if let Some(moving_dir) = creep_moving_to {
         let creeps_at_pos = room_cache.creeps.creeps_at_pos.get(moving_dir);
 
@@ -703,11 +733,11 @@ pub fn _check_relay(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomC
                 room_cache
                     .traffic
                     .intended_move
-                    .remove(&creep.try_id().unwrap());
+                    .remove(&creep.try_raw_id().unwrap());
                 room_cache
                     .traffic
                     .intended_move
-                    .remove(&creep_at_pos.try_id().unwrap());
+                    .remove(&creep_at_pos.try_raw_id().unwrap());
 
                 // Cancel any movement intents
                 let _ = creep.cancel_order(&JsString::from("move"));
@@ -1,6 +1,6 @@
-use screeps::{game, Creep, HasId, HasPosition, Part, Repairable, ResourceType, SharedCreepProperties, StructureObject, StructureProperties, StructureType};
+use screeps::{game, Creep, HasHits, HasId, HasPosition, Part, Repairable, ResourceType, SharedCreepProperties, StructureObject, StructureProperties, StructureType};
 
-use crate::{memory::{CreepMemory, ScreepsMemory}, room::cache::{hauling::{HaulTaskRequest, HaulingType}, CachedRoom, RoomCache}, traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking}, utils::{get_rampart_repair_rcl, under_storage_gate}};
+use crate::{memory::{CreepMemory, ScreepsMemory}, room::nukes::rampart_nuke_target, room::cache::{hauling::{HaulTaskRequest, HaulingType}, CachedRoom, RoomCache}, traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking}, utils::{get_rampart_repair_rcl, under_storage_gate}};
 use super::hauler;
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -30,7 +30,9 @@ pub fn run_repairer(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomC
 
         let max = if repairable.structure_type() == StructureType::Rampart {
             let controller = room_cache.structures.controller.as_ref().unwrap();
-            get_rampart_repair_rcl(controller.level())
+            let nuke_target = rampart_nuke_target(room_cache, repairable.pos().xy()).unwrap_or(0);
+
+            get_rampart_repair_rcl(controller.level()).max(nuke_target)
         } else {
             repairable.hits_max()
         };
@@ -85,6 +87,23 @@ pub fn get_repair_task(creep: &Creep, creep_memory: &mut CreepMemory, cache: &mu
         }
     }
 
+    // Ramparts about to take a nuke come first, whichever lands soonest.
+    let nuked_rampart = cache
+        .structures
+        .ramparts
+        .iter()
+        .filter(|rampart| rampart_nuke_target(cache, rampart.pos().xy()).is_some_and(|target| rampart.hits() < target))
+        .min_by_key(|rampart| {
+            let lands_at = cache.nuke_damage.get(&rampart.pos().xy()).map_or(u32::MAX, |(_, lands_at)| *lands_at);
+
+            (lands_at, creep.pos().get_range_to(rampart.pos()))
+        });
+
+    if let Some(rampart) = nuked_rampart {
+        creep_memory.repair_target = Some(StructureObject::from(rampart.clone()).as_structure().id());
+        return true;
+    }
+
     if cache.structures.needs_repair.is_empty() {
         creep.bsay("NO-REPAIRS", false);
         return false;
@@ -94,10 +113,12 @@ pub fn get_repair_task(creep: &Creep, creep_memory: &mut CreepMemory, cache: &mu
         let structure = repairable_structure.as_structure();
         if let Some(repairable) = repairable_structure.as_repairable() {
             let max_hits = if structure.structure_type() == StructureType::Rampart {
+                let nuke_target = rampart_nuke_target(cache, structure.pos().xy()).unwrap_or(0);
+
                 if cache.rcl >= 8 {
-                    10_000_000
+                    nuke_target.max(10_000_000)
                 } else {
-                    100_000
+                    nuke_target.max(100_000)
                 }
             } else {
                 repairable.hits_max()
@@ -3,7 +3,7 @@ use screeps::{Creep, HasPosition, Position, ResourceType, SharedCreepProperties}
 use crate::{
     memory::{Role, ScreepsMemory},
     movement::move_target::MoveOptions,
-    room::cache::{CachedRoom, RoomCache},
+    room::{cache::{CachedRoom, RoomCache}, spawning::prespawn::record_travel_time},
     traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking},
     utils,
 };
@@ -17,6 +17,8 @@ pub fn run_storagesitter(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut
         return;
     }
 
+    record_travel_time(creep, memory);
+
     let storage = if let Some(storage) = &room_cache.structures.storage {
         storage
     } else {
@@ -3,12 +3,13 @@ use screeps::{Creep, HasPosition, MaybeHasId, Part, ResourceType, SharedCreepPro
 use crate::{
     memory::ScreepsMemory,
     movement::move_target::MoveOptions,
-    room::cache::
-        {
+    room::{
+        cache::{
             hauling::{HaulTaskRequest, HaulingType},
             RoomCache,
-        }
-    ,
+        },
+        spawning::prespawn::record_travel_time,
+    },
     traits::{
         creep::CreepExtensions, intents_tracking::CreepExtensionsTracking, room::RoomExtensions
     },
@@ -63,6 +64,8 @@ pub fn run_upgrader(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomC
         creep.bsay("⚡", false);
         let _ = creep.upgrade_controller(controller);
 
+        record_travel_time(creep, memory);
+
         cached_room.stats.energy.spending_upgrading += energy_spent_upgrading(creep);
 
         creep.set_working_area(cached_room, controller.pos(), 3);
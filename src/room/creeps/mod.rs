@@ -1,4 +1,5 @@
 pub mod organizer;
+pub mod boosting;
 pub mod local;
 pub mod recovery;
 pub mod global;
@@ -4,15 +4,16 @@ use log::info;
 use screeps::{game, Room, SharedCreepProperties};
 
 use crate::{
-    combat::hate_handler::process_health_event, heap, heap_cache::heap_creep::{HealthChangeType, HeapCreep}, memory::{Role, ScreepsMemory}, room::{
+    combat::hate_handler::process_health_event, heap, inter_shard, heap_cache::heap_creep::{HealthChangeType, HeapCreep}, memory::{Role, ScreepsMemory}, room::{
         cache::RoomCache,
         creeps::{global, remote},
+        spawning::renewal,
     }, traits::{
         creep::CreepExtensions, room::RoomExtensions,
     }
 };
 
-use super::{combat, local};
+use super::{boosting, combat, local};
 
 #[cfg(feature = "season1")]
 use super::season1;
@@ -66,6 +67,7 @@ pub fn run_creeps(room: &Room, memory: &mut ScreepsMemory, cache: &mut RoomCache
             match role {
                 Role::RemoteHarvester => remote::remote_harvester::run_remoteharvester(&creep, memory, cache),
                 Role::RemoteDefender => remote::remote_defender::run_remotedefender(&creep, memory, cache),
+                Role::KeeperKiller => remote::keeper_killer::run_keeperkiller(&creep, memory, cache),
                 Role::Bulldozer => combat::bulldozer::run_bulldozer(&creep, memory, cache),
                 _ => { continue; }
             }
@@ -76,7 +78,19 @@ pub fn run_creeps(room: &Room, memory: &mut ScreepsMemory, cache: &mut RoomCache
         // Fucks up harvester spawning. Should be done per-creep.
         //if creep.spawning() { continue; }
 
+        if inter_shard::travel_to_shard(&creep, memory, cache) {
+            continue;
+        }
+
+        if renewal::travel_to_renew(&creep, memory, cache) {
+            continue;
+        }
+
+        let is_boosting = boosting::run_boosting(&creep, memory, cache);
+
         match role {
+            _ if is_boosting => {}
+
             Role::Harvester => local::harvester::run_harvester(&creep, memory, cache),
             Role::MineralMiner => local::mineral_miner::run_mineralminer(&creep, memory, cache),
             Role::Hauler => local::hauler::run_hauler(&creep, memory, cache, None),
@@ -97,10 +111,17 @@ pub fn run_creeps(room: &Room, memory: &mut ScreepsMemory, cache: &mut RoomCache
             Role::Recycler => global::recycler::run_recycler(&creep, memory, cache),
             Role::PhysicalObserver => global::physical_observer::run_physical_observer(&creep, memory, cache),
 
+            Role::PowerBankAttacker => global::power_bank_attacker::run_powerbankattacker(&creep, memory, cache),
+            Role::PowerBankHealer => global::power_bank_healer::run_powerbankhealer(&creep, memory, cache),
+            Role::PowerBankHauler => global::power_bank_hauler::run_powerbankhauler(&creep, memory, cache),
+            Role::DepositHarvester => global::deposit_harvester::run_depositharvester(&creep, memory, cache),
+            Role::DepositHauler => global::deposit_hauler::run_deposithauler(&creep, memory, cache),
+
             Role::Reserver => combat::reserver::run_reserver(&creep, memory, cache),
 
             Role::RemoteDefender => remote::remote_defender::run_remotedefender(&creep, memory, cache),
             Role::InvaderCoreCleaner => remote::invader_cleaner::run_invadercleaner(&creep, memory, cache),
+            Role::KeeperKiller => remote::keeper_killer::run_keeperkiller(&creep, memory, cache),
 
             #[cfg(feature = "season1")]
             Role::Season1Digger => season1::digger::run_digger(&creep, memory, cache),
@@ -0,0 +1,76 @@
+use screeps::{Creep, HasPosition, Position, RoomCoordinate, SharedCreepProperties};
+
+use crate::{
+    memory::{Role, ScreepsMemory},
+    movement::move_target::MoveOptions,
+    room::{cache::RoomCache, source_keepers},
+    traits::{creep::CreepExtensions, intents_tracking::CreepExtensionsTracking},
+};
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_keeperkiller(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    let creep_memory = memory.creeps.get_mut(&creep.name()).unwrap();
+
+    let target_room = if let Some(target_room) = creep_memory.target_room {
+        target_room
+    } else {
+        creep.bsay("kurt kob", true);
+        creep_memory.role = Role::Recycler;
+        return;
+    };
+
+    if creep.spawning() {
+        return;
+    }
+
+    let room_name = creep.room().unwrap().name();
+    let room_cache = cache.rooms.get_mut(&room_name).unwrap();
+
+    if room_name != target_room {
+        if creep.hits() < creep.hits_max() {
+            let _ = creep.ITheal(creep);
+        }
+
+        let pos = Position::new(RoomCoordinate::new(25).unwrap(), RoomCoordinate::new(25).unwrap(), target_room);
+        creep.better_move_to(memory, room_cache, pos, 23, MoveOptions::default());
+        return;
+    }
+
+    let keeper = source_keepers::source_keepers(room_cache)
+        .into_iter()
+        .min_by_key(|keeper| keeper.pos().get_range_to(creep.pos()))
+        .cloned();
+
+    if let Some(keeper) = keeper {
+        // Healing cancels out the attack, so only swing when we are next to it.
+        if creep.pos().is_near_to(keeper.pos()) {
+            let _ = creep.ITattack(&keeper);
+        } else if creep.hits() < creep.hits_max() {
+            let _ = creep.ITheal(creep);
+        }
+
+        creep.bsay("⚔️", false);
+        creep.better_move_to(memory, room_cache, keeper.pos(), 1, MoveOptions::default().path_age(1));
+        return;
+    }
+
+    if creep.hits() < creep.hits_max() {
+        let _ = creep.ITheal(creep);
+    }
+
+    // Wait next to whichever lair is spawning next, so we can get the first hit in.
+    let next_lair = room_cache
+        .structures
+        .keeper_lairs
+        .values()
+        .min_by_key(|lair| lair.ticks_to_spawn().unwrap_or(u32::MAX))
+        .map(|lair| lair.pos());
+
+    if let Some(lair_pos) = next_lair {
+        if !creep.pos().is_near_to(lair_pos) {
+            creep.better_move_to(memory, room_cache, lair_pos, 1, MoveOptions::default());
+        } else {
+            creep.bsay("⏳", false);
+        }
+    }
+}
@@ -1,3 +1,4 @@
 pub mod remote_harvester;
 pub mod remote_defender;
-pub mod invader_cleaner;
\ No newline at end of file
+pub mod invader_cleaner;
+pub mod keeper_killer;
\ No newline at end of file
@@ -9,6 +9,7 @@ use crate::{
     room::{
         cache::{CachedRoom, RoomCache},
         creeps::local::harvester::{harvest_source, repair_container},
+        source_keepers,
     },
     traits::{
         creep::CreepExtensions, intents_tracking::CreepExtensionsTracking, room::RoomExtensions,
@@ -33,7 +34,7 @@ pub fn run_remoteharvester(creep: &Creep, memory: &mut ScreepsMemory, cache: &mu
         }
 
         if let Some(remote_room) = cache.rooms.get_mut(&remote_room) {
-            remote_room.resources.sources[creep_memory.task_id.unwrap() as usize].add_creep(creep);
+            remote_room.resources.sources[creep_memory.task_id.unwrap() as usize].add_creep(creep, creep_memory.travel_time);
         }
 
         if let Some(remote_room_memory) = memory.remote_rooms.get(&remote_room) {
@@ -152,11 +153,22 @@ pub fn run_remoteharvester(creep: &Creep, memory: &mut ScreepsMemory, cache: &mu
                 }
             }
 
-            let scouted_source =
-                &mut room_cache.resources.sources[creep_memory.task_id.unwrap() as usize];
-            scouted_source.add_creep(creep);
+            // Keepers one-shot harvesters, so get out of the way until the killer deals with it.
+            let source_pos = room_cache.resources.sources[creep_memory.task_id.unwrap() as usize].source.pos();
+            if let Some(threat) = source_keepers::keeper_threat(room_cache, source_pos) {
+                creep.bsay("🏃", false);
 
-            let mut source = scouted_source.clone();
+                if source_keepers::in_keeper_range(creep.pos(), threat) {
+                    if let Some(retreat) = source_keepers::retreat_position(creep.pos(), threat) {
+                        creep.better_move_to(memory, room_cache, retreat, 0, MoveOptions::default());
+                    }
+                }
+
+                return;
+            }
+
+            // Already added to the source up top, doing it again counts our parts twice.
+            let mut source = room_cache.resources.sources[creep_memory.task_id.unwrap() as usize].clone();
 
             if creep.store().get_used_capacity(None) as f32
                 >= (creep.store().get_capacity(None) as f32 * 0.5)
@@ -11,10 +11,10 @@ use crate::{
     heap,
     memory::ScreepsMemory,
     room::{
-        cache::{hauling, resources, RoomCache},
+        cache::{hauling, resources, terminals, RoomCache},
         creeps::{organizer, recovery::recover_creeps},
         planning::room::{
-            plan_room, remotes, roads::{self, plan_main_room_roads}, skippy_base::run_planner,
+            plan_room, rebuild, remotes, roads::{self, plan_main_room_roads}, skippy_base::run_planner,
         },
         tower,
         visuals::run_full_visuals,
@@ -24,13 +24,11 @@ use crate::{
 };
 
 use super::{
-    links,
+    factory, labs, links, nukes, power, source_keepers,
     planning::{
         self,
         room::{construction::{
-            get_containers, get_rcl_2_plan, get_rcl_3_plan, get_rcl_4_plan, get_rcl_5_plan,
-            get_rcl_6_plan, get_rcl_7_plan, get_rcl_8_plan, get_roads_and_ramparts,
-            plan_remote_containers,
+            planned_structures_for_rcl, plan_remote_containers,
         }, roads::get_all_cached_road_positions},
     },
     visuals::visualise_room_visual,
@@ -146,7 +144,8 @@ pub fn start_government(room: Room, memory: &mut ScreepsMemory, cache: &mut Room
             }
         }
 
-        if game::cpu::bucket() >= 5000 {
+        // Hand made plans take over from the planner.
+        if game::cpu::bucket() >= 5000 && memory.rooms.get(&room.name()).unwrap().imported_plan.is_none() {
             run_planner(&room, memory.rooms.get_mut(&room.name()).unwrap());
         }
 
@@ -200,9 +199,26 @@ pub fn start_government(room: Room, memory: &mut ScreepsMemory, cache: &mut Room
                 }
             }
 
+            nukes::run_nuke_defense(cached_room, memory.rooms.get_mut(&room.name()).unwrap());
+            nukes::haul_nuker(cached_room);
+
             let pre_tower_cpu = game::cpu::get_used();
             tower::run_towers(cached_room);
             cached_room.stats.cpu_towers = game::cpu::get_used() - pre_tower_cpu;
+
+            let pre_lab_cpu = game::cpu::get_used();
+            labs::run_labs(cached_room, memory);
+            cached_room.stats.cpu_labs = game::cpu::get_used() - pre_lab_cpu;
+
+            let pre_factory_cpu = game::cpu::get_used();
+            factory::run_factory(cached_room, memory.rooms.get_mut(&room.name()).unwrap());
+            cached_room.stats.cpu_factory = game::cpu::get_used() - pre_factory_cpu;
+
+            let pre_power_cpu = game::cpu::get_used();
+            power::run_power_spawn(cached_room);
+            cached_room.stats.cpu_power = game::cpu::get_used() - pre_power_cpu;
+
+            terminals::declare_terminal_needs(cached_room, &mut cache.terminals);
         }
 
         // Makes hauling requests for the rooms remotes :)
@@ -274,6 +290,9 @@ pub fn start_government(room: Room, memory: &mut ScreepsMemory, cache: &mut Room
         }
 
         {
+            // Lost structures get put back before anything new goes down.
+            rebuild::run_rebuilds(&room, memory.rooms.get_mut(&room.name()).unwrap(), cache.rooms.get_mut(&room.name()).unwrap());
+
             run_crap_planner_code(&room, memory, cache);
 
             let room_cache = cache.rooms.get_mut(&room.name()).unwrap();
@@ -323,7 +342,8 @@ pub fn start_government(room: Room, memory: &mut ScreepsMemory, cache: &mut Room
                 && remote_memory.last_attack_time.unwrap() + 1000 < game::time()
             {
                 if let Some(remote_cache) = cache.rooms.get(&remote_memory.name) {
-                    if remote_cache.creeps.enemy_creeps.is_empty() {
+                    // Keepers are always around in keeper rooms, the killer deals with them.
+                    if remote_cache.creeps.enemy_creeps.len() == source_keepers::source_keepers(remote_cache).len() {
                         remote_memory.under_attack = false;
                         remote_memory.last_attack_time = None;
                     }
@@ -357,6 +377,7 @@ pub fn start_government(room: Room, memory: &mut ScreepsMemory, cache: &mut Room
         room_cache.stats.rcl = controller.level();
         room_cache.stats.rcl_progress = controller.progress();
         room_cache.stats.rcl_progress_total = controller.progress_total();
+        room_cache.stats.income_credits = std::mem::take(&mut room_cache.room_heap_cache.trade_credits);
 
         room_cache
             .stats
@@ -387,6 +408,9 @@ pub fn run_crap_planner_code(room: &Room, memory: &mut ScreepsMemory, cache: &mu
     }
 
     if game::cpu::bucket() > 1000 && game::time() % 100 == 0 {
+        // Dont build anything thats just going to get nuked.
+        let incoming_nukes = memory.rooms.get(&room.name()).unwrap().incoming_nukes.clone();
+
         let room_memory = memory.rooms.get_mut(&room.name()).unwrap();
         let room_cache = cache.rooms.get_mut(&room.name()).unwrap();
 
@@ -408,39 +432,26 @@ pub fn run_crap_planner_code(room: &Room, memory: &mut ScreepsMemory, cache: &mu
 
             room_memory.rcl_times.insert(level, game::time());
 
-            let structures = match room.controller().unwrap().level() {
-                2 => get_rcl_2_plan(),
-                3 => get_rcl_3_plan(),
-                4 => get_rcl_4_plan(),
-                5 => get_rcl_5_plan(),
-                6 => get_rcl_6_plan(),
-                7 => get_rcl_7_plan(),
-                8 => get_rcl_8_plan(),
-                _ => get_roads_and_ramparts(),
-            };
-
-            for structure in structures {
+            let structures = planned_structures_for_rcl(room_memory, level);
+
+            for (xy, structure_type) in structures {
                 if room_cache.structures.spawns.is_empty() {
                     continue;
                 }
 
-                if !should_rampart && structure.2 == StructureType::Rampart {
+                if !should_rampart && structure_type == StructureType::Rampart {
                     continue;
                 }
 
-                if !should_road && structure.2 == StructureType::Road {
+                if !should_road && structure_type == StructureType::Road {
                     continue;
                 }
 
-                let offset_x = room_cache.spawn_center.unwrap().x.u8();
-                let offset_y = room_cache.spawn_center.unwrap().y.u8() + 1;
+                if nukes::in_blast_radius(&incoming_nukes, xy) {
+                    continue;
+                }
 
-                let pos = RoomPosition::new(
-                    structure.0 as u8 + offset_x,
-                    structure.1 as u8 + offset_y,
-                    room.name(),
-                );
-                let r = room.ITcreate_construction_site(pos.x(), pos.y(), structure.2, None);
+                let r = room.ITcreate_construction_site(xy.x.u8(), xy.y.u8(), structure_type, None);
 
                 if r.is_ok() {
                     heap().flow_cache.lock().unwrap().remove(&room.name());
@@ -507,15 +518,13 @@ pub fn run_crap_planner_code(room: &Room, memory: &mut ScreepsMemory, cache: &mu
             room_memory.rcl = room.controller().unwrap().level();
         }
 
-        let stuffs = get_roads_and_ramparts();
-
-        let pos = room_cache.spawn_center.unwrap();
-
-        let offset_x = pos.x;
-        let offset_y = unsafe { RoomCoordinate::unchecked_new(pos.y.u8() + 1) };
-
         let room_memory = memory.rooms.get_mut(&room.name()).unwrap();
 
+        let stuffs = planned_structures_for_rcl(room_memory, room_memory.rcl)
+            .into_iter()
+            .filter(|(_, structure_type)| matches!(structure_type, StructureType::Road | StructureType::Container | StructureType::Rampart))
+            .collect::<Vec<_>>();
+
         if let Some(_flag) = game::flags().get("deleteAllRoadCSites".to_string()) {
             let csites = game::construction_sites()
                 .values()
@@ -621,40 +630,26 @@ pub fn run_crap_planner_code(room: &Room, memory: &mut ScreepsMemory, cache: &mu
             }
         }
 
-        for structure in get_containers() {
-            let pos = RoomPosition::new(
-                structure.0 as u8 + offset_x.u8(),
-                structure.1 as u8 + offset_y.u8(),
-                room.name(),
-            );
-            let r = room.ITcreate_construction_site(pos.x(), pos.y(), structure.2, None);
-
-            if r.is_ok() {
-                heap().flow_cache.lock().unwrap().remove(&room.name());
-            }
-        }
-
         let room_cache = cache.rooms.get_mut(&room.name()).unwrap();
 
-        for structure in stuffs {
-            if !should_rampart && structure.2 == StructureType::Rampart {
+        for (xy, structure_type) in stuffs {
+            if !should_rampart && structure_type == StructureType::Rampart {
                 continue;
             }
 
-            if !should_road && road_count < 50 && structure.2 == StructureType::Road {
+            if !should_road && road_count < 50 && structure_type == StructureType::Road {
                 continue;
             }
 
-            if structure.2 == StructureType::Road {
+            if structure_type == StructureType::Road {
                 road_count += 1;
             }
 
-            let pos = RoomPosition::new(
-                structure.0 as u8 + offset_x.u8(),
-                structure.1 as u8 + offset_y.u8(),
-                room.name(),
-            );
-            let r = room.ITcreate_construction_site(pos.x(), pos.y(), structure.2, None);
+            if nukes::in_blast_radius(&incoming_nukes, xy) {
+                continue;
+            }
+
+            let r = room.ITcreate_construction_site(xy.x.u8(), xy.y.u8(), structure_type, None);
 
             if r.is_ok() {
                 heap().flow_cache.lock().unwrap().remove(&room.name());
@@ -677,18 +672,20 @@ pub fn run_crap_planner_code(room: &Room, memory: &mut ScreepsMemory, cache: &mu
 
 pub fn clean_rooms_roads(room: &Room, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
     let all_roads = get_all_cached_road_positions(&room.name(), memory);
-    let t = get_roads_and_ramparts();
-    let bunker_roads = t.iter().filter(|s| s.2 == StructureType::Road).collect::<Vec<_>>();
 
     let room_cache = cache.rooms.get(&room.name()).unwrap();
 
-    let offset_x = room_cache.spawn_center.unwrap().x.u8();
-    let offset_y = room_cache.spawn_center.unwrap().y.u8() + 1;
+    let Some(room_memory) = memory.rooms.get(&room.name()) else {
+        return;
+    };
 
-    let mut all_planned_roads = Vec::new();
-    for road in bunker_roads {
-        all_planned_roads.push(new_xy((road.0 + offset_x as i8) as u8, (road.1 + offset_y as i8) as u8));
-    }
+    let base_roads = planned_structures_for_rcl(room_memory, 8);
+
+    let mut all_planned_roads = base_roads
+        .into_iter()
+        .filter(|(_, structure_type)| *structure_type == StructureType::Road)
+        .map(|(xy, _)| xy)
+        .collect::<Vec<_>>();
 
     if let Some(planned_roads) = all_roads.get(&room.name()) {
         for road in planned_roads {
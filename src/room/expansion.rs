@@ -10,7 +10,7 @@ use screeps::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    config, constants::ROOM_SIZE, goal_memory::RoomClaimGoal, memory::ScreepsMemory, movement::move_target::MoveTarget, traits::{
+    config, constants::ROOM_SIZE, goal_memory::{ClaimTarget, RoomClaimGoal}, memory::ScreepsMemory, movement::move_target::MoveTarget, traits::{
         position::RoomXYExtensions,
         room::{RoomNameExtensions, RoomType},
     }, utils
@@ -221,7 +221,7 @@ pub fn attempt_expansion(memory: &mut ScreepsMemory, cache: &RoomCache) {
                     top_scorer, highest_score
                 );
 
-                if memory.goals.room_claim.contains_key(&top_scorer) {
+                if memory.goals.room_claim.contains_key(&ClaimTarget::local(top_scorer)) {
                     info!("[EXPANSION] Room already claimed, skipping.");
                     memory.expansion = None;
 
@@ -232,9 +232,10 @@ pub fn attempt_expansion(memory: &mut ScreepsMemory, cache: &RoomCache) {
                 let goal = RoomClaimGoal {
                     claim_target: top_scorer,
                     creeps_assigned: Vec::new(),
+                    target_shard: None,
                 };
 
-                memory.goals.room_claim.insert(top_scorer, goal);
+                memory.goals.room_claim.insert(ClaimTarget::local(top_scorer), goal);
             } else {
                 info!("[EXPANSION] Found no suitable rooms to expand to.");
             }
@@ -0,0 +1,220 @@
+use log::info;
+use screeps::{game, HasId, ResourceType, StructureFactory, StructureProperties};
+
+use crate::{
+    config::{FACTORY_ENERGY_SURPLUS, FACTORY_MINERAL_SURPLUS, FACTORY_PRODUCT_TARGET, ROOM_ENERGY_STOCKPILE},
+    constants::{factory_recipe, FACTORY_COMMODITIES, FACTORY_COMPRESSIONS},
+    memory::{FactoryMemory, RoomMemory},
+    traits::intents_tracking::StructureFactoryExtensionsTracking,
+    utils::{self, get_room_stock, scale_haul_priority},
+};
+
+use super::cache::{
+    hauling::{haul_from_stock, HaulingPriority, HaulingType},
+    CachedRoom,
+};
+
+// How many runs worth of components we keep in the factory at once.
+const FACTORY_BATCH_RUNS: u32 = 5;
+// Products get pulled out once this much has built up.
+const FACTORY_EMPTY_THRESHOLD: u32 = 1000;
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_factory(cached_room: &mut CachedRoom, room_memory: &mut RoomMemory) {
+    if cached_room.rcl < 7 || cached_room.structures.storage.is_none() {
+        return;
+    }
+
+    let factory = if let Some(factory) = &cached_room.structures.factory {
+        factory.clone()
+    } else {
+        return;
+    };
+
+    if room_memory.factory.target.is_none()
+        || game::time() - room_memory.factory.last_target_check >= 50
+    {
+        let new_target = pick_target(cached_room, &room_memory.factory);
+
+        if new_target != room_memory.factory.target {
+            info!(
+                "  [FACTORY] Room {} switching production from {:?} to {:?}",
+                cached_room.room.name(),
+                room_memory.factory.target,
+                new_target
+            );
+
+            room_memory.factory.target = new_target;
+        }
+
+        room_memory.factory.last_target_check = game::time();
+    }
+
+    haul_factory(cached_room, &factory, room_memory.factory.target);
+
+    if let Some(target) = room_memory.factory.target {
+        produce(&factory, target);
+    }
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn produce(factory: &StructureFactory, target: ResourceType) {
+    if factory.cooldown() > 0 {
+        return;
+    }
+
+    let (_, components) = if let Some(recipe) = factory_recipe(target) {
+        recipe
+    } else {
+        return;
+    };
+
+    let has_components = components
+        .iter()
+        .all(|(resource, amount)| factory.store().get_used_capacity(Some(*resource)) >= *amount);
+
+    if has_components {
+        let _ = factory.ITproduce(target);
+    }
+}
+
+// Decompress if we are running dry on energy, compress if we are drowning in something,
+// then use whatever bars we have for commodities.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn pick_target(cached_room: &CachedRoom, factory_memory: &FactoryMemory) -> Option<ResourceType> {
+    let energy = get_room_stock(cached_room, ResourceType::Energy);
+
+    if energy < ROOM_ENERGY_STOCKPILE && can_produce(cached_room, ResourceType::Energy) {
+        return Some(ResourceType::Energy);
+    }
+
+    // Keep going with what we have if its still worth making.
+    if let Some(current) = factory_memory.target {
+        if current != ResourceType::Energy
+            && get_room_stock(cached_room, current) < FACTORY_PRODUCT_TARGET
+            && wants_product(cached_room, current)
+            && can_produce(cached_room, current)
+        {
+            return Some(current);
+        }
+    }
+
+    let mut candidates = vec![ResourceType::Battery];
+    candidates.extend(FACTORY_COMPRESSIONS.iter().map(|(_, bar)| *bar));
+    candidates.extend(FACTORY_COMMODITIES.iter());
+
+    candidates.into_iter().find(|product| {
+        get_room_stock(cached_room, *product) < FACTORY_PRODUCT_TARGET
+            && wants_product(cached_room, *product)
+            && can_produce(cached_room, *product)
+    })
+}
+
+// Compressing only makes sense if we have a surplus of the raw stuff.
+fn wants_product(cached_room: &CachedRoom, product: ResourceType) -> bool {
+    if product == ResourceType::Battery {
+        return get_room_stock(cached_room, ResourceType::Energy) > FACTORY_ENERGY_SURPLUS;
+    }
+
+    if let Some((mineral, _)) = FACTORY_COMPRESSIONS.iter().find(|(_, bar)| *bar == product) {
+        return get_room_stock(cached_room, *mineral) > FACTORY_MINERAL_SURPLUS;
+    }
+
+    FACTORY_COMMODITIES.contains(&product)
+}
+
+fn can_produce(cached_room: &CachedRoom, product: ResourceType) -> bool {
+    let (_, components) = if let Some(recipe) = factory_recipe(product) {
+        recipe
+    } else {
+        return false;
+    };
+
+    components
+        .iter()
+        .all(|(resource, amount)| get_factory_stock(cached_room, *resource) >= *amount)
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn haul_factory(cached_room: &mut CachedRoom, factory: &StructureFactory, target: Option<ResourceType>) {
+    let components: &[(ResourceType, u32)] = target
+        .and_then(factory_recipe)
+        .map(|(_, components)| components)
+        .unwrap_or(&[]);
+
+    // Pull out anything we arent using, and products once they pile up.
+    for (resource, amount) in utils::store_to_hashmap(&factory.store()) {
+        if components.iter().any(|(component, _)| *component == resource) {
+            continue;
+        }
+
+        if Some(resource) == target && amount < FACTORY_EMPTY_THRESHOLD {
+            continue;
+        }
+
+        let priority = scale_haul_priority(
+            FACTORY_EMPTY_THRESHOLD,
+            amount.min(FACTORY_EMPTY_THRESHOLD),
+            HaulingPriority::Minerals,
+            true,
+        );
+
+        cached_room.hauling.create_order(
+            factory.raw_id(),
+            Some(factory.structure_type()),
+            Some(resource),
+            Some(amount),
+            priority,
+            HaulingType::Withdraw,
+        );
+    }
+
+    for (resource, per_run) in components {
+        let wanted = per_run * FACTORY_BATCH_RUNS;
+        let in_factory = factory.store().get_used_capacity(Some(*resource));
+
+        // Dont bother until we are down to a couple runs.
+        if in_factory >= per_run * 2 {
+            continue;
+        }
+
+        fill_factory(cached_room, factory, *resource, wanted);
+    }
+}
+
+fn fill_factory(cached_room: &mut CachedRoom, factory: &StructureFactory, resource: ResourceType, wanted: u32) {
+    let in_factory = factory.store().get_used_capacity(Some(resource));
+    let needed = wanted.saturating_sub(in_factory);
+
+    let amount = haul_from_stock(cached_room, resource, needed, HaulingPriority::Minerals as u32 as f32);
+    if amount == 0 {
+        return;
+    }
+
+    let priority = scale_haul_priority(
+        wanted,
+        in_factory,
+        HaulingPriority::Minerals,
+        false,
+    );
+
+    cached_room.hauling.create_order(
+        factory.raw_id(),
+        Some(factory.structure_type()),
+        Some(resource),
+        Some(amount),
+        priority,
+        HaulingType::Transfer,
+    );
+}
+
+// Stock, plus whatever is already loaded in the factory.
+fn get_factory_stock(cached_room: &CachedRoom, resource: ResourceType) -> u32 {
+    let in_factory = cached_room
+        .structures
+        .factory
+        .as_ref()
+        .map_or(0, |f| f.store().get_used_capacity(Some(resource)));
+
+    get_room_stock(cached_room, resource) + in_factory
+}
@@ -0,0 +1,492 @@
+use std::collections::{HashMap, HashSet};
+
+use log::info;
+use screeps::{
+    game, HasId, HasPosition, ObjectId, Part, ResourceType, StructureLab, StructureProperties,
+    LAB_BOOST_MINERAL, LAB_ENERGY_CAPACITY, LAB_MINERAL_CAPACITY, LAB_REACTION_AMOUNT,
+};
+
+use crate::{
+    config::{LAB_COMPOUND_TARGET, LAB_REAGENT_MINIMUM},
+    constants::{boost_compounds_for_part, LAB_REACTION_PRIORITY},
+    memory::{LabMemory, RoomMemory, ScreepsMemory},
+    traits::intents_tracking::StructureLabExtensionsTracking,
+    utils::{get_room_stock, new_xy, scale_haul_priority},
+};
+
+use super::cache::{
+    hauling::{haul_from_stock, HaulingPriority, HaulingType},
+    CachedRoom,
+};
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_labs(cached_room: &mut CachedRoom, memory: &mut ScreepsMemory) {
+    if cached_room.rcl < 6 || cached_room.structures.labs.is_empty() {
+        return;
+    }
+
+    let room_name = cached_room.room.name();
+
+    // Let go of boost labs nobody is waiting on anymore.
+    if game::time() % 10 == 0 {
+        let wanted = memory
+            .creeps
+            .values()
+            .filter(|c| c.owning_room == room_name)
+            .filter_map(|c| c.boosts.as_ref())
+            .flatten()
+            .cloned()
+            .collect::<HashSet<_>>();
+
+        if let Some(room_memory) = memory.rooms.get_mut(&room_name) {
+            room_memory.labs.boost_labs.retain(|compound, _| wanted.contains(compound));
+        }
+    }
+
+    let room_memory = if let Some(room_memory) = memory.rooms.get_mut(&room_name) {
+        room_memory
+    } else {
+        return;
+    };
+
+    haul_boost_labs(cached_room, &room_memory.labs);
+
+    if cached_room.structures.labs.len() < 3 {
+        return;
+    }
+
+    if needs_assignment(cached_room, &room_memory.labs) {
+        assign_labs(cached_room, room_memory);
+    }
+
+    if room_memory.labs.input_labs.len() != 2 {
+        return;
+    }
+
+    if room_memory.labs.target.is_none()
+        || game::time() - room_memory.labs.last_target_check >= 100
+    {
+        let new_target = pick_target(cached_room, &room_memory.labs);
+
+        if new_target != room_memory.labs.target {
+            info!(
+                "  [LABS] Room {} switching reaction from {:?} to {:?}",
+                cached_room.room.name(),
+                room_memory.labs.target,
+                new_target
+            );
+
+            room_memory.labs.target = new_target;
+        }
+
+        room_memory.labs.last_target_check = game::time();
+    }
+
+    let boost_lab_ids = room_memory.labs.boost_labs.values().collect::<Vec<_>>();
+
+    let input_labs = resolve_labs(cached_room, &room_memory.labs.input_labs);
+    let output_labs = resolve_labs(cached_room, &room_memory.labs.output_labs)
+        .into_iter()
+        .filter(|l| !boost_lab_ids.contains(&&l.id()))
+        .collect::<Vec<_>>();
+
+    haul_labs(cached_room, room_memory.labs.target, &input_labs, &output_labs);
+
+    if let Some(target) = room_memory.labs.target {
+        run_reactions(target, &input_labs, &output_labs);
+    }
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_reactions(target: ResourceType, input_labs: &[StructureLab], output_labs: &[StructureLab]) {
+    let components = if let Some(components) = target.reaction_components() {
+        components
+    } else {
+        return;
+    };
+
+    if input_labs.len() != 2 {
+        return;
+    }
+
+    for (lab, component) in input_labs.iter().zip(components.iter()) {
+        if lab.store().get_used_capacity(Some(*component)) < LAB_REACTION_AMOUNT {
+            return;
+        }
+    }
+
+    for lab in output_labs {
+        if lab.cooldown() > 0 {
+            continue;
+        }
+
+        if let Some(mineral) = lab.mineral_type() {
+            if mineral != target {
+                continue;
+            }
+        }
+
+        if lab.store().get_free_capacity(Some(target)) < LAB_REACTION_AMOUNT as i32 {
+            continue;
+        }
+
+        let _ = lab.ITrun_reaction(&input_labs[0], &input_labs[1]);
+    }
+}
+
+// Pick the compound we want to be making, the first one in the priority
+// list that we are low on, and that we have the reagents for.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn pick_target(cached_room: &CachedRoom, lab_memory: &LabMemory) -> Option<ResourceType> {
+    // Dont flip-flop between reactions, if we can keep making it, keep making it.
+    if let Some(current) = lab_memory.target {
+        if get_room_stock(cached_room, current) < LAB_COMPOUND_TARGET {
+            if let Some(components) = current.reaction_components() {
+                if components
+                    .iter()
+                    .all(|c| get_reagent_stock(cached_room, lab_memory, *c) >= LAB_REACTION_AMOUNT)
+                {
+                    return Some(current);
+                }
+            }
+        }
+    }
+
+    for compound in LAB_REACTION_PRIORITY.iter() {
+        if get_room_stock(cached_room, *compound) >= LAB_COMPOUND_TARGET {
+            continue;
+        }
+
+        if let Some(components) = compound.reaction_components() {
+            if components
+                .iter()
+                .all(|c| get_reagent_stock(cached_room, lab_memory, *c) >= LAB_REAGENT_MINIMUM)
+            {
+                return Some(*compound);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn haul_labs(
+    cached_room: &mut CachedRoom,
+    target: Option<ResourceType>,
+    input_labs: &[StructureLab],
+    output_labs: &[StructureLab],
+) {
+    let components = target.and_then(|t| t.reaction_components());
+
+    for (index, lab) in input_labs.iter().enumerate() {
+        let wanted = components.map(|c| c[index]);
+
+        // Wrong mineral in here, either the reaction changed or the labs got reassigned.
+        if let Some(mineral) = lab.mineral_type() {
+            if Some(mineral) != wanted {
+                empty_lab(cached_room, lab, mineral);
+                continue;
+            }
+        }
+
+        if let Some(wanted) = wanted {
+            if lab.store().get_used_capacity(Some(wanted)) < LAB_MINERAL_CAPACITY / 2 {
+                fill_lab(cached_room, lab, wanted);
+            }
+        }
+    }
+
+    for lab in output_labs {
+        if let Some(mineral) = lab.mineral_type() {
+            let amount = lab.store().get_used_capacity(Some(mineral));
+
+            if Some(mineral) != target || amount >= LAB_MINERAL_CAPACITY / 3 {
+                empty_lab(cached_room, lab, mineral);
+            }
+        }
+    }
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn fill_lab(cached_room: &mut CachedRoom, lab: &StructureLab, resource: ResourceType) {
+    let needed = lab.store().get_free_capacity(Some(resource)).max(0) as u32;
+
+    if needed == 0 {
+        return;
+    }
+
+    let ordered = haul_from_stock(cached_room, resource, needed, HaulingPriority::Minerals as u32 as f32);
+    if ordered == 0 {
+        return;
+    }
+
+    let priority = scale_haul_priority(
+        LAB_MINERAL_CAPACITY,
+        lab.store().get_used_capacity(Some(resource)),
+        HaulingPriority::Minerals,
+        false,
+    );
+
+    cached_room.hauling.create_order(
+        lab.raw_id(),
+        Some(lab.structure_type()),
+        Some(resource),
+        Some(needed),
+        priority,
+        HaulingType::Transfer,
+    );
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn empty_lab(cached_room: &mut CachedRoom, lab: &StructureLab, resource: ResourceType) {
+    let amount = lab.store().get_used_capacity(Some(resource));
+
+    if amount == 0 {
+        return;
+    }
+
+    let priority = scale_haul_priority(
+        LAB_MINERAL_CAPACITY,
+        amount,
+        HaulingPriority::Minerals,
+        true,
+    );
+
+    cached_room.hauling.create_order(
+        lab.raw_id(),
+        Some(lab.structure_type()),
+        Some(resource),
+        Some(amount),
+        priority,
+        HaulingType::Withdraw,
+    );
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn needs_assignment(cached_room: &CachedRoom, lab_memory: &LabMemory) -> bool {
+    if lab_memory.input_labs.len() != 2 || game::time() % 1000 == 0 {
+        return true;
+    }
+
+    let assigned = lab_memory.input_labs.len() + lab_memory.output_labs.len();
+    if assigned > cached_room.structures.labs.len() {
+        return true;
+    }
+
+    lab_memory
+        .input_labs
+        .iter()
+        .chain(lab_memory.output_labs.iter())
+        .any(|id| !cached_room.structures.labs.contains_key(id))
+}
+
+// The two input labs need to be in range of as many of the other labs as possible.
+// If the planner placed them, we just use that.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn assign_labs(cached_room: &CachedRoom, room_memory: &mut RoomMemory) {
+    let labs = cached_room.structures.labs.values().collect::<Vec<_>>();
+    let mut input_labs = Vec::new();
+
+    if let Some(planner) = &room_memory.skippy_planner {
+        if planner.planned {
+            for index in planner.source_labs {
+                let xy = new_xy((index % 50) as u8, (index / 50) as u8);
+
+                if let Some(lab) = labs.iter().find(|l| l.pos().xy() == xy) {
+                    input_labs.push(lab.id());
+                }
+            }
+        }
+    }
+
+    if input_labs.len() != 2 {
+        let mut ranked = labs.clone();
+        ranked.sort_by_key(|lab| {
+            let in_range = labs
+                .iter()
+                .filter(|other| other.pos().get_range_to(lab.pos()) <= 2)
+                .count();
+
+            (std::cmp::Reverse(in_range), lab.id().to_string())
+        });
+
+        input_labs = ranked.iter().take(2).map(|l| l.id()).collect();
+    }
+
+    let input_positions = input_labs
+        .iter()
+        .filter_map(|id| cached_room.structures.labs.get(id))
+        .map(|l| l.pos())
+        .collect::<Vec<_>>();
+
+    let output_labs: Vec<ObjectId<StructureLab>> = labs
+        .iter()
+        .filter(|l| !input_labs.contains(&l.id()))
+        .filter(|l| input_positions.iter().all(|p| p.get_range_to(l.pos()) <= 2))
+        .map(|l| l.id())
+        .collect();
+
+    info!(
+        "  [LABS] Room {} assigned {} input labs and {} output labs",
+        cached_room.room.name(),
+        input_labs.len(),
+        output_labs.len()
+    );
+
+    room_memory.labs.input_labs = input_labs;
+    room_memory.labs.output_labs = output_labs;
+}
+
+// Pick the best compound we have enough of for each part we want boosted.
+// Parts we dont have anything for just go unboosted.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn plan_boosts(cached_room: &CachedRoom, body: &[Part], parts: &[Part]) -> HashMap<Part, ResourceType> {
+    let mut plan = HashMap::new();
+
+    if cached_room.rcl < 6 || cached_room.structures.labs.is_empty() {
+        return plan;
+    }
+
+    for part in parts {
+        let count = body.iter().filter(|p| *p == part).count() as u32;
+
+        if count == 0 {
+            continue;
+        }
+
+        for compound in boost_compounds_for_part(*part) {
+            if get_room_stock(cached_room, *compound) >= count * LAB_BOOST_MINERAL {
+                plan.insert(*part, *compound);
+                break;
+            }
+        }
+    }
+
+    plan
+}
+
+// Reserve a lab for each compound in the plan, returning what the creep needs to go get.
+// If we are short on anything, nothing is reserved and the creep goes out unboosted.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn reserve_boosts(
+    cached_room: &CachedRoom,
+    room_memory: &mut RoomMemory,
+    body: &[Part],
+    plan: &HashMap<Part, ResourceType>,
+) -> Option<Vec<ResourceType>> {
+    let mut needed: HashMap<ResourceType, u32> = HashMap::new();
+
+    for (part, compound) in plan {
+        let count = body.iter().filter(|p| *p == part).count() as u32;
+
+        if count > 0 {
+            *needed.entry(*compound).or_insert(0) += count * LAB_BOOST_MINERAL;
+        }
+    }
+
+    if needed.is_empty() {
+        return None;
+    }
+
+    let lab_memory = &room_memory.labs;
+    let mut free_labs = cached_room
+        .structures
+        .labs
+        .values()
+        .filter(|l| !lab_memory.input_labs.contains(&l.id()))
+        .filter(|l| !lab_memory.boost_labs.values().any(|id| *id == l.id()))
+        .collect::<Vec<_>>();
+
+    let mut reservations = Vec::new();
+
+    for (compound, amount) in needed.iter() {
+        let in_lab = lab_memory
+            .boost_labs
+            .get(compound)
+            .and_then(|id| cached_room.structures.labs.get(id))
+            .map_or(0, |l| l.store().get_used_capacity(Some(*compound)));
+
+        if get_room_stock(cached_room, *compound) + in_lab < *amount {
+            return None;
+        }
+
+        if lab_memory.boost_labs.contains_key(compound) {
+            continue;
+        }
+
+        // Prefer a lab that already has the stuff in it.
+        free_labs.sort_by_key(|l| l.mineral_type() != Some(*compound));
+
+        if free_labs.is_empty() {
+            return None;
+        }
+
+        reservations.push((*compound, free_labs.remove(0).id()));
+    }
+
+    for (compound, lab) in reservations {
+        room_memory.labs.boost_labs.insert(compound, lab);
+    }
+
+    Some(needed.into_keys().collect())
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn haul_boost_labs(cached_room: &mut CachedRoom, lab_memory: &LabMemory) {
+    for (compound, lab_id) in lab_memory.boost_labs.iter() {
+        let lab = if let Some(lab) = cached_room.structures.labs.get(lab_id) {
+            lab.clone()
+        } else {
+            continue;
+        };
+
+        if let Some(mineral) = lab.mineral_type() {
+            if mineral != *compound {
+                empty_lab(cached_room, &lab, mineral);
+                continue;
+            }
+        }
+
+        if lab.store().get_used_capacity(Some(*compound)) < LAB_MINERAL_CAPACITY / 2 {
+            fill_lab(cached_room, &lab, *compound);
+        }
+
+        let energy = lab.store().get_used_capacity(Some(ResourceType::Energy));
+        if energy < LAB_ENERGY_CAPACITY / 2 {
+            let priority = scale_haul_priority(
+                LAB_ENERGY_CAPACITY,
+                energy,
+                HaulingPriority::Minerals,
+                false,
+            );
+
+            cached_room.hauling.create_order(
+                lab.raw_id(),
+                Some(lab.structure_type()),
+                Some(ResourceType::Energy),
+                Some(LAB_ENERGY_CAPACITY - energy),
+                priority,
+                HaulingType::Transfer,
+            );
+        }
+    }
+}
+
+fn resolve_labs(cached_room: &CachedRoom, ids: &[ObjectId<StructureLab>]) -> Vec<StructureLab> {
+    ids.iter()
+        .filter_map(|id| cached_room.structures.labs.get(id).cloned())
+        .collect()
+}
+
+// Same as stock, but counts what is already sitting in the input labs.
+fn get_reagent_stock(cached_room: &CachedRoom, lab_memory: &LabMemory, resource: ResourceType) -> u32 {
+    let in_labs = lab_memory
+        .input_labs
+        .iter()
+        .filter_map(|id| cached_room.structures.labs.get(id))
+        .map(|l| l.store().get_used_capacity(Some(resource)))
+        .sum::<u32>();
+
+    get_room_stock(cached_room, resource) + in_labs
+}
@@ -1,48 +1,211 @@
-use std::cmp;
+use log::info;
+use screeps::{game, HasId, HasPosition, ResourceType, Room, StructureLink, StructureType};
 
-use screeps::{game, HasId, ResourceType, Room, StructureType};
-
-use crate::memory::Role;
+use crate::{
+    constants::{LINK_CAPACITY, LINK_LOSS_RATIO},
+    memory::Role,
+    traits::intents_tracking::StructureLinkExtensionsTracking,
+};
 
 use super::cache::{hauling::HaulingType, CachedRoom};
 
+// Not worth putting a link on cooldown to move less than this.
+const MIN_LINK_TRANSFER: u32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LinkRole {
+    // Wants to be empty, everything in it should go somewhere else.
+    Supply,
+    // Wants to sit at its fill level, never sends.
+    Demand,
+    // The storage link, takes whatever supply has left over, and tops up demand.
+    Buffer,
+}
+
+#[derive(Debug, Clone)]
+struct LinkNode {
+    link: StructureLink,
+    role: LinkRole,
+    energy: u32,
+    desired: u32,
+    // Higher gets fed first when two plans move the same amount.
+    priority: u32,
+}
+
+impl LinkNode {
+    fn new(link: &StructureLink, role: LinkRole, desired: u32, priority: u32) -> Self {
+        LinkNode {
+            link: link.clone(),
+            role,
+            energy: link.store().get_used_capacity(Some(ResourceType::Energy)),
+            desired,
+            priority,
+        }
+    }
+
+    fn can_send(&self) -> bool {
+        self.role != LinkRole::Demand && self.link.cooldown() == 0 && self.surplus() >= MIN_LINK_TRANSFER
+    }
+
+    fn surplus(&self) -> u32 {
+        match self.role {
+            LinkRole::Supply => self.energy,
+            LinkRole::Buffer => self.energy,
+            LinkRole::Demand => 0,
+        }
+    }
+
+    // What this node can take from the sender, the buffer only takes from supply.
+    fn accepts_from(&self, sender: &LinkNode) -> bool {
+        match self.role {
+            LinkRole::Demand => true,
+            LinkRole::Buffer => sender.role == LinkRole::Supply,
+            LinkRole::Supply => false,
+        }
+    }
+
+    fn room_for(&self) -> u32 {
+        match self.role {
+            LinkRole::Demand => self.desired.saturating_sub(self.energy),
+            LinkRole::Buffer => LINK_CAPACITY.saturating_sub(self.energy),
+            LinkRole::Supply => 0,
+        }
+    }
+}
+
+// What actually shows up on the other end.
+fn delivered(amount: u32) -> u32 {
+    amount - (amount as f32 * LINK_LOSS_RATIO).ceil() as u32
+}
+
+// The most we can send without overfilling the target.
+fn sendable(surplus: u32, room: u32) -> u32 {
+    let mut amount = surplus.min((room as f32 / (1.0 - LINK_LOSS_RATIO)) as u32);
+
+    while amount > 0 && delivered(amount) > room {
+        amount -= 1;
+    }
+
+    amount
+}
+
+fn link_nodes(room_cache: &CachedRoom) -> Vec<LinkNode> {
+    let links = room_cache.structures.links();
+    let mut nodes = Vec::new();
+
+    for link in links.source.iter().flatten() {
+        nodes.push(LinkNode::new(link, LinkRole::Supply, 0, 0));
+    }
+
+    // Nothing we know of sits next to these, so nobody is going to come empty them.
+    // Send it all somewhere useful instead, but say so, it probably means a misplaced link.
+    for link in links.other.iter().flatten() {
+        if game::time() % 100 == 0 {
+            info!("  [LINKS] Unclassified link at {}, treating it as supply", link.pos());
+        }
+
+        nodes.push(LinkNode::new(link, LinkRole::Supply, 0, 0));
+    }
+
+    if let Some(link) = &links.fast_filler {
+        nodes.push(LinkNode::new(link, LinkRole::Demand, LINK_CAPACITY, 3));
+    }
+
+    if let Some(link) = &links.controller {
+        nodes.push(LinkNode::new(link, LinkRole::Demand, LINK_CAPACITY, 2));
+    }
+
+    // Labs only burn energy on boosts, half full is plenty.
+    // Haulers grab it from the offer in balance_links, so it doesnt just sit there.
+    if let Some(link) = &links.lab {
+        nodes.push(LinkNode::new(link, LinkRole::Demand, LINK_CAPACITY / 2, 1));
+    }
+
+    if let Some(link) = &links.storage {
+        nodes.push(LinkNode::new(link, LinkRole::Buffer, 0, 0));
+    }
+
+    nodes
+}
+
+// Every sender picks at most one target, find the plan that delivers the most energy.
+// Rooms have six links at most, so checking every combination is cheap.
+fn solve_link_network(nodes: &[LinkNode]) -> Vec<(usize, usize, u32)> {
+    let senders = (0..nodes.len()).filter(|i| nodes[*i].can_send()).collect::<Vec<_>>();
+    let mut room_left = nodes.iter().map(|node| node.room_for()).collect::<Vec<_>>();
+
+    let mut best = (0, 0, Vec::new());
+    let mut current = Vec::new();
+
+    search_transfers(nodes, &senders, 0, &mut room_left, &mut current, (0, 0), &mut best);
+
+    best.2
+}
+
+fn search_transfers(
+    nodes: &[LinkNode],
+    senders: &[usize],
+    index: usize,
+    room_left: &mut [u32],
+    current: &mut Vec<(usize, usize, u32)>,
+    score: (u32, u32),
+    best: &mut (u32, u32, Vec<(usize, usize, u32)>),
+) {
+    if index == senders.len() {
+        if score > (best.0, best.1) {
+            *best = (score.0, score.1, current.clone());
+        }
+
+        return;
+    }
+
+    let sender = senders[index];
+
+    // Sending nothing is always an option.
+    search_transfers(nodes, senders, index + 1, room_left, current, score, best);
+
+    for target in 0..nodes.len() {
+        if target == sender || !nodes[target].accepts_from(&nodes[sender]) {
+            continue;
+        }
+
+        let amount = sendable(nodes[sender].surplus(), room_left[target]);
+        if amount < MIN_LINK_TRANSFER {
+            continue;
+        }
+
+        let got = delivered(amount);
+
+        room_left[target] -= got;
+        current.push((sender, target, amount));
+
+        let new_score = (score.0 + got, score.1 + got * nodes[target].priority);
+        search_transfers(nodes, senders, index + 1, room_left, current, new_score, best);
+
+        current.pop();
+        room_left[target] += got;
+    }
+}
+
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn balance_links(_room: &Room, room_cache: &mut CachedRoom) {
     if game::cpu::bucket() < 100 {
         return;
     }
 
-    if let Some(source_link) = &room_cache.structures.links().source {
-        for link in source_link {
-            if let Some(storage_link) = &room_cache.structures.links().storage {
-                let storage_capacity = storage_link.store().get_free_capacity(Some(screeps::constants::ResourceType::Energy));
-                let source_capacity = link.store().get_used_capacity(Some(screeps::constants::ResourceType::Energy));
-
-                if storage_capacity > 0 && source_capacity > 0 {
-                    let transfer_amount = cmp::min(storage_link.store().get_used_capacity(Some(ResourceType::Energy)), link.store().get_used_capacity(Some(ResourceType::Energy)));
-                    let _ = link.transfer_energy(storage_link, Some(transfer_amount));
-                }
-            }
-
-            if let Some(fastfill_link) = &room_cache.structures.links().fast_filler {
-                let fastfill_capacity = fastfill_link.store().get_free_capacity(Some(screeps::constants::ResourceType::Energy));
-                let source_capacity = link.store().get_used_capacity(Some(screeps::constants::ResourceType::Energy));
-
-                if fastfill_capacity > 0 && source_capacity > 0 {
-                    let transfer_amount = cmp::min(fastfill_link.store().get_used_capacity(Some(ResourceType::Energy)), link.store().get_used_capacity(Some(ResourceType::Energy)));
-                    let _ = link.transfer_energy(fastfill_link, Some(transfer_amount));
-                }
-            }
-
-            if let Some(controller_link) = &room_cache.structures.links().controller {
-                let controller_capacity = controller_link.store().get_free_capacity(Some(ResourceType::Energy));
-                let source_capacity = link.store().get_used_capacity(Some(ResourceType::Energy));
-
-                if controller_capacity > 0 && source_capacity > 0 {
-                    let transfer_amount = cmp::min(controller_link.store().get_used_capacity(Some(ResourceType::Energy)), link.store().get_used_capacity(Some(ResourceType::Energy)));
-                    let _ = link.transfer_energy(controller_link, Some(transfer_amount));
-                }
-            }
+    let nodes = link_nodes(room_cache);
+
+    for (sender, target, amount) in solve_link_network(&nodes) {
+        let _ = nodes[sender].link.ITtransfer_energy(&nodes[target].link, Some(amount));
+    }
+
+    // The lab link is filled for the labs, offer it up so the lab energy orders get fed from it
+    // before anyone walks to storage.
+    if let Some(lab_link) = &room_cache.structures.links().lab {
+        let amount = lab_link.store().get_used_capacity(Some(ResourceType::Energy));
+
+        if amount > 0 {
+            room_cache.hauling.create_order(lab_link.raw_id(), Some(StructureType::Link), Some(ResourceType::Energy), Some(amount), -(amount as f32), HaulingType::Offer);
         }
     }
 
@@ -50,27 +213,9 @@ pub fn balance_links(_room: &Room, room_cache: &mut CachedRoom) {
         let base_hauler_count = room_cache.creeps.creeps_of_role(Role::BaseHauler);
 
         if base_hauler_count == 0 {
-            let amount = storage_link.store().get_used_capacity(Some(screeps::constants::ResourceType::Energy));
+            let amount = storage_link.store().get_used_capacity(Some(ResourceType::Energy));
 
             room_cache.hauling.create_order(storage_link.raw_id(), Some(StructureType::Extension), Some(ResourceType::Energy), Some(amount), -(amount as f32), HaulingType::Offer);
         }
-
-        if let Some(fastfill_link) = &room_cache.structures.links().fast_filler {
-            let fastfill_capacity = fastfill_link.store().get_free_capacity(Some(screeps::constants::ResourceType::Energy));
-            let source_capacity = storage_link.store().get_used_capacity(Some(screeps::constants::ResourceType::Energy));
-
-            if fastfill_capacity > 0 && source_capacity > 0 {
-                let transfer_amount = cmp::min(fastfill_link.store().get_used_capacity(Some(ResourceType::Energy)), storage_link.store().get_used_capacity(Some(ResourceType::Energy)));
-                let _ = storage_link.transfer_energy(fastfill_link, Some(transfer_amount));
-            }
-        }
-
-        if let Some(controller_link) = &room_cache.structures.links().controller {
-            let half_capacity = storage_link.store().get_used_capacity(Some(screeps::constants::ResourceType::Energy)) / 2;
-            if controller_link.store().get_used_capacity(Some(screeps::constants::ResourceType::Energy)) < half_capacity {
-                let transfer_amount = storage_link.store().get_used_capacity(Some(screeps::constants::ResourceType::Energy));
-                let _ = storage_link.transfer_energy(controller_link, Some(transfer_amount));
-            }
-        }
     }
-}
\ No newline at end of file
+}
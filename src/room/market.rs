@@ -0,0 +1,266 @@
+use log::info;
+use screeps::{
+    game::{
+        self,
+        market::{MarketResourceType, Order, OrderType},
+    },
+    ResourceType, StructureTerminal,
+};
+
+use crate::{
+    config::{
+        LAB_REAGENT_MINIMUM, MARKET_BUY_PRICE_MARKUP, MARKET_BUY_TARGET, MARKET_INTERVAL,
+        MARKET_MAX_BUY_PRICE, MARKET_MAX_DEAL_AMOUNT, MARKET_MIN_CREDITS, MARKET_SELL_ENERGY_THRESHOLD,
+        MARKET_SELL_MINERAL_THRESHOLD, MARKET_SELL_PRICE_FLOOR,
+    },
+    constants::BASE_MINERALS,
+    heap,
+    memory::ScreepsMemory,
+    utils::{self, get_room_stock},
+};
+
+use super::cache::{terminals::TerminalCache, CachedRoom, RoomCache};
+
+// History is only updated once a day, no need to ask any more than this.
+const PRICE_REFRESH_INTERVAL: u32 = 1000;
+// Used when nobody has traded energy recently enough to have a history.
+const DEFAULT_ENERGY_PRICE: f64 = 1.0;
+
+pub struct MarketDeal {
+    pub order_id: String,
+    pub resource: ResourceType,
+    pub order_type: OrderType,
+    pub amount: u32,
+    pub price: f64,
+    pub energy_cost: u32,
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_market(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    if game::time() % MARKET_INTERVAL != 0 || game::cpu::bucket() < 2000 {
+        return;
+    }
+
+    let orders = game::market::get_all_orders(None);
+    let energy_price = get_average_price(ResourceType::Energy).unwrap_or(DEFAULT_ENERGY_PRICE);
+
+    for room_name in cache.my_rooms.clone() {
+        let room_cache = if let Some(room_cache) = cache.rooms.get(&room_name) {
+            room_cache
+        } else {
+            continue;
+        };
+
+        let terminal = if let Some(terminal) = &room_cache.structures.terminal {
+            terminal
+        } else {
+            continue;
+        };
+
+        if terminal.cooldown() > 0 {
+            continue;
+        }
+
+        let deal = find_sell_deal(room_cache, terminal, &orders, energy_price)
+            .or_else(|| find_buy_deal(room_cache, terminal, &cache.terminals, &orders, energy_price));
+
+        if let Some(deal) = deal {
+            if execute_deal(memory, cache.rooms.get_mut(&room_name).unwrap(), deal) {
+                cache.terminals.busy_terminals.push(room_name);
+            }
+        }
+    }
+}
+
+// Volume weighted average over the history we have, which is up to two weeks of days.
+pub fn get_average_price(resource: ResourceType) -> Option<f64> {
+    let mut prices = heap().market_prices.lock().unwrap();
+
+    if let Some((checked, price)) = prices.get(&resource) {
+        if game::time() - checked < PRICE_REFRESH_INTERVAL {
+            return *price;
+        }
+    }
+
+    let history = game::market::get_history(Some(MarketResourceType::Resource(resource)));
+
+    let volume = history.iter().map(|day| day.volume() as f64).sum::<f64>();
+    let price = if volume > 0.0 {
+        Some(history.iter().map(|day| day.avg_price() * day.volume() as f64).sum::<f64>() / volume)
+    } else {
+        None
+    };
+
+    prices.insert(resource, (game::time(), price));
+
+    price
+}
+
+// How much we want to keep before selling off the rest.
+fn sell_threshold(resource: ResourceType) -> Option<u32> {
+    if resource == ResourceType::Energy {
+        Some(MARKET_SELL_ENERGY_THRESHOLD)
+    } else if BASE_MINERALS.contains(&resource) {
+        Some(MARKET_SELL_MINERAL_THRESHOLD)
+    } else {
+        None
+    }
+}
+
+fn orders_for<'a>(orders: &'a [Order], resource: ResourceType, order_type: OrderType) -> impl Iterator<Item = &'a Order> {
+    orders.iter().filter(move |order| {
+        order.order_type() == order_type
+            && order.resource_type() == MarketResourceType::Resource(resource)
+            && order.amount() > 0
+            && order.room_name().is_some()
+    })
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn find_sell_deal(room_cache: &CachedRoom, terminal: &StructureTerminal, orders: &[Order], energy_price: f64) -> Option<MarketDeal> {
+    let room_name = room_cache.room.name();
+    let terminal_energy = terminal.store().get_used_capacity(Some(ResourceType::Energy));
+
+    let mut best: Option<(MarketDeal, f64)> = None;
+
+    for (resource, in_terminal) in utils::store_to_hashmap(&terminal.store()) {
+        let threshold = if let Some(threshold) = sell_threshold(resource) {
+            threshold
+        } else {
+            continue;
+        };
+
+        let surplus = get_room_stock(room_cache, resource).saturating_sub(threshold);
+        let amount = surplus.min(in_terminal).min(MARKET_MAX_DEAL_AMOUNT);
+        if amount == 0 {
+            continue;
+        }
+
+        let average = if let Some(average) = get_average_price(resource) {
+            average
+        } else {
+            continue;
+        };
+
+        for order in orders_for(orders, resource, OrderType::Buy) {
+            let amount = amount.min(order.amount());
+            let energy_cost = utils::calc_terminal_cost(amount, &room_name, &order.room_name().unwrap());
+
+            // Selling energy eats into what we have to pay the fee with.
+            let energy_needed = if resource == ResourceType::Energy { amount + energy_cost } else { energy_cost };
+            if energy_needed > terminal_energy {
+                continue;
+            }
+
+            let effective = order.price() - (energy_cost as f64 * energy_price) / amount as f64;
+            if effective < average * MARKET_SELL_PRICE_FLOOR {
+                continue;
+            }
+
+            if best.as_ref().map_or(true, |(_, best_effective)| effective > *best_effective) {
+                best = Some((
+                    MarketDeal {
+                        order_id: order.id().into(),
+                        resource,
+                        order_type: OrderType::Buy,
+                        amount,
+                        price: order.price(),
+                        energy_cost,
+                    },
+                    effective,
+                ));
+            }
+        }
+    }
+
+    best.map(|(deal, _)| deal)
+}
+
+// Buy base minerals the labs are short on, as long as its not a ripoff.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn find_buy_deal(room_cache: &CachedRoom, terminal: &StructureTerminal, terminals: &TerminalCache, orders: &[Order], energy_price: f64) -> Option<MarketDeal> {
+    if room_cache.structures.labs.len() < 3 || game::market::credits() < MARKET_MIN_CREDITS {
+        return None;
+    }
+
+    let room_name = room_cache.room.name();
+    let terminal_energy = terminal.store().get_used_capacity(Some(ResourceType::Energy));
+
+    for resource in BASE_MINERALS {
+        let stock = get_room_stock(room_cache, resource);
+        // No point paying for it if another room can send it over.
+        if stock >= LAB_REAGENT_MINIMUM || terminals.offers_by_resource.contains_key(&resource) {
+            continue;
+        }
+
+        let wanted = MARKET_BUY_TARGET.saturating_sub(stock).min(MARKET_MAX_DEAL_AMOUNT);
+        let cap = get_average_price(resource).map_or(MARKET_MAX_BUY_PRICE, |average| {
+            (average * MARKET_BUY_PRICE_MARKUP).min(MARKET_MAX_BUY_PRICE)
+        });
+
+        let mut best: Option<(MarketDeal, f64)> = None;
+
+        for order in orders_for(orders, resource, OrderType::Sell) {
+            let amount = wanted.min(order.amount());
+            let energy_cost = utils::calc_terminal_cost(amount, &room_name, &order.room_name().unwrap());
+
+            if energy_cost > terminal_energy || order.price() * amount as f64 > game::market::credits() {
+                continue;
+            }
+
+            let effective = order.price() + (energy_cost as f64 * energy_price) / amount as f64;
+            if effective > cap {
+                continue;
+            }
+
+            if best.as_ref().map_or(true, |(_, best_effective)| effective < *best_effective) {
+                best = Some((
+                    MarketDeal {
+                        order_id: order.id().into(),
+                        resource,
+                        order_type: OrderType::Sell,
+                        amount,
+                        price: order.price(),
+                        energy_cost,
+                    },
+                    effective,
+                ));
+            }
+        }
+
+        if let Some((deal, _)) = best {
+            return Some(deal);
+        }
+    }
+
+    None
+}
+
+fn execute_deal(memory: &mut ScreepsMemory, room_cache: &mut CachedRoom, deal: MarketDeal) -> bool {
+    let room_name = &room_cache.room.name();
+    let result = game::market::deal(&deal.order_id.as_str().into(), deal.amount, Some(*room_name));
+
+    if result.is_err() {
+        info!("  [MARKET] Room {} failed to deal {} {:?}: {:?}", room_name, deal.amount, deal.resource, result);
+        return false;
+    }
+
+    let credits = deal.price * deal.amount as f64;
+
+    let stats = &mut memory.stats;
+    stats.market.deals += 1;
+    stats.market.fees += deal.energy_cost;
+
+    if deal.order_type == OrderType::Buy {
+        info!("  [MARKET] Room {} sold {} {:?} at {:.3} for {:.0} credits", room_name, deal.amount, deal.resource, deal.price, credits);
+
+        stats.market.income += credits;
+        room_cache.room_heap_cache.trade_credits += credits;
+    } else {
+        info!("  [MARKET] Room {} bought {} {:?} at {:.3} for {:.0} credits", room_name, deal.amount, deal.resource, deal.price, credits);
+
+        stats.market.spending += credits;
+    }
+
+    true
+}
@@ -5,6 +5,14 @@ pub mod tower;
 pub mod planning;
 pub mod cache;
 pub mod links;
+pub mod power;
+pub mod factory;
+pub mod labs;
 pub mod visuals;
 pub mod spawning;
-pub mod expansion;
\ No newline at end of file
+pub mod expansion;
+pub mod market;
+pub mod nukes;
+pub mod observer;
+pub mod power_creeps;
+pub mod source_keepers;
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+
+use log::{info, warn};
+use screeps::{find, game, HasHits, HasId, HasPosition, Part, ResourceType, RoomXY, StructureProperties, StructureType};
+
+use crate::{
+    constants::{NUKER_GHODIUM_CAPACITY, REPAIR_POWER},
+    memory::{IncomingNuke, RoomMemory, Role},
+    utils::scale_haul_priority,
+};
+
+use super::cache::{
+    hauling::{haul_from_stock, HaulingPriority, HaulingType},
+    CachedRoom,
+};
+
+const NUKE_DAMAGE_CENTER: u32 = 10_000_000;
+const NUKE_DAMAGE_AREA: u32 = 5_000_000;
+const NUKE_RANGE: u8 = 2;
+// Extra on top of the expected damage, so a rampart doesnt pop with a sliver left.
+const NUKE_RAMPART_BUFFER: u32 = 500_000;
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_nuke_defense(cached_room: &mut CachedRoom, room_memory: &mut RoomMemory) {
+    room_memory.incoming_nukes.retain(|nuke| nuke.impact_tick > game::time());
+
+    let mut new_nukes = false;
+
+    if game::time() % 10 == 0 {
+        for nuke in cached_room.room.find(find::NUKES, None) {
+            let pos = nuke.pos().xy();
+            let impact_tick = game::time() + nuke.time_to_land();
+
+            if room_memory.incoming_nukes.iter().any(|known| known.pos == pos && known.impact_tick.abs_diff(impact_tick) <= 1) {
+                continue;
+            }
+
+            info!(
+                "  [NUKES] Nuke from {} will land in {} at {:?} in {} ticks",
+                nuke.launch_room_name(),
+                cached_room.room.name(),
+                pos,
+                nuke.time_to_land()
+            );
+
+            room_memory.incoming_nukes.push(IncomingNuke {
+                pos,
+                impact_tick,
+                launch_room: nuke.launch_room_name(),
+            });
+            new_nukes = true;
+        }
+    }
+
+    if room_memory.incoming_nukes.is_empty() {
+        return;
+    }
+
+    cached_room.nuke_damage = nuke_damage_map(&room_memory.incoming_nukes);
+    cached_room.nuke_ramparts = plan_nuke_ramparts(cached_room);
+
+    if new_nukes {
+        alert_unsavable(cached_room);
+    }
+}
+
+// Total damage per tile, and the tick the first nuke hitting it lands.
+pub fn nuke_damage_map(nukes: &[IncomingNuke]) -> HashMap<RoomXY, (u32, u32)> {
+    let mut damage: HashMap<RoomXY, (u32, u32)> = HashMap::new();
+
+    for nuke in nukes {
+        for dx in -(NUKE_RANGE as i8)..=NUKE_RANGE as i8 {
+            for dy in -(NUKE_RANGE as i8)..=NUKE_RANGE as i8 {
+                if let Some(xy) = nuke.pos.checked_add((dx, dy)) {
+                    let hit = if dx == 0 && dy == 0 { NUKE_DAMAGE_CENTER } else { NUKE_DAMAGE_AREA };
+
+                    let entry = damage.entry(xy).or_insert((0, nuke.impact_tick));
+                    entry.0 += hit;
+                    entry.1 = entry.1.min(nuke.impact_tick);
+                }
+            }
+        }
+    }
+
+    damage
+}
+
+pub fn in_blast_radius(nukes: &[IncomingNuke], xy: RoomXY) -> bool {
+    nukes.iter().any(|nuke| nuke.pos.get_range_to(xy) <= NUKE_RANGE)
+}
+
+// Hits per tick the repairers we have out can put into ramparts.
+pub fn repair_throughput(cached_room: &CachedRoom) -> u32 {
+    let work_parts = cached_room
+        .creeps
+        .creeps_of_role
+        .get(&Role::Repairer)
+        .unwrap_or(&Vec::new())
+        .iter()
+        .filter_map(|name| game::creeps().get(name.to_string()))
+        .map(|creep| creep.body().iter().filter(|part| part.part() == Part::Work && part.hits() > 0).count() as u32)
+        .sum::<u32>();
+
+    work_parts * REPAIR_POWER as u32
+}
+
+// Ramparts worth pumping up before the nukes land, and the hits each one needs.
+// Ones landing first get the repair time first, anything we cant get there in time is left to die
+// so we dont sink energy into a rampart thats popping anyway.
+fn plan_nuke_ramparts(cached_room: &CachedRoom) -> HashMap<RoomXY, u32> {
+    let mut candidates = Vec::new();
+
+    for rampart in &cached_room.structures.ramparts {
+        let xy = rampart.pos().xy();
+
+        if let Some((damage, lands_at)) = cached_room.nuke_damage.get(&xy) {
+            let target = damage + NUKE_RAMPART_BUFFER;
+
+            if target > rampart.hits_max() {
+                continue;
+            }
+
+            candidates.push((xy, target, target.saturating_sub(rampart.hits()), *lands_at));
+        }
+    }
+
+    candidates.sort_by_key(|(_, _, needed, lands_at)| (*lands_at, *needed));
+
+    let throughput = repair_throughput(cached_room) as u64;
+    let mut committed = 0;
+    let mut saveable = HashMap::new();
+
+    for (xy, target, needed, lands_at) in candidates {
+        let ticks_left = lands_at.saturating_sub(game::time()) as u64;
+
+        if committed + needed as u64 > throughput * ticks_left {
+            continue;
+        }
+
+        committed += needed as u64;
+        saveable.insert(xy, target);
+    }
+
+    saveable
+}
+
+// How many hits a rampart needs to soak up everything headed its way, if we can get it there in time.
+pub fn rampart_nuke_target(cached_room: &CachedRoom, xy: RoomXY) -> Option<u32> {
+    cached_room.nuke_ramparts.get(&xy).copied()
+}
+
+// Work parts it would take to get every rampart that could hold its hit up in time.
+pub fn nuke_repair_parts(cached_room: &CachedRoom) -> u32 {
+    let mut needed = 0;
+    let mut first_landing = u32::MAX;
+
+    for rampart in &cached_room.structures.ramparts {
+        if let Some((damage, lands_at)) = cached_room.nuke_damage.get(&rampart.pos().xy()) {
+            let target = damage + NUKE_RAMPART_BUFFER;
+
+            if target <= rampart.hits_max() {
+                needed += target.saturating_sub(rampart.hits()) as u64;
+                first_landing = first_landing.min(*lands_at);
+            }
+        }
+    }
+
+    if needed == 0 {
+        return 0;
+    }
+
+    let ticks_left = first_landing.saturating_sub(game::time()).max(1) as u64;
+
+    needed.div_ceil(ticks_left * REPAIR_POWER as u64) as u32
+}
+
+// Anything without a rampart we can get up in time is gone.
+pub fn doomed_structures(cached_room: &CachedRoom) -> Vec<(RoomXY, StructureType)> {
+    let mut doomed = Vec::new();
+
+    for xy in cached_room.nuke_damage.keys() {
+        let structures = if let Some(structures) = cached_room.structures.structures_at_pos.get(xy) {
+            structures
+        } else {
+            continue;
+        };
+
+        if cached_room.nuke_ramparts.contains_key(xy) {
+            continue;
+        }
+
+        for structure in structures {
+            if *structure != StructureType::Rampart && *structure != StructureType::Road {
+                doomed.push((*xy, *structure));
+            }
+        }
+    }
+
+    doomed
+}
+
+fn alert_unsavable(cached_room: &CachedRoom) {
+    let doomed = doomed_structures(cached_room);
+
+    if !doomed.is_empty() {
+        info!("  [NUKES] Room {} is going to lose {:?}", cached_room.room.name(), doomed);
+    }
+
+    for (xy, structure) in doomed {
+        if structure == StructureType::Spawn || structure == StructureType::Storage {
+            warn!(
+                "Room {} is getting nuked and cant save its {:?} at {:?}",
+                cached_room.room.name(),
+                structure,
+                xy
+            );
+        }
+    }
+}
+
+// The storage sitter handles the energy, ghodium has to be hauled in.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn haul_nuker(cached_room: &mut CachedRoom) {
+    let nuker = if let Some(nuker) = &cached_room.structures.nuker {
+        nuker.clone()
+    } else {
+        return;
+    };
+
+    let in_nuker = nuker.store().get_used_capacity(Some(ResourceType::Ghodium));
+    let needed = NUKER_GHODIUM_CAPACITY.saturating_sub(in_nuker);
+
+    if needed == 0 {
+        return;
+    }
+
+    let amount = haul_from_stock(cached_room, ResourceType::Ghodium, needed, HaulingPriority::Minerals as u32 as f32);
+    if amount == 0 {
+        return;
+    }
+
+    let priority = scale_haul_priority(NUKER_GHODIUM_CAPACITY, in_nuker, HaulingPriority::Minerals, false);
+
+    cached_room.hauling.create_order(
+        nuker.raw_id(),
+        Some(nuker.structure_type()),
+        Some(ResourceType::Ghodium),
+        Some(amount),
+        priority,
+        HaulingType::Transfer,
+    );
+}
@@ -0,0 +1,98 @@
+use std::collections::HashSet;
+
+use log::info;
+use screeps::{game, RoomName};
+
+use crate::{
+    combat::nuke_planner::nuke_enemies,
+    constants::OBSERVER_RANGE,
+    memory::ScreepsMemory,
+    traits::intents_tracking::StructureObserverExtensionsTracking,
+};
+
+use super::cache::RoomCache;
+
+// Rooms we have goals in get looked at this many times more often.
+const GOAL_ROOM_WEIGHT: u32 = 5;
+// Dont bother re-observing anything fresher than this.
+const MIN_OBSERVE_AGE: u32 = 100;
+
+// Each observer looks at the stalest room it can reach. Whatever it sees shows up in
+// game::rooms next tick, where the government scouts it like any other visible room.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_observers(memory: &ScreepsMemory, cache: &RoomCache) {
+    let observers = cache
+        .my_rooms
+        .iter()
+        .filter_map(|room_name| Some((room_name, cache.rooms.get(room_name)?.structures.observer.as_ref()?)))
+        .collect::<Vec<_>>();
+
+    if observers.is_empty() {
+        return;
+    }
+
+    // Both of these get checked for every room in range of every observer, so work them out once up front.
+    let goal_rooms = goal_rooms(memory);
+    let visible_rooms = game::rooms().keys().collect::<HashSet<RoomName>>();
+    let mut observed = HashSet::new();
+
+    for (room_name, observer) in observers {
+        let mut best: Option<(RoomName, u32)> = None;
+
+        for dx in -(OBSERVER_RANGE as i32)..=OBSERVER_RANGE as i32 {
+            for dy in -(OBSERVER_RANGE as i32)..=OBSERVER_RANGE as i32 {
+                let target = if let Some(target) = room_name.checked_add((dx, dy)) {
+                    target
+                } else {
+                    continue;
+                };
+
+                if observed.contains(&target) || visible_rooms.contains(&target) {
+                    continue;
+                }
+
+                let age = memory
+                    .scouted_rooms
+                    .get(&target)
+                    .map_or(u32::MAX / GOAL_ROOM_WEIGHT, |scouted| game::time() - scouted.last_scouted);
+
+                if age < MIN_OBSERVE_AGE {
+                    continue;
+                }
+
+                let score = if goal_rooms.contains(&target) { age * GOAL_ROOM_WEIGHT } else { age };
+
+                if best.map_or(true, |(_, best_score)| score > best_score) {
+                    best = Some((target, score));
+                }
+            }
+        }
+
+        if let Some((target, _)) = best {
+            if observer.ITobserve_room(target).is_ok() {
+                info!("  [OBSERVER] Room {} is observing {}", room_name, target);
+                observed.insert(target);
+            }
+        }
+    }
+}
+
+fn goal_rooms(memory: &ScreepsMemory) -> HashSet<RoomName> {
+    let goals = &memory.goals;
+
+    // Claims on other shards arent anything we can see from here.
+    goals
+        .room_claim
+        .keys()
+        .filter(|target| target.shard.is_none())
+        .map(|target| &target.room)
+        .chain(goals.room_reservation.keys())
+        .chain(goals.remote_defense.keys())
+        .chain(goals.remote_invader_cleanup.keys())
+        .chain(goals.power_bank.keys())
+        .chain(goals.deposit.keys())
+        // Nuke targets need fresh eyes before anything gets launched at them.
+        .chain(nuke_enemies(memory).into_iter().flat_map(|enemy| enemy.owned_rooms.iter()))
+        .copied()
+        .collect()
+}
@@ -0,0 +1,594 @@
+// The base planner itself, working off a plain terrain buffer so it runs in game (driven a step
+// at a time by skippy_base) and offline from a terrain dump (the plan binary) alike.
+// Nothing in here is allowed to touch the game.
+//
+// Mappings:
+// 'c' = container
+// 'r' = road
+// 'e' = extension
+// 'p' = spawn
+// 'l' = link
+// 'q' = terminal
+// 'n' = nuker
+// 'f' = factory
+// 's' = storage
+// 'z' = power spawn
+// 'o' = observer
+// 'x' = lab
+// 't' = tower
+// 'W' = wall, 'E' = exit or next to one, only while planning.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+};
+
+use screeps::{LocalCostMatrix, RoomName, RoomXY, StructureType};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{RAMPART_BASE_MARGIN, RAMPART_CONTROLLER_MARGIN, RAMPART_SOURCE_MARGIN},
+    constants::{SWAMP_MASK, WALL_MASK},
+    utils::{distance_transform_matrix, new_xy},
+};
+
+use super::min_cut;
+
+// Where the stamps that other code cares about sit in stamp_list.
+pub const CORE_STAMP: usize = 0;
+pub const STORE_STAMP: usize = 1;
+pub const LAB_STAMP: usize = 2;
+
+pub fn stamp_list() -> Vec<(u8, Vec<Vec<char>>)> {
+    vec![
+        (4, ff_stamp()),
+        (3, store_stamp()),
+        (4, lab_stamp()),
+        (4, large_ext_stamp()),
+        (3, ext_stamp()),
+        (3, ext_stamp()),
+        (3, ext_stamp()),
+        (3, ext_stamp()),
+        (3, ext_stamp()),
+        (3, ext_stamp()),
+        (3, full_ext_stamp()),
+        (3, full_ext_stamp()),
+    ]
+}
+
+pub fn large_ext_stamp() -> Vec<Vec<char>> {
+    vec![
+        vec![' ', ' ', ' ', 'r', ' ', ' ', ' '],
+        vec![' ', ' ', 'r', 'e', 'r', ' ', ' '],
+        vec![' ', 'r', 'e', 'e', 'e', 'r', ' '],
+        vec!['r', 'e', 'e', ' ', 'e', 'e', 'r'],
+        vec![' ', 'r', 'e', 'e', 'e', 'r', ' '],
+        vec![' ', ' ', 'r', 'e', 'r', ' ', ' '],
+        vec![' ', ' ', ' ', 'r', ' ', ' ', ' '],
+    ]
+}
+
+// The link sits on the spawn center, fast fillers stand either side of it.
+// Same core the fast fillers and traffic already expect from the old bunker.
+pub fn ff_stamp() -> Vec<Vec<char>> {
+    vec![
+        vec!['r', 'r', 'r', 'r', 'r', 'r', 'r'],
+        vec!['r', 'e', 'e', 'e', 'e', 'e', 'r'],
+        vec!['r', 'e', 'p', 'e', 'p', 'e', 'r'],
+        vec!['r', 'c', ' ', 'l', ' ', 'c', 'r'],
+        vec!['r', 'e', 'e', 'p', 'e', 'e', 'r'],
+        vec!['r', 'e', 'e', 'e', 'e', 'e', 'r'],
+        vec!['r', 'r', 'r', 'r', 'r', 'r', 'r'],
+    ]
+}
+
+pub fn store_stamp() -> Vec<Vec<char>> {
+    vec![
+        vec!['r', 'r', 'r', 'r', 'r'],
+        vec!['r', 'l', 'r', 'q', 'r'],
+        vec!['r', 'n', ' ', 'f', 'r'],
+        vec!['r', 's', 'z', 'o', 'r'],
+        vec!['r', 'r', 'r', 'r', 'r'],
+    ]
+}
+
+pub fn lab_stamp() -> Vec<Vec<char>> {
+    vec![
+        vec![' ', ' ', ' ', 'r', ' ', ' ', ' '],
+        vec![' ', ' ', 'r', 'x', 'r', ' ', ' '],
+        vec![' ', 'r', 'x', 'x', 'r', 'r', ' '],
+        vec!['r', 'r', 'x', 'x', 'x', 'x', 'r'],
+        vec![' ', 'r', 'x', 'x', 'x', 'r', ' '],
+        vec![' ', ' ', 'r', 'r', 'r', ' ', ' '],
+        vec![' ', ' ', ' ', 'r', ' ', ' ', ' '],
+    ]
+}
+
+pub fn ext_stamp() -> Vec<Vec<char>> {
+    vec![
+        vec![' ', ' ', 'r', ' ', ' '],
+        vec![' ', 'r', 'e', 'r', ' '],
+        vec!['r', 'e', 't', 'e', 'r'],
+        vec![' ', 'r', 'e', 'r', ' '],
+        vec![' ', ' ', 'r', ' ', ' '],
+    ]
+}
+
+// Once all six towers are down, the last few extensions take the middle too.
+pub fn full_ext_stamp() -> Vec<Vec<char>> {
+    vec![
+        vec![' ', ' ', 'r', ' ', ' '],
+        vec![' ', 'r', 'e', 'r', ' '],
+        vec!['r', 'e', 'e', 'e', 'r'],
+        vec![' ', 'r', 'e', 'r', ' '],
+        vec![' ', ' ', 'r', ' ', ' '],
+    ]
+}
+
+pub fn char_to_structure(c: char) -> Option<StructureType> {
+    match c {
+        'c' => Some(StructureType::Container),
+        'r' => Some(StructureType::Road),
+        'l' => Some(StructureType::Link),
+        'p' => Some(StructureType::Spawn),
+        'e' => Some(StructureType::Extension),
+        'x' => Some(StructureType::Lab),
+        't' => Some(StructureType::Tower),
+        'q' => Some(StructureType::Terminal),
+        'n' => Some(StructureType::Nuker),
+        'f' => Some(StructureType::Factory),
+        's' => Some(StructureType::Storage),
+        'z' => Some(StructureType::PowerSpawn),
+        'o' => Some(StructureType::Observer),
+        _ => None,
+    }
+}
+
+pub fn index_to_xy(index: i32) -> RoomXY {
+    new_xy((index % 50) as u8, (index / 50) as u8)
+}
+
+pub fn xy_to_index(xy: RoomXY) -> i32 {
+    xy.y.u8() as i32 * 50 + xy.x.u8() as i32
+}
+
+// Terrain dumps store the raw buffer as a string of digits, one per tile.
+pub fn parse_terrain(terrain: &str) -> Option<Vec<u8>> {
+    let parsed = terrain
+        .chars()
+        .map(|c| c.to_digit(10).map(|digit| digit as u8))
+        .collect::<Option<Vec<_>>>()?;
+
+    (parsed.len() == 2500).then_some(parsed)
+}
+
+// Walls, and everything on or next to an exit, are off limits.
+pub fn blank_map(terrain: &[u8]) -> Vec<char> {
+    let mut map = terrain
+        .iter()
+        .take(2500)
+        .map(|tile| if tile & WALL_MASK != 0 { 'W' } else { ' ' })
+        .collect::<Vec<_>>();
+
+    let offsets = [-51, -50, -49, -1, 1, 49, 50, 51];
+    for i in 0..50 {
+        for j in [i, 2450 + i, i * 50, i * 50 + 49] {
+            if terrain[j] & WALL_MASK != 0 {
+                continue;
+            }
+
+            map[j] = 'E';
+            for offset in &offsets {
+                let k = j as i32 + offset;
+                if k < 0 || k as usize >= map.len() {
+                    continue;
+                }
+
+                if map[k as usize] == ' ' {
+                    map[k as usize] = 'E'
+                }
+            }
+        }
+    }
+
+    map
+}
+
+// Walking distance from the tiles around start to everywhere else, 255 for walls.
+// Exits are reachable, but we dont walk along them.
+pub fn flood_fill(terrain: &[u8], start: RoomXY) -> Vec<i32> {
+    let mut fill = terrain
+        .iter()
+        .take(2500)
+        .map(|tile| if tile & WALL_MASK != 0 { 255 } else { 0 })
+        .collect::<Vec<i32>>();
+
+    for i in 0..50 {
+        for j in [i, 2450 + i, i * 50, i * 50 + 49] {
+            if fill[j] == 0 {
+                fill[j] = -1;
+            }
+        }
+    }
+
+    let offset = [-51, -50, -49, -1, 1, 49, 50, 51];
+    let start_index = xy_to_index(start);
+
+    let mut steps = offset.iter().map(|off| start_index + off).collect::<HashSet<i32>>();
+
+    for i in 0..100 {
+        let mut next_steps = HashSet::new();
+
+        for j in steps.iter().filter(|j| (0..2500).contains(*j)) {
+            let tile = fill[*j as usize];
+
+            if tile == 0 {
+                fill[*j as usize] = i + 1;
+                next_steps.extend(offset.iter().map(|off| j + off));
+            } else if tile == -1 {
+                fill[*j as usize] = i + 1;
+
+                let x = j % 50;
+                let y = j / 50;
+
+                let along_exit: &[i32] = if x == 0 {
+                    &[-49, 1, 51]
+                } else if x == 49 {
+                    &[-51, -1, 49]
+                } else if y == 0 {
+                    &[49, 50, 51]
+                } else if y == 49 {
+                    &[-51, -50, -49]
+                } else {
+                    &[]
+                };
+
+                next_steps.extend(along_exit.iter().map(|off| j + off));
+            }
+        }
+
+        steps = next_steps;
+    }
+
+    fill
+}
+
+// How big a stamp fits centered on each tile. Roads count as open, stamps can share them.
+pub fn orth_wall_fill(map: &[char]) -> Vec<i32> {
+    let mut icm = LocalCostMatrix::new();
+
+    for (i, c) in map.iter().enumerate() {
+        icm.set(index_to_xy(i as i32), if [' ', 'r'].contains(c) { 255 } else { 0 });
+    }
+
+    let dt_res = distance_transform_matrix(icm);
+
+    map.iter()
+        .enumerate()
+        .map(|(i, c)| if *c == 'r' { 1 } else { dt_res.get(index_to_xy(i as i32)) as i32 })
+        .collect()
+}
+
+// Best center for the stamp, close to the controller and sources, and to the core once there is one.
+pub fn find_stamp_spot(
+    stamp_index: usize,
+    orth_fill: &[i32],
+    controller_fill: &[i32],
+    source_fills: &[Vec<i32>],
+    core: Option<i32>,
+) -> Option<i32> {
+    let stamp_size = stamp_list()[stamp_index].0 as i32;
+
+    let mut best_spot = None;
+    let mut lowest_score = 9001;
+
+    for i in 0..2500 {
+        if orth_fill[i] < stamp_size || orth_fill[i] >= 255 {
+            continue;
+        }
+
+        let mut score = controller_fill[i] * 3;
+        for fill in source_fills {
+            score += fill[i] * 2;
+        }
+
+        // Keep the rest of the base close to the core, haulers walk all of it.
+        if let Some(core) = core {
+            score += index_to_xy(i as i32).get_range_to(index_to_xy(core)) as i32 * 3;
+        }
+
+        if score < lowest_score {
+            lowest_score = score;
+            best_spot = Some(i as i32);
+        }
+    }
+
+    best_spot
+}
+
+// Whether every structure in the stamp lands on open ground. Roads can go missing, connect_stamps patches those up.
+pub fn stamp_fits(map: &[char], stamp_index: usize, center: i32) -> bool {
+    let (stamp_size, stamp_layout) = &stamp_list()[stamp_index];
+
+    let anchor_x = center % 50 - (*stamp_size as i32 - 1);
+    let anchor_y = center / 50 - (*stamp_size as i32 - 1);
+
+    stamp_layout.iter().enumerate().all(|(i, row)| {
+        row.iter().enumerate().all(|(j, stamp_char)| {
+            if [' ', 'r'].contains(stamp_char) {
+                return true;
+            }
+
+            let x = anchor_x + j as i32;
+            let y = anchor_y + i as i32;
+
+            (0..50).contains(&x) && (0..50).contains(&y) && map[(y * 50 + x) as usize] == ' '
+        })
+    })
+}
+
+pub fn stamp_onto_map(map: &mut [char], stamp_index: usize, center: i32) {
+    let (stamp_size, stamp_layout) = &stamp_list()[stamp_index];
+
+    let anchor_x = center % 50 - (*stamp_size as i32 - 1);
+    let anchor_y = center / 50 - (*stamp_size as i32 - 1);
+
+    for (i, row) in stamp_layout.iter().enumerate() {
+        for (j, stamp_char) in row.iter().enumerate() {
+            let x = anchor_x + j as i32;
+            let y = anchor_y + i as i32;
+
+            if !(0..50).contains(&x) || !(0..50).contains(&y) {
+                continue;
+            }
+
+            // Stamps can share roads, but nothing gets built over one.
+            let current = map[(y * 50 + x) as usize];
+            if *stamp_char != ' ' && (current == ' ' || (current == 'r' && *stamp_char == 'r')) {
+                map[(y * 50 + x) as usize] = *stamp_char;
+            }
+        }
+    }
+}
+
+fn road_cost(c: char) -> Option<u32> {
+    match c {
+        'r' => Some(1),
+        ' ' => Some(2),
+        // Right up against the exits, only if theres no other way.
+        'E' => Some(10),
+        _ => None,
+    }
+}
+
+// Cheapest walk from one tile to within range of another, over what the map leaves open.
+// The first tile isnt part of the path, same as the game pathfinder.
+pub fn path_on_map(map: &[char], from: i32, to: i32, range: u8) -> Option<Vec<i32>> {
+    let goal = index_to_xy(to);
+
+    let mut cost = vec![u32::MAX; 2500];
+    let mut came_from = vec![-1; 2500];
+    let mut open = BinaryHeap::new();
+
+    cost[from as usize] = 0;
+    open.push(Reverse((0, from)));
+
+    while let Some(Reverse((tile_cost, tile))) = open.pop() {
+        if tile_cost > cost[tile as usize] {
+            continue;
+        }
+
+        if tile != from && index_to_xy(tile).get_range_to(goal) <= range {
+            let mut path = vec![tile];
+            while came_from[*path.last().unwrap() as usize] != from {
+                path.push(came_from[*path.last().unwrap() as usize]);
+            }
+            path.reverse();
+
+            return Some(path);
+        }
+
+        let (x, y) = (tile % 50, tile / 50);
+        for (nx, ny) in (-1..=1).flat_map(|dx| (-1..=1).map(move |dy| (x + dx, y + dy))) {
+            if !(0..50).contains(&nx) || !(0..50).contains(&ny) || (nx, ny) == (x, y) {
+                continue;
+            }
+
+            let next = ny * 50 + nx;
+            let Some(step) = road_cost(map[next as usize]) else {
+                continue;
+            };
+
+            if tile_cost + step < cost[next as usize] {
+                cost[next as usize] = tile_cost + step;
+                came_from[next as usize] = tile;
+                open.push(Reverse((tile_cost + step, next)));
+            }
+        }
+    }
+
+    None
+}
+
+// Road every stamp back to the core, reusing roads we already have where we can.
+// Returns the stamps it couldnt reach.
+pub fn connect_stamps(map: &mut [char], core: i32, stamp_centers: &[i32]) -> Vec<i32> {
+    let mut unreachable = Vec::new();
+
+    for center in stamp_centers.iter().skip(1).filter(|center| **center >= 0) {
+        let Some(path) = path_on_map(map, core, *center, 1) else {
+            unreachable.push(*center);
+            continue;
+        };
+
+        for tile in path {
+            if [' ', 'E'].contains(&map[tile as usize]) {
+                map[tile as usize] = 'r';
+            }
+        }
+    }
+
+    unreachable
+}
+
+// Wall the base, controller and sources off from the exits with as few ramparts as we can.
+pub fn plan_ramparts(map: &[char], terrain: &[u8], controller: Option<RoomXY>, sources: &[RoomXY]) -> Option<Vec<u16>> {
+    let mut protected = map
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| char_to_structure(**c).is_some_and(|structure_type| structure_type != StructureType::Road))
+        .map(|(i, _)| (index_to_xy(i as i32), RAMPART_BASE_MARGIN))
+        .collect::<Vec<_>>();
+
+    // A controller or source sat on an exit cant be walled in, so dont let it sink the whole perimeter.
+    if let Some(controller) = controller.filter(|controller| !min_cut::next_to_exit(terrain, *controller)) {
+        protected.push((controller, RAMPART_CONTROLLER_MARGIN));
+    }
+
+    for source in sources.iter().filter(|source| !min_cut::next_to_exit(terrain, **source)) {
+        protected.push((*source, RAMPART_SOURCE_MARGIN));
+    }
+
+    let ramparts = min_cut::min_cut_ramparts(terrain, &protected)?;
+
+    Some(ramparts.iter().map(|xy| xy_to_index(*xy) as u16).collect())
+}
+
+// The layout string that gets saved, and the ramparts that go on top of what we cant afford to lose.
+pub fn finish_layout(map: &[char]) -> (String, Vec<u16>) {
+    let layout = map
+        .iter()
+        .map(|c| if char_to_structure(*c).is_some() { *c } else { ' ' })
+        .collect();
+
+    let key_ramparts = map
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| ['p', 't', 's', 'q', 'f', 'n', 'z'].contains(c))
+        .map(|(i, _)| i as u16)
+        .collect();
+
+    (layout, key_ramparts)
+}
+
+// What the offline planner reads, one per room. Terrain is the raw buffer as a string of digits.
+// The spawn is optional, without one the core goes wherever scores best.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlanRoom {
+    pub name: RoomName,
+    pub terrain: String,
+    pub controller: RoomXY,
+    pub sources: Vec<RoomXY>,
+    #[serde(default)]
+    pub mineral: Option<RoomXY>,
+    #[serde(default)]
+    pub spawn: Option<RoomXY>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BasePlan {
+    pub name: RoomName,
+    pub layout: String,
+    pub ramparts: Vec<u16>,
+    pub core: i32,
+    pub source_labs: [i32; 2],
+    // -1 for stamps that didnt fit.
+    pub stamp_centers: Vec<i32>,
+}
+
+// The whole plan in one go. In game the same steps get spread over ticks by skippy_base.
+pub fn plan_base(room: &PlanRoom) -> Option<BasePlan> {
+    let terrain = parse_terrain(&room.terrain)?;
+
+    let mut map = blank_map(&terrain);
+    let controller_fill = flood_fill(&terrain, room.controller);
+    let source_fills = room.sources.iter().map(|source| flood_fill(&terrain, *source)).collect::<Vec<_>>();
+
+    let mut core = None;
+    let mut source_labs = [0; 2];
+    let mut stamp_centers = Vec::new();
+
+    for stamp_index in 0..stamp_list().len() {
+        // The spawn center is one above the first spawn, same as plan_room. If the core
+        // doesnt fit around the spawn we have, theres no plan to be had.
+        let spot = match (stamp_index, room.spawn) {
+            (CORE_STAMP, Some(spawn)) if stamp_fits(&map, CORE_STAMP, xy_to_index(spawn) - 50) => Some(xy_to_index(spawn) - 50),
+            (CORE_STAMP, Some(_)) => return None,
+            _ => find_stamp_spot(stamp_index, &orth_wall_fill(&map), &controller_fill, &source_fills, core),
+        };
+
+        let Some(spot) = spot else {
+            stamp_centers.push(-1);
+            continue;
+        };
+
+        if stamp_index == CORE_STAMP {
+            core = Some(spot);
+        }
+        if stamp_index == LAB_STAMP {
+            source_labs = [spot - 50, spot + 1];
+        }
+
+        stamp_onto_map(&mut map, stamp_index, spot);
+        stamp_centers.push(spot);
+    }
+
+    let core = core?;
+    connect_stamps(&mut map, core, &stamp_centers);
+
+    let mut ramparts = plan_ramparts(&map, &terrain, Some(room.controller), &room.sources).unwrap_or_default();
+    let (layout, key_ramparts) = finish_layout(&map);
+
+    ramparts.extend(key_ramparts);
+    ramparts.sort_unstable();
+    ramparts.dedup();
+
+    Some(BasePlan {
+        name: room.name,
+        layout,
+        ramparts,
+        core,
+        source_labs,
+        stamp_centers,
+    })
+}
+
+// One line per row, plan chars as above plus '#' walls, '~' swamp, '+' ramparts on open
+// ground or roads, and the controller, sources and mineral as 'C', 'S' and 'M'.
+pub fn render_ascii(room: &PlanRoom, plan: &BasePlan) -> String {
+    let terrain = parse_terrain(&room.terrain).unwrap_or_else(|| vec![0; 2500]);
+    let mut tiles = plan.layout.chars().collect::<Vec<_>>();
+
+    for (i, tile) in tiles.iter_mut().enumerate() {
+        if *tile != ' ' {
+            continue;
+        }
+
+        if terrain[i] & WALL_MASK != 0 {
+            *tile = '#';
+        } else if terrain[i] & SWAMP_MASK != 0 {
+            *tile = '~';
+        }
+    }
+
+    for rampart in &plan.ramparts {
+        let tile = &mut tiles[*rampart as usize];
+        if [' ', '~', 'r'].contains(tile) {
+            *tile = '+';
+        }
+    }
+
+    tiles[xy_to_index(room.controller) as usize] = 'C';
+    for source in &room.sources {
+        tiles[xy_to_index(*source) as usize] = 'S';
+    }
+    if let Some(mineral) = room.mineral {
+        tiles[xy_to_index(mineral) as usize] = 'M';
+    }
+
+    tiles
+        .chunks(50)
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,140 @@
+// Plans in the community building planner format, {"rcl":8,"buildings":{"extension":{"pos":[{"x":1,"y":2}]}}}.
+// Rooms export whatever they build from, and an imported plan replaces the base planner for its room.
+
+use std::collections::HashMap;
+
+use screeps::{game, RoomXY, StructureType};
+
+use crate::{
+    memory::{BuildingPlannerPlan, BuildingPlannerPos, BuildingPlannerPositions, RoomMemory},
+    utils::new_xy,
+};
+
+use super::construction::planned_structures_for_rcl;
+
+// Every tile in the plan, in the order the planner listed them. Anything outside the room is dropped.
+pub fn plan_structures(plan: &BuildingPlannerPlan) -> Vec<(RoomXY, StructureType)> {
+    plan.buildings
+        .iter()
+        .flat_map(|(structure_type, positions)| {
+            positions
+                .pos
+                .iter()
+                .filter(|pos| pos.x < 50 && pos.y < 50)
+                .map(|pos| (new_xy(pos.x, pos.y), *structure_type))
+        })
+        .collect()
+}
+
+// Same idea as skippy_base::structures_for_rcl, but the planner already put them in the order it wants.
+// Ramparts and walls come along too, theres nothing else to plan them from.
+pub fn structures_for_rcl(plan: &BuildingPlannerPlan, rcl: u8) -> Vec<(RoomXY, StructureType)> {
+    let mut structures = Vec::new();
+
+    for (structure_type, positions) in &plan.buildings {
+        let allowed = match structure_type {
+            StructureType::Road if rcl >= 3 => positions.pos.len(),
+            StructureType::Container if rcl >= 2 => positions.pos.len(),
+            StructureType::Road | StructureType::Container => 0,
+            _ => structure_type.controller_structures(rcl as u32) as usize,
+        };
+
+        structures.extend(
+            positions
+                .pos
+                .iter()
+                .filter(|pos| pos.x < 50 && pos.y < 50)
+                .take(allowed)
+                .map(|pos| (new_xy(pos.x, pos.y), *structure_type)),
+        );
+    }
+
+    structures
+}
+
+pub fn from_structures(name: String, structures: Vec<(RoomXY, StructureType)>) -> BuildingPlannerPlan {
+    let mut buildings: HashMap<StructureType, BuildingPlannerPositions> = HashMap::new();
+
+    for (xy, structure_type) in structures {
+        let (x, y) = (xy.x.u8(), xy.y.u8());
+        let positions = &mut buildings.entry(structure_type).or_insert(BuildingPlannerPositions { pos: Vec::new() }).pos;
+
+        if !positions.iter().any(|pos| pos.x == x && pos.y == y) {
+            positions.push(BuildingPlannerPos { x, y });
+        }
+    }
+
+    BuildingPlannerPlan {
+        name: Some(name),
+        shard: Some(game::shard::name()),
+        rcl: 8,
+        buildings,
+    }
+}
+
+// The full RCL 8 plan the room builds from, imported, from the base planner, or the fixed bunker.
+pub fn export_room_plan(memory: &RoomMemory) -> BuildingPlannerPlan {
+    from_structures(memory.name.to_string(), planned_structures_for_rcl(memory, 8))
+}
+
+// The rest of the code works off spawn_center and storage_center, so an imported plan has to give us both.
+// Spawn center sits above the first spawn like it does for our own plans, and the storage center is
+// whichever free tile next to the storage can reach the most of the core.
+pub fn plan_centers(plan: &BuildingPlannerPlan, existing_spawns: &[RoomXY]) -> Result<(RoomXY, Option<RoomXY>), String> {
+    let structures = plan_structures(plan);
+    let planned = |structure_type: StructureType| structures.iter().filter(move |(_, planned_type)| *planned_type == structure_type).map(|(xy, _)| *xy);
+
+    let spawns = planned(StructureType::Spawn).collect::<Vec<_>>();
+    if spawns.is_empty() {
+        return Err("plan has no spawns".to_string());
+    }
+
+    // Dont hand the room a plan that pretends its spawn isnt there.
+    let first_spawn = if existing_spawns.is_empty() {
+        spawns[0]
+    } else {
+        *existing_spawns
+            .iter()
+            .find(|spawn| spawns.contains(spawn))
+            .ok_or_else(|| "none of the rooms spawns are in the plan".to_string())?
+    };
+
+    if first_spawn.y.u8() == 0 {
+        return Err("spawn is on the room edge".to_string());
+    }
+    let spawn_center = new_xy(first_spawn.x.u8(), first_spawn.y.u8() - 1);
+
+    let Some(storage) = planned(StructureType::Storage).next() else {
+        return Ok((spawn_center, None));
+    };
+
+    let core = [
+        StructureType::Storage,
+        StructureType::Terminal,
+        StructureType::Link,
+        StructureType::Factory,
+        StructureType::PowerSpawn,
+        StructureType::Nuker,
+    ];
+    let walkable = |xy: RoomXY| {
+        structures
+            .iter()
+            .all(|(planned_xy, structure_type)| *planned_xy != xy || matches!(structure_type, StructureType::Road | StructureType::Rampart | StructureType::Container))
+    };
+
+    let (storage_x, storage_y) = (storage.x.u8() as i32, storage.y.u8() as i32);
+    let storage_center = (-1..=1)
+        .flat_map(|dx| (-1..=1).map(move |dy| (storage_x + dx, storage_y + dy)))
+        .filter(|(x, y)| (1..49).contains(x) && (1..49).contains(y))
+        .map(|(x, y)| new_xy(x as u8, y as u8))
+        .filter(|xy| walkable(*xy))
+        .max_by_key(|xy| {
+            structures
+                .iter()
+                .filter(|(planned_xy, structure_type)| core.contains(structure_type) && planned_xy.get_range_to(*xy) <= 1)
+                .count()
+        })
+        .ok_or_else(|| "nowhere for the storage sitter to stand".to_string())?;
+
+    Ok((spawn_center, Some(storage_center)))
+}
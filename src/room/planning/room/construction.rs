@@ -1,15 +1,18 @@
 use std::vec;
 
 use log::info;
-use screeps::{HasId, HasPosition, Position, Room, StructureProperties, StructureType};
+use screeps::{HasId, HasPosition, Position, Room, RoomXY, StructureProperties, StructureType};
 
 use crate::{
     heap,
-    memory::ScreepsMemory,
+    memory::{RoomMemory, ScreepsMemory},
     room::cache::{CachedRoom, RoomCache},
     traits::position::PositionExtensions,
+    utils::new_xy,
 };
 
+use super::{building_planner, skippy_base};
+
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 fn find_pos_most_accessible(
     start_pos: &Position,
@@ -294,6 +297,47 @@ pub fn plan_containers_and_links(room: &Room, room_cache: &CachedRoom) {
     }
 }
 
+// Everything the room should have at this RCL, roads, containers and ramparts included.
+// Imported plans win, then rooms the planner is done with build from its layout, the rest fall back on the bunker.
+// Anything that cares about the base layout should go through here, so they all agree on which plan that is.
+pub fn planned_structures_for_rcl(room_memory: &RoomMemory, rcl: u8) -> Vec<(RoomXY, StructureType)> {
+    if let Some(plan) = &room_memory.imported_plan {
+        return building_planner::structures_for_rcl(plan, rcl);
+    }
+
+    if let Some(plan) = skippy_base::finished_plan(room_memory) {
+        let mut structures = skippy_base::structures_for_rcl(plan, rcl);
+        structures.extend(skippy_base::planned_ramparts(plan).into_iter().map(|xy| (xy, StructureType::Rampart)));
+
+        return structures;
+    }
+
+    let levels: [fn() -> Vec<(i8, i8, StructureType)>; 7] = [
+        get_rcl_2_plan,
+        get_rcl_3_plan,
+        get_rcl_4_plan,
+        get_rcl_5_plan,
+        get_rcl_6_plan,
+        get_rcl_7_plan,
+        get_rcl_8_plan,
+    ];
+
+    let mut plan = levels.iter().take((rcl as usize).saturating_sub(1)).flat_map(|level| level()).collect::<Vec<_>>();
+    plan.extend(get_roads_and_ramparts());
+
+    bunker_positions(plan, room_memory.spawn_center)
+}
+
+// The fixed bunker offsets are from the tile under the spawn center.
+pub fn bunker_positions(plan: Vec<(i8, i8, StructureType)>, spawn_center: RoomXY) -> Vec<(RoomXY, StructureType)> {
+    let offset_x = spawn_center.x.u8() as i8;
+    let offset_y = spawn_center.y.u8() as i8 + 1;
+
+    plan.into_iter()
+        .map(|(x, y, structure_type)| (new_xy((x + offset_x) as u8, (y + offset_y) as u8), structure_type))
+        .collect()
+}
+
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn get_containers() -> Vec<(i8, i8, StructureType)> {
     vec![
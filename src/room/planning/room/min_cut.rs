@@ -0,0 +1,311 @@
+use std::collections::VecDeque;
+
+use screeps::RoomXY;
+
+use crate::{constants::WALL_MASK, utils::new_xy};
+
+// Every tile is split in two, an in and an out node. Cutting the edge between them is a rampart.
+// Protected tiles hang off the source, exits drain into the sink, and the max flow between
+// them is the smallest number of ramparts that keeps the two apart.
+
+const INFINITE: i32 = i32::MAX / 2;
+
+const TILE_COUNT: usize = 2500;
+const SOURCE: usize = TILE_COUNT * 2;
+const SINK: usize = TILE_COUNT * 2 + 1;
+
+struct Edge {
+    to: usize,
+    capacity: i32,
+}
+
+struct FlowGraph {
+    edges: Vec<Edge>,
+    adjacency: Vec<Vec<usize>>,
+    level: Vec<i32>,
+    next_edge: Vec<usize>,
+}
+
+impl FlowGraph {
+    fn new(nodes: usize) -> Self {
+        FlowGraph {
+            edges: Vec::new(),
+            adjacency: vec![Vec::new(); nodes],
+            level: vec![-1; nodes],
+            next_edge: vec![0; nodes],
+        }
+    }
+
+    // Reverse edges sit right after their forward edge, so edge ^ 1 is always the other one.
+    fn add_edge(&mut self, from: usize, to: usize, capacity: i32) {
+        self.adjacency[from].push(self.edges.len());
+        self.edges.push(Edge { to, capacity });
+
+        self.adjacency[to].push(self.edges.len());
+        self.edges.push(Edge { to: from, capacity: 0 });
+    }
+
+    fn build_levels(&mut self) -> bool {
+        self.level.iter_mut().for_each(|level| *level = -1);
+        self.level[SOURCE] = 0;
+
+        let mut queue = VecDeque::from([SOURCE]);
+        while let Some(node) = queue.pop_front() {
+            for edge_index in self.adjacency[node].iter() {
+                let edge = &self.edges[*edge_index];
+
+                if edge.capacity > 0 && self.level[edge.to] < 0 {
+                    self.level[edge.to] = self.level[node] + 1;
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+
+        self.level[SINK] >= 0
+    }
+
+    fn push_flow(&mut self, node: usize, flow: i32) -> i32 {
+        if node == SINK {
+            return flow;
+        }
+
+        while self.next_edge[node] < self.adjacency[node].len() {
+            let edge_index = self.adjacency[node][self.next_edge[node]];
+            let (to, capacity) = (self.edges[edge_index].to, self.edges[edge_index].capacity);
+
+            if capacity > 0 && self.level[to] == self.level[node] + 1 {
+                let pushed = self.push_flow(to, flow.min(capacity));
+
+                if pushed > 0 {
+                    self.edges[edge_index].capacity -= pushed;
+                    self.edges[edge_index ^ 1].capacity += pushed;
+
+                    return pushed;
+                }
+            }
+
+            self.next_edge[node] += 1;
+        }
+
+        0
+    }
+
+    // Dinic, returns None if the protected tiles cant be cut off at all.
+    fn max_flow(&mut self) -> Option<i32> {
+        let mut total = 0;
+
+        while self.build_levels() {
+            self.next_edge.iter_mut().for_each(|next| *next = 0);
+
+            loop {
+                let pushed = self.push_flow(SOURCE, INFINITE);
+                if pushed == 0 {
+                    break;
+                }
+
+                total += pushed;
+                if total >= INFINITE {
+                    return None;
+                }
+            }
+        }
+
+        Some(total)
+    }
+
+    fn reachable_from_source(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.adjacency.len()];
+        reachable[SOURCE] = true;
+
+        let mut queue = VecDeque::from([SOURCE]);
+        while let Some(node) = queue.pop_front() {
+            for edge_index in self.adjacency[node].iter() {
+                let edge = &self.edges[*edge_index];
+
+                if edge.capacity > 0 && !reachable[edge.to] {
+                    reachable[edge.to] = true;
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+
+        reachable
+    }
+}
+
+fn neighbours(x: i32, y: i32) -> impl Iterator<Item = (i32, i32)> {
+    (-1..=1)
+        .flat_map(move |dx| (-1..=1).map(move |dy| (x + dx, y + dy)))
+        .filter(move |(nx, ny)| (*nx != x || *ny != y) && (0..50).contains(nx) && (0..50).contains(ny))
+}
+
+// Tiles within range of an exit, exits themselves included.
+fn near_exits(terrain: &[u8], range: i32) -> Vec<bool> {
+    let mut near = vec![false; TILE_COUNT];
+
+    for i in 0..50 {
+        for (x, y) in [(i, 0), (i, 49), (0, i), (49, i)] {
+            if terrain[(y * 50 + x) as usize] & WALL_MASK != 0 {
+                continue;
+            }
+
+            for nx in (x - range).max(0)..=(x + range).min(49) {
+                for ny in (y - range).max(0)..=(y + range).min(49) {
+                    near[(ny * 50 + nx) as usize] = true;
+                }
+            }
+        }
+    }
+
+    near
+}
+
+// On or next to an exit, where nothing can be built and nothing can be walled in.
+pub fn next_to_exit(terrain: &[u8], xy: RoomXY) -> bool {
+    let (x, y) = (xy.x.u8() as i32, xy.y.u8() as i32);
+
+    neighbours(x, y)
+        .chain([(x, y)])
+        .any(|(nx, ny)| (nx == 0 || nx == 49 || ny == 0 || ny == 49) && terrain[(ny * 50 + nx) as usize] & WALL_MASK == 0)
+}
+
+// The smallest set of ramparts that walls off every protected tile, plus its margin, from the exits.
+// Margins are chebyshev, so a margin of 3 keeps ranged attackers off the tile itself.
+// Terrain is the raw buffer from the room terrain. Returns None if there is no cut,
+// which only happens if something protected is right up against an exit.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn min_cut_ramparts(terrain: &[u8], protected: &[(RoomXY, u8)]) -> Option<Vec<RoomXY>> {
+    let walkable = |x: i32, y: i32| terrain[(y * 50 + x) as usize] & WALL_MASK == 0;
+
+    // Nothing can be built within one of an exit, and protecting anything within two
+    // of one leaves nowhere to put the rampart. Margins just get trimmed back, but
+    // the protected tile itself being there means there is no cut at all.
+    let unbuildable = near_exits(terrain, 1);
+    let too_close = near_exits(terrain, 2);
+
+    if protected.iter().any(|(xy, _)| next_to_exit(terrain, *xy)) {
+        return None;
+    }
+
+    let mut is_protected = vec![false; TILE_COUNT];
+    for (xy, margin) in protected {
+        let (x, y, margin) = (xy.x.u8() as i32, xy.y.u8() as i32, *margin as i32);
+
+        for nx in (x - margin).max(0)..=(x + margin).min(49) {
+            for ny in (y - margin).max(0)..=(y + margin).min(49) {
+                let index = (ny * 50 + nx) as usize;
+
+                if walkable(nx, ny) && !too_close[index] {
+                    is_protected[index] = true;
+                }
+            }
+        }
+    }
+
+    if !is_protected.iter().any(|protected| *protected) {
+        return Some(Vec::new());
+    }
+
+    let mut graph = FlowGraph::new(TILE_COUNT * 2 + 2);
+
+    for y in 0..50 {
+        for x in 0..50 {
+            if !walkable(x, y) {
+                continue;
+            }
+
+            let index = (y * 50 + x) as usize;
+            let (tile_in, tile_out) = (index * 2, index * 2 + 1);
+
+            graph.add_edge(tile_in, tile_out, if unbuildable[index] { INFINITE } else { 1 });
+
+            if is_protected[index] {
+                graph.add_edge(SOURCE, tile_out, INFINITE);
+            }
+
+            if x == 0 || x == 49 || y == 0 || y == 49 {
+                graph.add_edge(tile_out, SINK, INFINITE);
+            }
+
+            for (nx, ny) in neighbours(x, y) {
+                if walkable(nx, ny) {
+                    graph.add_edge(tile_out, (ny * 50 + nx) as usize * 2, INFINITE);
+                }
+            }
+        }
+    }
+
+    graph.max_flow()?;
+
+    let reachable = graph.reachable_from_source();
+
+    let ramparts = (0..TILE_COUNT)
+        .filter(|index| reachable[index * 2] && !reachable[index * 2 + 1])
+        .map(|index| new_xy((index % 50) as u8, (index / 50) as u8))
+        .collect();
+
+    Some(ramparts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_terrain() -> Vec<u8> {
+        vec![0; TILE_COUNT]
+    }
+
+    fn set_wall(terrain: &mut [u8], x: u8, y: u8) {
+        terrain[y as usize * 50 + x as usize] |= WALL_MASK;
+    }
+
+    #[test]
+    fn nothing_protected_needs_no_ramparts() {
+        assert_eq!(min_cut_ramparts(&open_terrain(), &[]), Some(Vec::new()));
+    }
+
+    #[test]
+    fn cuts_exactly_the_chokes() {
+        // A walled box with a one tile gap on the left and right, the gaps are the only way in.
+        let mut terrain = open_terrain();
+        for i in 10..=40 {
+            set_wall(&mut terrain, 10, i);
+            set_wall(&mut terrain, 40, i);
+            set_wall(&mut terrain, i, 10);
+            set_wall(&mut terrain, i, 40);
+        }
+        terrain[25 * 50 + 10] = 0;
+        terrain[25 * 50 + 40] = 0;
+
+        let ramparts = min_cut_ramparts(&terrain, &[(new_xy(25, 25), 3)]);
+
+        assert_eq!(ramparts, Some(vec![new_xy(10, 25), new_xy(40, 25)]));
+    }
+
+    #[test]
+    fn corridor_is_cut_next_to_the_protected_tile() {
+        // Single row corridor running exit to exit.
+        let mut terrain = vec![WALL_MASK; TILE_COUNT];
+        for x in 0..50 {
+            terrain[25 * 50 + x] = 0;
+        }
+
+        let ramparts = min_cut_ramparts(&terrain, &[(new_xy(25, 25), 0)]);
+
+        assert_eq!(ramparts, Some(vec![new_xy(24, 25), new_xy(26, 25)]));
+    }
+
+    #[test]
+    fn no_cut_next_to_an_exit() {
+        assert_eq!(min_cut_ramparts(&open_terrain(), &[(new_xy(1, 25), 0)]), None);
+    }
+
+    #[test]
+    fn margins_near_exits_get_trimmed() {
+        // The margin reaches the exit, but the tile itself is far enough off to wall in.
+        let ramparts = min_cut_ramparts(&open_terrain(), &[(new_xy(25, 5), 4)]).unwrap();
+
+        assert!(!ramparts.is_empty());
+        assert!(ramparts.iter().all(|xy| xy.y.u8() >= 2));
+    }
+}
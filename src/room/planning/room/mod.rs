@@ -3,9 +3,13 @@ use std::collections::HashMap;
 use log::info;
 use screeps::{find, game, pathfinder::SearchResults, HasPosition, Room, RoomXY, StructureProperties};
 
-use crate::{memory::{RoomMemory, ScreepsMemory}, room::cache::RoomCache, traits::{intents_tracking::RoomExtensionsTracking, room::RoomExtensions}};
+use crate::{memory::{FactoryMemory, LabMemory, RoomMemory, ScreepsMemory}, room::cache::RoomCache, traits::{intents_tracking::RoomExtensionsTracking, room::RoomExtensions}};
 
+pub mod base_plan;
+pub mod building_planner;
 pub mod construction;
+pub mod min_cut;
+pub mod rebuild;
 pub mod structure_visuals;
 pub mod remotes;
 pub mod roads;
@@ -66,7 +70,14 @@ pub fn plan_room(room: &Room, memory: &mut ScreepsMemory, cache: &mut RoomCache)
         storage_center: store_pos,
         planned_paths: HashMap::new(),
 
-        skippy_planner: None,
+        // New rooms get the base planner, the bunker is only kept around for rooms already built from it.
+        skippy_planner: Some(skippy_base::new_plan()),
+        imported_plan: None,
+
+        labs: LabMemory::default(),
+        factory: FactoryMemory::default(),
+        incoming_nukes: Vec::new(),
+        rebuild_queue: Vec::new(),
 
         avg_spawn_expense: 0.0,
         income: 0,
@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+
+use log::info;
+use screeps::{game, HasPosition, Room, RoomXY, StructureType};
+
+use crate::{
+    memory::RoomMemory,
+    room::{cache::CachedRoom, nukes},
+    traits::intents_tracking::RoomExtensionsTracking,
+};
+
+use super::construction::planned_structures_for_rcl;
+
+// What gets put back first, lowest first. Spawns and towers keep the room alive.
+pub fn rebuild_priority(structure_type: StructureType) -> u8 {
+    match structure_type {
+        StructureType::Spawn => 0,
+        StructureType::Tower => 1,
+        StructureType::Storage => 2,
+        StructureType::Extension => 3,
+        StructureType::Terminal => 4,
+        StructureType::Link => 5,
+        StructureType::Container => 6,
+        StructureType::Lab | StructureType::Factory | StructureType::PowerSpawn => 7,
+        StructureType::Nuker | StructureType::Observer => 8,
+        StructureType::Rampart | StructureType::Wall => 9,
+        _ => 10,
+    }
+}
+
+// Sites count as structures in structures_at_pos, so keep track of them on our own.
+fn site_spots(room_cache: &CachedRoom) -> HashSet<(RoomXY, StructureType)> {
+    room_cache
+        .structures
+        .construction_sites
+        .iter()
+        .map(|site| (site.pos().xy(), site.structure_type()))
+        .collect()
+}
+
+fn is_standing(room_cache: &CachedRoom, sites: &HashSet<(RoomXY, StructureType)>, xy: RoomXY, structure_type: StructureType) -> bool {
+    room_cache.structures.structures_at_pos.get(&xy).is_some_and(|types| types.contains(&structure_type))
+        && !sites.contains(&(xy, structure_type))
+}
+
+// Diffs the plan against whats standing whenever the rooms structures change.
+// Anything we had and dont anymore goes in the queue. Returns true if something got lost.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn update_rebuild_queue(room_memory: &mut RoomMemory, room_cache: &mut CachedRoom) -> bool {
+    // First look since a reset, pick up where memory left off.
+    if !room_cache.room_heap_cache.rebuild_loaded {
+        room_cache.room_heap_cache.rebuild_queue = room_memory.rebuild_queue.clone();
+        room_cache.room_heap_cache.rebuild_loaded = true;
+    }
+
+    if room_cache.structures.structure_hash == room_cache.room_heap_cache.structure_hash {
+        return false;
+    }
+
+    let sites = site_spots(room_cache);
+    let mut lost = Vec::new();
+    let mut standing = Vec::new();
+
+    for (xy, structure_type) in planned_structures_for_rcl(room_memory, room_cache.rcl) {
+        if is_standing(room_cache, &sites, xy, structure_type) {
+            standing.push((xy, structure_type));
+        } else if room_cache.room_heap_cache.built.contains(&(xy, structure_type)) {
+            lost.push((xy, structure_type));
+        }
+    }
+
+    let queue_before = room_cache.room_heap_cache.rebuild_queue.len();
+    room_cache.room_heap_cache.rebuild_queue.retain(|(xy, structure_type)| !standing.contains(&(*xy, *structure_type)));
+
+    let heap = &mut room_cache.room_heap_cache;
+    heap.structure_hash = room_cache.structures.structure_hash;
+    heap.built.extend(standing);
+
+    for entry in &lost {
+        heap.built.remove(entry);
+        heap.rebuild_queue.push(*entry);
+
+        info!("  [REBUILD] Lost {:?} at {}, {} in {}, queued for rebuild", entry.1, entry.0.x.u8(), entry.0.y.u8(), room_memory.name);
+    }
+
+    heap.rebuild_queue.sort_by_key(|(_, structure_type)| rebuild_priority(*structure_type));
+
+    if heap.rebuild_queue.len() < queue_before {
+        info!("  [REBUILD] {} rebuilds left in {}", heap.rebuild_queue.len(), room_memory.name);
+    }
+
+    if room_memory.rebuild_queue != heap.rebuild_queue {
+        room_memory.rebuild_queue = heap.rebuild_queue.clone();
+    }
+
+    !lost.is_empty()
+}
+
+// Put sites down for everything queued, right away instead of waiting on the construction pass.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn place_rebuild_sites(room: &Room, room_memory: &RoomMemory, room_cache: &CachedRoom) {
+    let sites = site_spots(room_cache);
+
+    for (xy, structure_type) in &room_cache.room_heap_cache.rebuild_queue {
+        if sites.contains(&(*xy, *structure_type)) {
+            continue;
+        }
+
+        // No point rebuilding what the next nuke takes out again.
+        if nukes::in_blast_radius(&room_memory.incoming_nukes, *xy) {
+            continue;
+        }
+
+        let _ = room.ITcreate_construction_site(xy.x.u8(), xy.y.u8(), *structure_type, None);
+    }
+}
+
+pub fn run_rebuilds(room: &Room, room_memory: &mut RoomMemory, room_cache: &mut CachedRoom) {
+    let lost_something = update_rebuild_queue(room_memory, room_cache);
+
+    if !room_cache.room_heap_cache.rebuild_queue.is_empty() && (lost_something || game::time() % 50 == 0) {
+        place_rebuild_sites(room, room_memory, room_cache);
+    }
+}
+
+// Rebuild sites come first for builders, in queue order, everything else after.
+pub fn rebuild_site_order(room_cache: &CachedRoom, xy: RoomXY, structure_type: StructureType) -> usize {
+    room_cache
+        .room_heap_cache
+        .rebuild_queue
+        .iter()
+        .position(|entry| *entry == (xy, structure_type))
+        .unwrap_or(usize::MAX)
+}
@@ -4,7 +4,7 @@ use screeps::{
 };
 
 use crate::{
-    config, constants, goal_memory::RemoteInvaderCleanup, memory::{RemoteRoomMemory, ScreepsMemory}, profiling::timing::PATHFIND_CPU, room::{cache::RoomCache, democracy::remote_path_call}, traits::{position::RoomXYExtensions, room::{RoomExtensions, RoomType}}, utils
+    config, constants, goal_memory::RemoteInvaderCleanup, memory::{RemoteRoomMemory, ScreepsMemory}, profiling::timing::PATHFIND_CPU, room::{cache::RoomCache, democracy::remote_path_call, source_keepers::is_keeper_room}, traits::{position::RoomXYExtensions, room::{RoomExtensions, RoomType}}, utils
 };
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -18,12 +18,13 @@ pub fn fetch_possible_remotes(
     // Little high on CPU, but its run every 3k ticks, so its fine. I guess.
     let mut pre_existing = Vec::new();
     let adjacent_rooms = room.get_adjacent(2);
+    let allow_keepers = room_cache.rcl >= config::SK_MINING_RCL;
 
     // Go through all the adjacent rooms and rank them
     let mut possible_remotes = Vec::new();
 
     for room_name in adjacent_rooms {
-        let rank = rank_remote_room(memory, &room_name, &room_cache.spawn_center.unwrap().as_position(&room.name()));
+        let rank = rank_remote_room(memory, &room_name, &room_cache.spawn_center.unwrap().as_position(&room.name()), allow_keepers);
 
         if rank == u32::MAX {
             continue;
@@ -37,6 +38,17 @@ pub fn fetch_possible_remotes(
     // Sort the remotes by rank - ascending
     possible_remotes.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
 
+    // Each keeper room needs its own killer, so only take the best few.
+    let mut keeper_rooms = 0;
+    possible_remotes.retain(|(room_name, _)| {
+        if !is_keeper_room(room_name) {
+            return true;
+        }
+
+        keeper_rooms += 1;
+        keeper_rooms <= config::SK_REMOTES_PER_ROOM
+    });
+
     info!("  [REMOTES] Found {} possible remotes, picking...", possible_remotes.len());
 
     let room_memory = memory.rooms.get_mut(&room.name()).unwrap();
@@ -126,14 +138,20 @@ pub fn rank_remote_room(
     memory: &ScreepsMemory,
     remote_room: &RoomName,
     measure_pos: &Position,
+    allow_keepers: bool,
 ) -> u32 {
     let mut i = 0;
     let mut current_avg = 0;
 
     // If we have no scouting data
     let scouted = memory.scouted_rooms.get(remote_room);
-    // This >= 4 check is for SK rooms, idk why, or how, but my room classification is borked.
-    if scouted.is_none() || scouted.unwrap().sources.is_none() || scouted.unwrap().sources.as_ref().unwrap().len() >= 3 {
+    // This >= 3 check is for SK rooms, idk why, or how, but my room classification is borked.
+    let keeper_room = is_keeper_room(remote_room);
+    if scouted.is_none() || scouted.unwrap().sources.is_none() || (scouted.unwrap().sources.as_ref().unwrap().len() >= 3 && !keeper_room) {
+        return u32::MAX;
+    }
+
+    if keeper_room && !allow_keepers {
         return u32::MAX;
     }
 
@@ -168,7 +186,7 @@ pub fn rank_remote_room(
         return u32::MAX;
     }
 
-    if scouted.unwrap().room_type == RoomType::SourceKeeper
+    if (scouted.unwrap().room_type == RoomType::SourceKeeper && !allow_keepers)
         || scouted.unwrap().room_type == RoomType::Highway
         || scouted.unwrap().room_type == RoomType::Center
     {
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use screeps::{
     game,
     pathfinder::{self, MultiRoomCostResult, SearchOptions},
-    CircleStyle, HasPosition, LocalCostMatrix, Position, Room, RoomCoordinate, RoomName, RoomXY,
+    CircleStyle, HasPosition, LocalCostMatrix, Position, Room, RoomName, RoomXY,
     StructureProperties, StructureType,
 };
 
@@ -11,7 +11,7 @@ use crate::{
     compression::{decode_pos_list, encode_pos_list}, constants::{SWAMP_MASK, WALKABLE_STRUCTURES, WALL_MASK}, memory::ScreepsMemory, profiling::timing::PATHFIND_CPU, room::cache::RoomCache, traits::position::RoomXYExtensions
 };
 
-use super::construction::get_all_structure_plans;
+use super::construction::planned_structures_for_rcl;
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn plan_main_room_roads(
@@ -265,25 +265,12 @@ fn room_callback(
     }
 
     if let Some(room_memory) = memory.rooms.get(room_name) {
-        let offset_x = room_memory.spawn_center.x.u8();
-        let offset_y = room_memory.spawn_center.y.u8() + 1;
-
-        let all_plans = get_all_structure_plans();
-
-        for plan in all_plans {
-            if plan.2 == StructureType::Road {
+        for (xy, structure_type) in planned_structures_for_rcl(room_memory, 8) {
+            if structure_type == StructureType::Road {
                 continue;
             }
 
-            let x = plan.0 as u8 + offset_x;
-            let y = plan.1 as u8 + offset_y;
-
-            let xy = RoomXY::new(
-                RoomCoordinate::new(x).unwrap(),
-                RoomCoordinate::new(y).unwrap(),
-            );
-
-            if WALKABLE_STRUCTURES.contains(&plan.2) {
+            if WALKABLE_STRUCTURES.contains(&structure_type) {
                 matrix.set(xy, 10);
             } else {
                 matrix.set(xy, 255);
@@ -1,218 +1,206 @@
-// Mappings:
-// 'c' = container
-// 'r' = road
-// 'e' = extension
-// 'p' = spawn
-// 'l' = link
-// 'q' = terminal
-// 'n' = nuker
-// 'f' = factory
-// 's' = storage
-// 'z' = power spawn
-// 'o' = observer
-// 'x' = lab
-// 't' = tower
+// Drives the planner in base_plan a step a tick, keeping the scratch space in memory between them.
+// Mappings are documented over there.
 
 use std::collections::HashMap;
 
 use log::info;
-use screeps::{find, game, CircleStyle, CostMatrixSet, HasId, HasPosition, LocalCostMatrix, Room, StructureType, Terrain, TextStyle};
+use screeps::{find, game, CircleStyle, HasId, HasPosition, Room, RoomXY, StructureType, TextStyle};
 
-use crate::{
-    memory::{RoomMemory, SkippyMem}, utils::{self, new_xy},
-};
-
-use super::structure_visuals::RoomVisualExt;
-
-pub fn stamp_list() -> Vec<(u8, Vec<Vec<char>>)> {
-    vec![
-        (4, ff_stamp()),
-        (3, store_stamp()),
-        (4, lab_stamp()),
-        (4, large_ext_stamp()),
-        (3, ext_stamp()),
-        (3, ext_stamp()),
-        (3, ext_stamp()),
-        (3, ext_stamp()),
-        (3, ext_stamp()),
-        (3, ext_stamp()),
-    ]
-}
+use crate::memory::{RoomMemory, SkippyMem};
 
-pub fn large_ext_stamp() -> Vec<Vec<char>> {
-    vec![
-        vec![' ', ' ', ' ', 'r', ' ', ' ', ' '],
-        vec![' ', ' ', 'r', 'e', 'r', ' ', ' '],
-        vec![' ', 'r', 'e', 'e', 'e', 'r', ' '],
-        vec!['r', 'e', 'e', ' ', 'e', 'e', 'r'],
-        vec![' ', 'r', 'e', 'e', 'e', 'r', ' '],
-        vec![' ', ' ', 'r', 'e', 'r', ' ', ' '],
-        vec![' ', ' ', ' ', 'r', ' ', ' ', ' '],
-    ]
-}
+use super::{
+    base_plan::{self, char_to_structure, index_to_xy, stamp_list, CORE_STAMP, LAB_STAMP, STORE_STAMP},
+    structure_visuals::RoomVisualExt,
+};
 
-pub fn ff_stamp() -> Vec<Vec<char>> {
-    vec![
-        vec!['r', 'r', 'r', 'r', 'r', 'r', 'r'],
-        vec!['r', 'e', 'e', 'e', 'e', 'e', 'r'],
-        vec!['r', 'p', ' ', 'e', ' ', 'p', 'r'],
-        vec!['r', 'c', 'e', 'l', 'e', 'c', 'r'],
-        vec!['r', 'e', ' ', 'e', ' ', 'e', 'r'],
-        vec!['r', 'e', 'e', 'p', 'e', 'e', 'r'],
-        vec!['r', 'r', 'r', 'r', 'r', 'r', 'r'],
-    ]
+// Memory keeps the fills keyed by tile, the planner wants them flat.
+fn fill_to_memory(fill: Vec<i32>) -> HashMap<i32, i32> {
+    fill.into_iter().enumerate().map(|(i, value)| (i as i32, value)).collect()
 }
 
-pub fn store_stamp() -> Vec<Vec<char>> {
-    vec![
-        vec!['r', 'r', 'r', 'r', 'r'],
-        vec!['r', 'l', 'r', 'q', 'r'],
-        vec!['r', 'n', ' ', 'f', 'r'],
-        vec!['r', 's', 'z', 'o', 'r'],
-        vec!['r', 'r', 'r', 'r', 'r'],
-    ]
+fn fill_from_memory(fill: &HashMap<i32, i32>) -> Vec<i32> {
+    (0..2500).map(|i| fill.get(&i).copied().unwrap_or(255)).collect()
 }
 
-pub fn lab_stamp() -> Vec<Vec<char>> {
-    vec![
-        vec![' ', ' ', ' ', 'r', ' ', ' ', ' '],
-        vec![' ', ' ', 'r', 'x', 'r', ' ', ' '],
-        vec![' ', 'r', 'x', 'x', 'r', 'r', ' '],
-        vec!['r', 'r', 'x', 'x', 'x', 'x', 'r'],
-        vec![' ', 'r', 'x', 'x', 'x', 'r', ' '],
-        vec![' ', ' ', 'r', 'r', 'r', ' ', ' '],
-        vec![' ', ' ', ' ', 'r', ' ', ' ', ' '],
-    ]
+fn draw_fill(room: &Room, fill: &[i32]) {
+    for (i, value) in fill.iter().enumerate() {
+        if *value < 255 {
+            let red = (255).min(value * 5).to_string();
+            let non_red = (255 - (255).min(value * 5)).to_string();
+            room.visual().text(
+                (i % 50) as f32,
+                (i / 50) as f32,
+                value.to_string(),
+                Some(
+                    TextStyle::default()
+                        .custom_font("0.6 serif")
+                        .color(&format!("rgb({}, {}, {})", red, non_red, non_red)),
+                ),
+            )
+        }
+    }
 }
 
-pub fn ext_stamp() -> Vec<Vec<char>> {
-    vec![
-        vec![' ', ' ', 'r', ' ', ' '],
-        vec![' ', 'r', 'e', 'r', ' '],
-        vec!['r', 'e', 't', 'e', 'r'],
-        vec![' ', 'r', 'e', 'r', ' '],
-        vec![' ', ' ', 'r', ' ', ' '],
-    ]
+// The base links, on top of the controller and source links from plan_containers_and_links.
+// Storage link comes with the controller link at 5, the fast filler one once the sources are linked at 8.
+fn base_link_count(rcl: u8) -> usize {
+    match rcl {
+        5..=7 => 1,
+        8 => 2,
+        _ => 0,
+    }
 }
 
-pub fn setup_plan_room(room: &Room, memory: &mut SkippyMem) -> bool {
-    let mut plan_arr = Vec::new();
+// Everything the plan allows at this RCL, most important first for each structure type.
+// Ramparts are left to the caller, as they depend on more than the RCL.
+pub fn structures_for_rcl(plan: &SkippyMem, rcl: u8) -> Vec<(RoomXY, StructureType)> {
+    let mut by_type: HashMap<StructureType, Vec<RoomXY>> = HashMap::new();
 
-    let terrain = room.get_terrain().get_raw_buffer().to_vec();
-    for item in terrain.iter().take(2500) {
-        if item & 1 == 1 {
-            plan_arr.push('W');
-        } else {
-            plan_arr.push(' ');
+    for (index, c) in plan.layout.chars().enumerate() {
+        if let Some(structure_type) = char_to_structure(c) {
+            by_type.entry(structure_type).or_default().push(index_to_xy(index as i32));
         }
     }
 
-    let offsets: Vec<i32> = vec![-51, -50, -49, -1, 1, 49, 50, 51];
-    for i in 0..50 {
-        for j in [i, 2450 + i, i * 50, i * 50 + 49] {
-            if terrain[j] & 1 != 1 {
-                plan_arr[j] = 'E';
-                for offset in &offsets {
-                    if (j as i32 + offset) as usize > plan_arr.len() {
-                        continue;
-                    }
-
-                    if plan_arr[(j as i32 + offset) as usize] == ' ' {
-                        plan_arr[(j as i32 + offset) as usize] = 'E'
-                    }
-                }
-            }
+    let core = index_to_xy(plan.core);
+    let store = plan
+        .stamp_centers
+        .get(STORE_STAMP)
+        .filter(|index| **index >= 0)
+        .map_or(core, |index| index_to_xy(*index));
+    let source_labs = plan.source_labs.map(index_to_xy);
+    // The room's first spawn sits right under the spawn center, it has to be the one planned first.
+    let first_spawn = index_to_xy(plan.core + 50);
+
+    let mut structures = Vec::new();
+
+    for (structure_type, mut positions) in by_type {
+        match structure_type {
+            StructureType::Link => positions.sort_by_key(|xy| xy.get_range_to(store)),
+            StructureType::Spawn => positions.sort_by_key(|xy| xy.get_range_to(first_spawn)),
+            StructureType::Lab => positions.sort_by_key(|xy| (!source_labs.contains(xy), xy.get_range_to(source_labs[0]))),
+            _ => positions.sort_by_key(|xy| xy.get_range_to(core)),
         }
+
+        let allowed = match structure_type {
+            StructureType::Road if rcl >= 3 => positions.len(),
+            StructureType::Container if rcl >= 2 => positions.len(),
+            StructureType::Road | StructureType::Container => 0,
+            StructureType::Link => base_link_count(rcl),
+            _ => structure_type.controller_structures(rcl as u32) as usize,
+        };
+
+        structures.extend(positions.into_iter().take(allowed).map(|xy| (xy, structure_type)));
     }
 
-    memory.map = plan_arr;
+    structures
+}
+
+pub fn planned_ramparts(plan: &SkippyMem) -> Vec<RoomXY> {
+    plan.ramparts.iter().map(|index| index_to_xy(*index as i32)).collect()
+}
+
+// The plan to build from, once the planner is done with the room.
+pub fn finished_plan(memory: &RoomMemory) -> Option<&SkippyMem> {
+    memory
+        .skippy_planner
+        .as_ref()
+        .filter(|plan| plan.planned && !plan.layout.is_empty())
+}
+
+pub fn setup_plan_room(room: &Room, memory: &mut SkippyMem) -> bool {
+    let terrain = room.get_terrain().get_raw_buffer().to_vec();
+
+    memory.map = base_plan::blank_map(&terrain);
     true
 }
 
+pub fn new_plan() -> SkippyMem {
+    SkippyMem {
+        map: Vec::new(),
+        step: 0,
+        source_fills: HashMap::new(),
+        controller_fill: HashMap::new(),
+        orth_wall_fill: HashMap::new(),
+        core: 0,
+        stamp_index: None,
+        source_labs: [0; 2],
+        planned: false,
+        stamp_centers: Vec::new(),
+        layout: String::new(),
+        ramparts: Vec::new(),
+    }
+}
+
+// Only rooms that start out on it get planned, see plan_room. Rooms already built from the bunker
+// stay on it, switching them over would mean tearing down half the base. A useSkippyPlanner flag
+// in the room opts it in anyway.
 pub fn run_planner(room: &Room, memory: &mut RoomMemory) {
+    // Plans from before the layout was saved dont have anything to build from.
+    if memory.skippy_planner.as_ref().is_some_and(|plan| plan.planned && plan.layout.is_empty()) {
+        memory.skippy_planner = None;
+    }
+
+    let flag_in_room = |name: &str| game::flags().get(name.to_string()).is_some_and(|flag| flag.pos().room_name() == room.name());
+
+    if memory.skippy_planner.is_none() && flag_in_room("useSkippyPlanner") {
+        info!("  [PLANNER] Switching {} over to the base planner", room.name());
+        memory.skippy_planner = Some(new_plan());
+    }
+
     if memory.skippy_planner.is_none() {
-        memory.skippy_planner = Some(SkippyMem {
-            map: Vec::new(),
-            step: 0,
-            source_fills: HashMap::new(),
-            controller_fill: HashMap::new(),
-            orth_wall_fill: HashMap::new(),
-            core: 0,
-            stamp_index: None,
-            source_labs: [0; 2],
-            planned: false,
-        });
+        return;
     }
 
     if game::flags().get("resetPlanner".to_string()).is_some() {
-        memory.skippy_planner = None;
+        memory.skippy_planner = Some(new_plan());
         return;
     }
 
+    // The core goes around the spawn we already have, everything else fits around it.
+    let spawn_center = memory.spawn_center.y.u8() as i32 * 50 + memory.spawn_center.x.u8() as i32;
+    let has_storage = room.storage().is_some();
+
     let plan_mem = &mut memory.skippy_planner.as_mut().unwrap();
 
     if plan_mem.planned {
-        if game::flags().get("visOrth".to_string()).is_some() {
-            let fill = &plan_mem.orth_wall_fill;
-            for i in 0..2500 {
-                if fill[&i] < 255 {
-                    let red = (255).min(fill[&i] * 5).to_string();
-                    let non_red = (255 - (255).min(fill[&i] * 5)).to_string();
-                    room.visual().text(
-                        (i % 50) as f32,
-                        (i as f32 / 50.0).floor(),
-                        fill[&i].to_string(),
-                        Some(
-                            TextStyle::default()
-                                .custom_font("0.6 serif")
-                                .color(&format!("rgb({}, {}, {})", red, non_red, non_red)),
-                        ),
-                    )
-                }
-            }
-        } else {
-            for i in 0..2500 {
-                let mut vis = RoomVisualExt::new(room.name());
-                let x = i % 50;
-                let y = (i as f32 / 50.0).floor() as i32;
+        if game::flags().get("visOrth".to_string()).is_some() && !plan_mem.orth_wall_fill.is_empty() {
+            draw_fill(room, &fill_from_memory(&plan_mem.orth_wall_fill));
+        } else if game::flags().get("visPlan".to_string()).is_some() {
+            let mut vis = RoomVisualExt::new(room.name());
 
-                let stringified = plan_mem.map[i];
+            for (i, c) in plan_mem.layout.chars().enumerate() {
+                let x = i % 50;
+                let y = i / 50;
 
-                if stringified == 'W' {
-                    room.visual()
-                        .text(x as f32, y as f32, "W".to_string(), None);
+                if let Some(s_type) = char_to_structure(c) {
+                    vis.structure(x as f32, y as f32, s_type, 0.5);
                 }
+            }
 
-                let s_type = match stringified {
-                    'c' => StructureType::Container,
-                    'r' => StructureType::Road,
-                    'l' => StructureType::Link,
-                    'p' => StructureType::Spawn,
-                    'e' => StructureType::Extension,
-                    'x' => StructureType::Lab,
-                    't' => StructureType::Tower,
-                    'q' => StructureType::Terminal,
-                    'n' => StructureType::Nuker,
-                    'f' => StructureType::Factory,
-                    's' => StructureType::Storage,
-                    'z' => StructureType::PowerSpawn,
-                    'o' => StructureType::Observer,
-                    _ => StructureType::Controller,
-                };
-
-                vis.structure(x as f32, y as f32, s_type, 0.5);
+            for rampart in &plan_mem.ramparts {
+                vis.structure((*rampart % 50) as f32, (*rampart / 50) as f32, StructureType::Rampart, 0.5);
             }
         }
         return;
     }
 
+    // The core has to go around the spawn we already have, if it doesnt fit the room stays on the bunker.
+    if plan_mem.step == 4 && plan_mem.stamp_index.is_none() && !base_plan::stamp_fits(&plan_mem.map, CORE_STAMP, spawn_center) {
+        info!("  [PLANNER] Core stamp doesnt fit around the spawn in {}, staying on the bunker", room.name());
+        memory.skippy_planner = None;
+        return;
+    }
+
     let ret = match plan_mem.step {
         0 => setup_plan_room(room, plan_mem),
         1 => source_fills(room, plan_mem),
         2 => controller_fill(room, plan_mem),
         3 => orth_wall_fill(room, plan_mem),
-        4 => place_stamp(room, plan_mem),
-        5 => finialize(room, plan_mem),
+        4 => place_stamp(room, plan_mem, spawn_center),
+        5 => connect_stamps(room, plan_mem),
+        6 => plan_ramparts(room, plan_mem),
+        7 => finialize(room, plan_mem),
         _ => {
             plan_mem.step = 0;
             false
@@ -222,110 +210,28 @@ pub fn run_planner(room: &Room, memory: &mut RoomMemory) {
     if ret {
         plan_mem.step += 1;
     }
-}
-
-pub fn source_fills(room: &Room, memory: &mut SkippyMem) -> bool {
-    let sources = room.find(find::SOURCES, None);
 
-    for source in sources {
-        if let std::collections::hash_map::Entry::Vacant(e) = memory.source_fills.entry(source.id())
-        {
-            let mut fill: HashMap<i32, i32> = HashMap::new();
-
-            let mut terrain = room.get_terrain();
-            for i in 0..2500_i32 {
-                let x = i % 50;
-                let y = (i as f32 / 50.0).floor() as i32;
-        
-                let xy = utils::new_xy(x as u8, y as u8);
-        
-                match terrain.get_xy(xy) {
-                    Terrain::Wall => {
-                        fill.insert(i, 255);
-                    },
-                    _ => {
-                        fill.insert(i, 0);
-                    }
-                }
-            }
-
-            for i in 0..50 {
-                for j in [i, 2450 + i, i * 50, i * 50 + 49] {
-                    if fill[&j] == 0 {
-                        fill.insert(j, -1);
-                    }
-                }
-            }
+    if plan_mem.planned {
+        info!("  [PLANNER] Finished base plan for {}", room.name());
 
-            let mut steps: HashMap<i32, i32> = HashMap::new();
-            let source_index = source.pos().y().u8() as i32 * 50 + source.pos().x().u8() as i32;
-            let offset = [-51, -50, -49, -1, 1, 49, 50, 51];
-            for off in offset {
-                steps.insert(source_index + off, 1);
-            }
+        // Until a storage is down, the sitter and hauling should work off the planned one.
+        let store = plan_mem.stamp_centers.get(STORE_STAMP).copied().filter(|store| *store >= 0);
+        if let (false, Some(store)) = (has_storage, store) {
+            memory.storage_center = index_to_xy(store);
+        }
+    }
+}
 
-            for i in 0..100 {
-                let mut next_steps = HashMap::new();
-                for j in steps.keys() {
-                    if fill[j] == 0 {
-                        fill.insert(*j, i + 1);
-                        for off in offset {
-                            let k = j + off;
-                            next_steps.insert(k, 1);
-                        }
-                    } else if fill[j] == -1 {
-                        fill.insert(*j, i + 1);
-
-                        let x = j % 50;
-                        let y = (*j as f32 / 50.0).floor() as i32;
-
-                        if x == 0 {
-                            for off in [-49, 1, 51] {
-                                let k = j + off;
-                                next_steps.insert(k, 1);
-                            }
-                        } else if x == 49 {
-                            for off in [-51, -1, 49] {
-                                let k = j + off;
-                                next_steps.insert(k, 1);
-                            }
-                        } else if y == 0 {
-                            for off in [49, 50, 51] {
-                                let k = j + off;
-                                next_steps.insert(k, 1);
-                            }
-                        } else if y == 49 {
-                            for off in [-51, -50, -49] {
-                                let k = j + off;
-                                next_steps.insert(k, 1);
-                            }
-                        }
-                    }
-                }
+pub fn source_fills(room: &Room, memory: &mut SkippyMem) -> bool {
+    let terrain = room.get_terrain().get_raw_buffer().to_vec();
 
-                steps = next_steps
-            }
+    for source in room.find(find::SOURCES, None) {
+        if let std::collections::hash_map::Entry::Vacant(e) = memory.source_fills.entry(source.id()) {
+            let fill = base_plan::flood_fill(&terrain, source.pos().xy());
 
-            if true {
-                for i in 0..2500 {
-                    if fill[&i] < 255 {
-                        let red = (255).min(fill[&i] * 5).to_string();
-                        let non_red = (255 - (255).min(fill[&i] * 5)).to_string();
-                        room.visual().text(
-                            (i % 50) as f32,
-                            (i as f32 / 50.0).floor(),
-                            fill[&i].to_string(),
-                            Some(
-                                TextStyle::default()
-                                    .custom_font("0.6 serif")
-                                    .color(&format!("rgb({}, {}, {})", red, non_red, non_red)),
-                            ),
-                        )
-                    }
-                }
-            }
+            draw_fill(room, &fill);
 
-            e.insert(fill);
+            e.insert(fill_to_memory(fill));
             return false;
         }
     }
@@ -333,246 +239,110 @@ pub fn source_fills(room: &Room, memory: &mut SkippyMem) -> bool {
 }
 
 pub fn controller_fill(room: &Room, memory: &mut SkippyMem) -> bool {
-    let mut fill: HashMap<i32, i32> = HashMap::new();
-
-    let mut terrain = room.get_terrain();
-    for i in 0..2500_i32 {
-        let x = i % 50;
-        let y = (i as f32 / 50.0).floor() as i32;
-
-        let xy = utils::new_xy(x as u8, y as u8);
-
-        match terrain.get_xy(xy) {
-            Terrain::Wall => {
-                fill.insert(i, 255);
-            },
-            _ => {
-                fill.insert(i, 0);
-            }
-        }
-    }
-
-    for i in 0..50 {
-        for j in [i, 2450 + i, i * 50, i * 50 + 49] {
-            if fill[&j] == 0 {
-                fill.insert(j, -1);
-            }
-        }
-    }
-
-    let mut steps: HashMap<i32, i32> = HashMap::new();
-    let controller_index =
-        room.controller().unwrap().pos().y().u8() as i32 * 50 + room.controller().unwrap().pos().x().u8() as i32;
-    let offset = [-51, -50, -49, -1, 1, 49, 50, 51];
-
-    for off in offset {
-        steps.insert(controller_index + off, 1);
-    }
-    for i in 0..100 {
-        let mut next_steps: HashMap<i32, i32> = HashMap::new();
-        for j in steps.keys() {
-            if fill[j] == 0 {
-                fill.insert(*j, i + 1);
-                for off in offset {
-                    let k = j + off;
-                    next_steps.insert(k, 1);
-                }
-            } else if fill[j] == -1 {
-                fill.insert(*j, i + 1);
-
-                let x = j % 50;
-                let y = (*j as f32 / 50.0).floor() as i32;
-
-                if x == 0 {
-                    for off in [-49, 1, 51] {
-                        let k = j + off;
-                        next_steps.insert(k, 1);
-                    }
-                } else if x == 49 {
-                    for off in [-51, -1, 49] {
-                        let k = j + off;
-                        next_steps.insert(k, 1);
-                    }
-                } else if y == 0 {
-                    for off in [49, 50, 51] {
-                        let k = j + off;
-                        next_steps.insert(k, 1);
-                    }
-                } else if y == 49 {
-                    for off in [-51, -50, -49] {
-                        let k = j + off;
-                        next_steps.insert(k, 1);
-                    }
-                }
-            }
-        }
-
-        steps = next_steps;
-    }
+    let terrain = room.get_terrain().get_raw_buffer().to_vec();
+    let fill = base_plan::flood_fill(&terrain, room.controller().unwrap().pos().xy());
 
-    if true {
-        for i in 0..2500 {
-            if fill[&i] < 255 {
-                let red = (255).min(fill[&i] * 5).to_string();
-                let non_red = (255 - (255).min(fill[&i] * 5)).to_string();
-                room.visual().text(
-                    (i % 50) as f32,
-                    (i as f32 / 50.0).floor(),
-                    fill[&i].to_string(),
-                    Some(
-                        TextStyle::default()
-                            .custom_font("0.6 serif")
-                            .color(&format!("rgb({}, {}, {})", red, non_red, non_red)),
-                    ),
-                )
-            }
-        }
-    }
+    draw_fill(room, &fill);
 
-    memory.controller_fill = fill;
+    memory.controller_fill = fill_to_memory(fill);
     true
 }
 
 pub fn orth_wall_fill(room: &Room, memory: &mut SkippyMem) -> bool {
-    let mut fill: HashMap<i32, i32> = HashMap::new();
-    let mut icm = LocalCostMatrix::new();
-
-    for x in 0..50 {
-        for y in 0..50 {
-            if [' ', 'r'].contains(&memory.map[(y as usize) * 50 + (x as usize)]) {
-                icm.set_xy(new_xy(x, y), 255);
-            } else {
-                icm.set_xy(new_xy(x, y), 0);
-            }
-        }
-    }
-
-    let dt_res = utils::distance_transform(&room.name(), Some(icm), true, false);
-
-    for x in 0..50 {
-        for y in 0..50 {
-            let nxy = utils::new_xy(x, y);
+    let fill = base_plan::orth_wall_fill(&memory.map);
 
-            if 'r' == memory.map[(y as usize) * 50 + (x as usize)] {
-                fill.insert(y as i32 * 50 + x as i32, 1);
-
-                continue;
-            }
-
-            fill.insert(y as i32 * 50 + x as i32, dt_res.get(nxy) as i32);
-        }
-    }
-
-    if true {
-        for i in 0..2500 {
-            if fill[&i] < 255 {
-                let red = (255).min(fill[&i] * 5).to_string();
-                let non_red = (255 - (255).min(fill[&i] * 5)).to_string();
-                room.visual().text(
-                    (i % 50) as f32,
-                    (i as f32 / 50.0).floor(),
-                    fill[&i].to_string(),
-                    Some(
-                        TextStyle::default()
-                            .custom_font("0.6 serif")
-                            .color(&format!("rgb({}, {}, {})", red, non_red, non_red)),
-                    ),
-                )
-            }
-        }
-    }
-
-    memory.orth_wall_fill = fill;
+    draw_fill(room, &fill);
 
+    memory.orth_wall_fill = fill_to_memory(fill);
     true
 }
 
-pub fn place_stamp(room: &Room, memory: &mut SkippyMem) -> bool {
-    if memory.stamp_index.is_none() {
-        memory.stamp_index = Some(0);
-    }
-
-    let current_stamp_size = stamp_list()[memory.stamp_index.unwrap() as usize].0;
-    let current_stamp_layout = &stamp_list()[memory.stamp_index.unwrap() as usize].1;
-    let mut best_spot = 0;
-    let mut lowest_score = 9001;
-
-    for i in 0..2500 {
-        if memory.orth_wall_fill[&i] >= current_stamp_size.into() && memory.orth_wall_fill[&i] < 255
-        {
-            let mut score = memory.controller_fill[&i] * 3;
-            for s_id in memory.source_fills.keys() {
-                score += memory.source_fills[s_id][&i] * 2
-            }
-            if score < lowest_score {
-                lowest_score = score;
-                best_spot = i;
-            }
-        }
-    }
+pub fn place_stamp(room: &Room, memory: &mut SkippyMem, spawn_center: i32) -> bool {
+    let stamp_index = *memory.stamp_index.get_or_insert(0) as usize;
 
-    if best_spot > 0 {
-        if memory.stamp_index == Some(0) {
-            memory.core = best_spot;
-        }
-        if memory.stamp_index == Some(2) {
-            memory.source_labs = [best_spot - 50, best_spot + 1];
-        }
+    let orth_fill = fill_from_memory(&memory.orth_wall_fill);
 
-        let anchor_x = best_spot % 50 - (current_stamp_size as i32 - 1) as i32;
-        let anchor_y = (best_spot as f32 / 50.0).floor() as i32 - (current_stamp_size as i32 - 1) as i32;
+    let spot = if stamp_index == CORE_STAMP {
+        Some(spawn_center)
+    } else {
+        let controller_fill = fill_from_memory(&memory.controller_fill);
+        let source_fills = memory.source_fills.values().map(fill_from_memory).collect::<Vec<_>>();
 
-        for (i, _) in current_stamp_layout.iter().enumerate() {
-            for j in 0..current_stamp_layout[i].len() {
-                let x = anchor_x + j as i32;
-                let y = anchor_y + i as i32;
+        base_plan::find_stamp_spot(stamp_index, &orth_fill, &controller_fill, &source_fills, Some(memory.core))
+    };
 
-                if current_stamp_layout[i][j] != ' ' {
-                    memory.map[(y * 50 + x) as usize] = current_stamp_layout[i][j];
-                }
-            }
+    if let Some(spot) = spot {
+        if stamp_index == CORE_STAMP {
+            memory.core = spot;
         }
-    } else {
-        info!("Uhmm, yeah, not working... Best spot is not > 0. Kill me");
-    }
-
-    if true {
-        for i in 0..2500 {
-            if memory.orth_wall_fill[&i] < 255 {
-                let red = (255).min(memory.orth_wall_fill[&i] * 5).to_string();
-                let non_red = (255 - (255).min(memory.orth_wall_fill[&i] * 5)).to_string();
-                room.visual().text(
-                    (i % 50) as f32,
-                    (i as f32 / 50.0).floor(),
-                    memory.orth_wall_fill[&i].to_string(),
-                    Some(
-                        TextStyle::default()
-                            .custom_font("0.6 serif")
-                            .color(&format!("rgb({}, {}, {})", red, non_red, non_red)),
-                    ),
-                )
-            }
+        if stamp_index == LAB_STAMP {
+            memory.source_labs = [spot - 50, spot + 1];
         }
+
+        memory.stamp_centers.push(spot);
+        base_plan::stamp_onto_map(&mut memory.map, stamp_index, spot);
+
+        draw_fill(room, &orth_fill);
         room.visual().circle(
-            best_spot as f32 % 50.0,
-            (best_spot as f32 / 50.0).floor(),
+            (spot % 50) as f32,
+            (spot / 50) as f32,
             Some(CircleStyle::default().radius(0.5).fill("#ff0000")),
         )
+    } else {
+        info!("  [PLANNER] No room left for stamp {} in {}, skipping it", stamp_index, room.name());
+        memory.stamp_centers.push(-1);
     }
 
     memory.stamp_index = Some(memory.stamp_index.unwrap() + 1);
     if stamp_list().len() > memory.stamp_index.unwrap() as usize {
+        // The walls moved, so the orth fill has to be redone before the next stamp.
         memory.step -= 1;
         return false;
-    } else {
-        info!("len {}", stamp_list().len());
-        info!("Index {}", memory.stamp_index.unwrap());
     }
 
     true
 }
 
+pub fn connect_stamps(room: &Room, memory: &mut SkippyMem) -> bool {
+    for center in base_plan::connect_stamps(&mut memory.map, memory.core, &memory.stamp_centers) {
+        let target = index_to_xy(center);
+        info!("  [PLANNER] Couldnt road stamp at {}, {} back to the core in {}", target.x.u8(), target.y.u8(), room.name());
+    }
+
+    true
+}
+
+pub fn plan_ramparts(room: &Room, memory: &mut SkippyMem) -> bool {
+    let terrain = room.get_terrain().get_raw_buffer().to_vec();
+    let controller = room.controller().map(|controller| controller.pos().xy());
+    let sources = room.find(find::SOURCES, None).iter().map(|source| source.pos().xy()).collect::<Vec<_>>();
+
+    match base_plan::plan_ramparts(&memory.map, &terrain, controller, &sources) {
+        Some(ramparts) => {
+            info!("  [PLANNER] Planned {} perimeter ramparts for {}", ramparts.len(), room.name());
+
+            memory.ramparts = ramparts;
+        }
+        None => info!("  [PLANNER] No rampart perimeter possible for {}", room.name()),
+    }
+
+    true
+}
+
+// Lock the plan in, and drop all the scratch space so it stays small in memory.
 pub fn finialize(_room: &Room, memory: &mut SkippyMem) -> bool {
+    let (layout, key_ramparts) = base_plan::finish_layout(&memory.map);
+
+    memory.layout = layout;
+    memory.ramparts.extend(key_ramparts);
+    memory.ramparts.sort_unstable();
+    memory.ramparts.dedup();
+
+    memory.map.clear();
+    memory.source_fills.clear();
+    memory.controller_fill.clear();
+    memory.orth_wall_fill.clear();
+
     memory.planned = true;
 
     false
@@ -0,0 +1,87 @@
+use screeps::{
+    HasId, ResourceType, StructureProperties, POWER_SPAWN_ENERGY_CAPACITY, POWER_SPAWN_ENERGY_RATIO,
+    POWER_SPAWN_POWER_CAPACITY,
+};
+
+use crate::{
+    config::ROOM_ENERGY_STOCKPILE,
+    traits::intents_tracking::StructurePowerSpawnExtensionsTracking,
+    utils::{get_room_stock, scale_haul_priority},
+};
+
+use super::cache::{
+    hauling::{haul_from_stock, HaulingPriority, HaulingType},
+    CachedRoom,
+};
+
+// Burns surplus energy and power into GPL.
+// Only runs while we have more energy than we want stockpiled.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_power_spawn(cached_room: &mut CachedRoom) {
+    if cached_room.rcl < 8 || cached_room.structures.storage.is_none() {
+        return;
+    }
+
+    let power_spawn = if let Some(power_spawn) = &cached_room.structures.power_spawn {
+        power_spawn.clone()
+    } else {
+        return;
+    };
+
+    if get_room_stock(cached_room, ResourceType::Energy) < ROOM_ENERGY_STOCKPILE {
+        return;
+    }
+
+    let energy = power_spawn.store().get_used_capacity(Some(ResourceType::Energy));
+    let power = power_spawn.store().get_used_capacity(Some(ResourceType::Power));
+
+    if energy < POWER_SPAWN_ENERGY_CAPACITY / 2 {
+        let priority = scale_haul_priority(
+            POWER_SPAWN_ENERGY_CAPACITY,
+            energy,
+            HaulingPriority::Minerals,
+            false,
+        );
+
+        cached_room.hauling.create_order(
+            power_spawn.raw_id(),
+            Some(power_spawn.structure_type()),
+            Some(ResourceType::Energy),
+            Some(POWER_SPAWN_ENERGY_CAPACITY - energy),
+            priority,
+            HaulingType::Transfer,
+        );
+    }
+
+    if power < POWER_SPAWN_POWER_CAPACITY / 2 {
+        let ordered = haul_from_stock(
+            cached_room,
+            ResourceType::Power,
+            POWER_SPAWN_POWER_CAPACITY - power,
+            HaulingPriority::Minerals as u32 as f32,
+        );
+
+        if ordered > 0 {
+            let priority = scale_haul_priority(
+                POWER_SPAWN_POWER_CAPACITY,
+                power,
+                HaulingPriority::Minerals,
+                false,
+            );
+
+            cached_room.hauling.create_order(
+                power_spawn.raw_id(),
+                Some(power_spawn.structure_type()),
+                Some(ResourceType::Power),
+                Some(ordered),
+                priority,
+                HaulingType::Transfer,
+            );
+        }
+    }
+
+    if power >= 1 && energy >= POWER_SPAWN_ENERGY_RATIO && power_spawn.ITprocess_power().is_ok() {
+        cached_room.stats.energy.processed_power += 1;
+        cached_room.stats.energy.spending_power += POWER_SPAWN_ENERGY_RATIO;
+    }
+}
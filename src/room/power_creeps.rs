@@ -0,0 +1,336 @@
+use log::info;
+use screeps::{
+    find, game, EffectType, HasPosition, MaybeHasId, Position, PowerCreep, PowerCreepClass, PowerType, ResourceType,
+    RoomName, RoomObject, SharedCreepProperties,
+};
+
+use crate::{
+    memory::{PowerCreepMemory, ScreepsMemory},
+    movement::move_target::MoveTarget,
+    traits::intents_tracking::PowerCreepExtensionsTracking,
+    utils,
+};
+
+use super::cache::{CachedRoom, RoomCache};
+
+// Head back to the power spawn once we are under this.
+const RENEW_TTL: u32 = 1000;
+// Ops we keep on hand, anything more goes into storage.
+const OPS_CARRY_TARGET: u32 = 300;
+
+// What operators level up, in order. Each pass through the list is another level in each.
+const OPERATOR_POWERS: [PowerType; 6] = [
+    PowerType::GenerateOps,
+    PowerType::OperateSpawn,
+    PowerType::OperateExtension,
+    PowerType::RegenSource,
+    PowerType::OperateStorage,
+    PowerType::OperateFactory,
+];
+
+fn power_ops_cost(power: PowerType) -> u32 {
+    match power {
+        PowerType::OperateSpawn => 100,
+        PowerType::OperateExtension => 2,
+        PowerType::OperateStorage => 100,
+        PowerType::OperateFactory => 100,
+        _ => 0,
+    }
+}
+
+// Creating, levelling up and spawning operators.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn manage_power_creeps(memory: &mut ScreepsMemory, cache: &RoomCache) {
+    let account_creeps = game::power_creeps();
+
+    memory.power_creeps.retain(|name, _| account_creeps.get(name.to_string()).is_some());
+
+    if game::time() % 10 != 0 {
+        return;
+    }
+
+    // Every power creep, and every level on one, costs a GPL level.
+    let used_gpl = account_creeps.values().map(|power_creep| power_creep.level() + 1).sum::<u32>();
+    let free_gpl = game::gpl::level().saturating_sub(used_gpl);
+
+    let homeless_room = cache.my_rooms.iter().find(|room_name| {
+        cache.rooms.get(room_name).is_some_and(|room_cache| room_cache.structures.power_spawn.is_some())
+            && !memory.power_creeps.values().any(|power_creep| power_creep.home_room == **room_name)
+    });
+
+    if free_gpl > 0 {
+        if let Some(home_room) = homeless_room {
+            let name = format!("operator-{}", utils::get_unique_id());
+
+            if PowerCreep::create(&name.as_str().into(), PowerCreepClass::Operator).is_ok() {
+                info!("[POWER CREEPS] Created {} for {}", name, home_room);
+
+                memory.power_creeps.insert(name, PowerCreepMemory { home_room: *home_room });
+            }
+        } else {
+            upgrade_power_creeps();
+        }
+    }
+
+    for power_creep in account_creeps.values() {
+        // Already out somewhere.
+        if power_creep.shard().is_some() {
+            continue;
+        }
+
+        let home_room = if let Some(power_creep_memory) = memory.power_creeps.get(&power_creep.name()) {
+            power_creep_memory.home_room
+        } else {
+            continue;
+        };
+
+        if let Some(power_spawn) = cache.rooms.get(&home_room).and_then(|room_cache| room_cache.structures.power_spawn.as_ref()) {
+            if power_creep.spawn(power_spawn).is_ok() {
+                info!("[POWER CREEPS] Spawning {} in {}", power_creep.name(), home_room);
+            }
+        }
+    }
+}
+
+// Spread levels evenly, first power in the list that is behind gets it.
+fn upgrade_power_creeps() {
+    for power_creep in game::power_creeps().values() {
+        let powers = power_creep.powers();
+
+        for target_level in 1..=5 {
+            for power in OPERATOR_POWERS {
+                let level = powers.get(power).map_or(0, |info| info.level());
+
+                if level < target_level && power_creep.upgrade(power).is_ok() {
+                    info!("[POWER CREEPS] Upgraded {:?} on {} to {}", power, power_creep.name(), level + 1);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn run_power_creeps(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    for room_name in game::rooms().keys() {
+        let room = game::rooms().get(room_name).unwrap();
+
+        for power_creep in room.find(find::MY_POWER_CREEPS, None) {
+            let home_room = if let Some(power_creep_memory) = memory.power_creeps.get(&power_creep.name()) {
+                power_creep_memory.home_room
+            } else {
+                continue;
+            };
+
+            run_operator(&power_creep, &home_room, memory, cache);
+        }
+    }
+}
+
+fn run_operator(power_creep: &PowerCreep, home_room: &RoomName, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    let current_room = power_creep.room().unwrap().name();
+
+    // Everything in the room gets solved together, so creeps know to work around us.
+    if let (Some(room_cache), Some(id)) = (cache.rooms.get_mut(&current_room), power_creep.try_raw_id()) {
+        room_cache.traffic.power_creeps.insert(id, power_creep.clone());
+    }
+
+    let move_target = if let Some(home_cache) = cache.rooms.get(home_room) {
+        operator_action(power_creep, home_cache, current_room == *home_room)
+    } else {
+        None
+    };
+
+    if let Some((target, range)) = move_target {
+        move_power_creep(power_creep, target, range, memory, cache);
+    }
+}
+
+// Does whatever the operator should be doing this tick, returning where it wants to go if it isn't there yet.
+fn operator_action(power_creep: &PowerCreep, home_cache: &CachedRoom, in_home_room: bool) -> Option<(Position, u16)> {
+    // Renew first, losing the operator is a lot worse than missing a power.
+    if power_creep.ticks_to_live().unwrap_or(0) < RENEW_TTL {
+        if let Some(power_spawn) = &home_cache.structures.power_spawn {
+            if !power_creep.pos().is_near_to(power_spawn.pos()) {
+                return Some((power_spawn.pos(), 1));
+            }
+
+            let _ = power_creep.ITrenew(power_spawn);
+            return None;
+        }
+    }
+
+    if let Some(controller) = &home_cache.structures.controller {
+        if !controller.is_power_enabled() {
+            if !power_creep.pos().is_near_to(controller.pos()) {
+                return Some((controller.pos(), 1));
+            }
+
+            let _ = power_creep.ITenable_room(controller);
+            return None;
+        }
+    }
+
+    if !in_home_room {
+        return home_cache.structures.storage.as_ref().map(|storage| (storage.pos(), 1));
+    }
+
+    // Generating ops is free to do from anywhere, so do it whenever we can.
+    if power_ready(power_creep, PowerType::GenerateOps) {
+        let _ = power_creep.ITuse_power(PowerType::GenerateOps, None);
+    }
+
+    if manage_ops(power_creep, home_cache) {
+        return None;
+    }
+
+    if let Some((power, target)) = find_duty(power_creep, home_cache) {
+        let range = power.range().unwrap_or(3) as u32;
+
+        if power_creep.pos().get_range_to(target.pos()) > range {
+            return Some((target.pos(), range as u16));
+        }
+
+        let _ = power_creep.ITuse_power(power, Some(&target));
+        return None;
+    }
+
+    // Nothing to do, wait by storage where the ops are.
+    home_cache
+        .structures
+        .storage
+        .as_ref()
+        .filter(|storage| power_creep.pos().get_range_to(storage.pos()) > 2)
+        .map(|storage| (storage.pos(), 2))
+}
+
+// Keep a working amount of ops on us. Returns true if we spent the tick on it.
+fn manage_ops(power_creep: &PowerCreep, home_cache: &CachedRoom) -> bool {
+    let storage = if let Some(storage) = &home_cache.structures.storage {
+        storage
+    } else {
+        return false;
+    };
+
+    if !power_creep.pos().is_near_to(storage.pos()) {
+        return false;
+    }
+
+    let ops = power_creep.store().get_used_capacity(Some(ResourceType::Ops));
+
+    if ops > OPS_CARRY_TARGET * 2 {
+        let _ = power_creep.ITtransfer(storage, ResourceType::Ops, Some(ops - OPS_CARRY_TARGET));
+        return true;
+    }
+
+    if ops < OPS_CARRY_TARGET && storage.store().get_used_capacity(Some(ResourceType::Ops)) > 0 {
+        let amount = (OPS_CARRY_TARGET - ops)
+            .min(storage.store().get_used_capacity(Some(ResourceType::Ops)))
+            .min(power_creep.store().get_free_capacity(None).max(0) as u32);
+
+        if amount > 0 {
+            let _ = power_creep.ITwithdraw(storage, ResourceType::Ops, Some(amount));
+            return true;
+        }
+    }
+
+    false
+}
+
+fn power_ready(power_creep: &PowerCreep, power: PowerType) -> bool {
+    let ops = power_creep.store().get_used_capacity(Some(ResourceType::Ops));
+
+    power_creep
+        .powers()
+        .get(power)
+        .is_some_and(|info| info.cooldown() == 0 && ops >= power_ops_cost(power))
+}
+
+fn has_effect(object: &RoomObject, power: PowerType) -> bool {
+    object.effects().iter().any(|effect| matches!(effect.effect(), EffectType::PowerEffect(p) if p == power))
+}
+
+// What the room could use a hand with, most important first.
+fn find_duty(power_creep: &PowerCreep, home_cache: &CachedRoom) -> Option<(PowerType, RoomObject)> {
+    let structures = &home_cache.structures;
+
+    if power_ready(power_creep, PowerType::OperateSpawn) {
+        if let Some(spawn) = structures
+            .spawns
+            .values()
+            .find(|spawn| spawn.spawning().is_some() && !has_effect(spawn.as_ref(), PowerType::OperateSpawn))
+        {
+            return Some((PowerType::OperateSpawn, spawn.as_ref().clone()));
+        }
+    }
+
+    if power_ready(power_creep, PowerType::OperateExtension) {
+        let room = &home_cache.room;
+
+        if room.energy_available() < room.energy_capacity_available() / 2 {
+            if let Some(storage) = &structures.storage {
+                if storage.store().get_used_capacity(Some(ResourceType::Energy)) > room.energy_capacity_available() {
+                    return Some((PowerType::OperateExtension, storage.as_ref().clone()));
+                }
+            }
+        }
+    }
+
+    if power_ready(power_creep, PowerType::RegenSource) {
+        if let Some(source) = home_cache
+            .resources
+            .sources
+            .iter()
+            .find(|source| !has_effect(source.source.as_ref(), PowerType::RegenSource))
+        {
+            return Some((PowerType::RegenSource, source.source.as_ref().clone()));
+        }
+    }
+
+    if power_ready(power_creep, PowerType::OperateStorage) {
+        if let Some(storage) = &structures.storage {
+            if storage.store().get_free_capacity(None) < (storage.store().get_capacity(None) / 10) as i32
+                && !has_effect(storage.as_ref(), PowerType::OperateStorage)
+            {
+                return Some((PowerType::OperateStorage, storage.as_ref().clone()));
+            }
+        }
+    }
+
+    if power_ready(power_creep, PowerType::OperateFactory) {
+        if let Some(factory) = &structures.factory {
+            if !has_effect(factory.as_ref(), PowerType::OperateFactory) && factory.level().is_none() {
+                return Some((PowerType::OperateFactory, factory.as_ref().clone()));
+            }
+        }
+    }
+
+    None
+}
+
+// Hands the next step to the traffic solver, the same way creeps do.
+fn move_power_creep(power_creep: &PowerCreep, target: Position, range: u16, memory: &mut ScreepsMemory, cache: &mut RoomCache) {
+    let current_room = power_creep.room().unwrap().name();
+
+    let search = MoveTarget {
+        pos: target,
+        range: range.into(),
+    }
+    .caching_pathfind(power_creep.pos(), memory);
+
+    let next = search.path().into_iter().find(|step| *step != power_creep.pos());
+
+    let Some(next) = next else { return };
+
+    if let (Some(room_cache), Some(id)) = (cache.rooms.get_mut(&current_room), power_creep.try_raw_id()) {
+        if next.room_name() == current_room {
+            room_cache.traffic.intended_move.insert(id, next.xy());
+            return;
+        }
+    }
+
+    // Crossing a room edge, nothing to solve for there.
+    if let Some(direction) = power_creep.pos().get_direction_to(next) {
+        let _ = power_creep.ITmove_direction(direction);
+    }
+}
@@ -0,0 +1,86 @@
+use screeps::{game, Creep, HasPosition, Position, RoomName, Terrain};
+
+use crate::{
+    constants::SOURCE_KEEPER_USERNAME,
+    traits::{position::RoomXYExtensions, room::RoomType},
+    utils,
+};
+
+use super::cache::CachedRoom;
+
+// How early we clear out before a lair spawns its keeper.
+const LAIR_RETREAT_TICKS: u32 = 15;
+// Keepers guard everything within a few tiles of their lair.
+const KEEPER_DANGER_RANGE: u32 = 5;
+
+pub fn is_keeper_room(room_name: &RoomName) -> bool {
+    utils::room_type(room_name) == RoomType::SourceKeeper
+}
+
+pub fn source_keepers(room_cache: &CachedRoom) -> Vec<&Creep> {
+    room_cache
+        .creeps
+        .enemy_creeps
+        .iter()
+        .filter(|creep| creep.owner().username() == SOURCE_KEEPER_USERNAME)
+        .collect()
+}
+
+// Is a keeper at, or about to spawn near, this position.
+pub fn keeper_threat(room_cache: &CachedRoom, pos: Position) -> Option<Position> {
+    if let Some(keeper) = source_keepers(room_cache)
+        .into_iter()
+        .find(|keeper| keeper.pos().get_range_to(pos) <= KEEPER_DANGER_RANGE)
+    {
+        return Some(keeper.pos());
+    }
+
+    room_cache
+        .structures
+        .keeper_lairs
+        .values()
+        .find(|lair| {
+            lair.pos().get_range_to(pos) <= KEEPER_DANGER_RANGE
+                && lair.ticks_to_spawn().is_some_and(|ticks| ticks <= LAIR_RETREAT_TICKS)
+        })
+        .map(|lair| lair.pos())
+}
+
+pub fn in_keeper_range(pos: Position, threat: Position) -> bool {
+    pos.get_range_to(threat) <= KEEPER_DANGER_RANGE
+}
+
+// The closest walkable tile just outside of the keepers reach.
+pub fn retreat_position(creep_pos: Position, threat: Position) -> Option<Position> {
+    let terrain = game::map::get_room_terrain(threat.room_name())?;
+    let range = KEEPER_DANGER_RANGE as i32 + 1;
+
+    let mut best: Option<Position> = None;
+
+    for dx in -range..=range {
+        for dy in -range..=range {
+            if dx.abs() != range && dy.abs() != range {
+                continue;
+            }
+
+            let x = threat.x().u8() as i32 + dx;
+            let y = threat.y().u8() as i32 + dy;
+
+            if !(1..49).contains(&x) || !(1..49).contains(&y) {
+                continue;
+            }
+
+            if terrain.get(x as u8, y as u8) == Terrain::Wall {
+                continue;
+            }
+
+            let pos = utils::new_xy(x as u8, y as u8).as_position(&threat.room_name());
+
+            if best.map_or(true, |best| creep_pos.get_range_to(pos) < creep_pos.get_range_to(best)) {
+                best = Some(pos);
+            }
+        }
+    }
+
+    best
+}
@@ -7,15 +7,19 @@ use screeps::{
 use spawn_manager::{SpawnManager, SpawnRequest};
 
 use crate::{
+    combat::goals::source_keeper::keepers_handled,
+    config::{REBUILD_BUILDER_PRIORITY, REBUILD_MIN_WORK_PARTS},
     formation::duo::duo_utils,
     memory::{iter_roles, CreepMemory, DuoMemory, Role, ScreepsMemory},
-    traits::position::PositionExtensions,
+    traits::position::{PositionExtensions, RoomXYExtensions},
     utils::{self, get_body_cost, get_unique_id, role_to_name, under_storage_gate},
 };
 
-use super::cache::{CachedRoom, RoomCache};
+use super::{cache::{CachedRoom, RoomCache}, labs, nukes, source_keepers::is_keeper_room};
 
 pub mod creep_sizing;
+pub mod prespawn;
+pub mod renewal;
 pub mod spawn_manager;
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -228,6 +232,10 @@ pub fn _temp_duo_spawning(
                 ..Default::default()
             };
 
+            let boosts = cache.rooms.get(&room.name()).map_or(HashMap::new(), |room_cache| {
+                labs::plan_boosts(room_cache, &body, &[Part::Attack, Part::Move])
+            });
+
             let req = cache.spawning.create_room_spawn_request(
                 Role::InvaderDuoAttacker,
                 body,
@@ -237,7 +245,7 @@ pub fn _temp_duo_spawning(
                 Some(creep_memory),
                 None,
                 Some(creep_name.clone()),
-            );
+            ).with_boosts(boosts);
             memory
                 .formations
                 .duos
@@ -267,6 +275,10 @@ pub fn _temp_duo_spawning(
                 ..Default::default()
             };
 
+            let boosts = cache.rooms.get(&room.name()).map_or(HashMap::new(), |room_cache| {
+                labs::plan_boosts(room_cache, &body, &[Part::Heal, Part::Move])
+            });
+
             let req = cache.spawning.create_room_spawn_request(
                 Role::InvaderDuoHealer,
                 body,
@@ -276,7 +288,7 @@ pub fn _temp_duo_spawning(
                 Some(creep_memory),
                 None,
                 Some(creep_name.clone()),
-            );
+            ).with_boosts(boosts);
             memory
                 .formations
                 .duos
@@ -307,11 +319,11 @@ pub fn create_spawn_requests_for_room(
 
     let mut requests = vec![
         base_hauler(room, room_cache, &mut cache.spawning),
-        storage_sitter(room, room_cache, &mut cache.spawning),
+        storage_sitter(room, room_cache, memory, &mut cache.spawning),
         fast_filler(room, room_cache, &mut cache.spawning),
         flag_attacker(room, room_cache, &mut cache.spawning),
         repairer(room, room_cache, &mut cache.spawning),
-        upgrader(room, room_cache, &mut cache.spawning),
+        upgrader(room, room_cache, memory, &mut cache.spawning),
         scout(room, room_cache, &mut cache.spawning),
         mineral_miner(room, room_cache, &mut cache.spawning),
         hauler(room, room_cache, memory, &mut cache.spawning),
@@ -329,7 +341,11 @@ pub fn create_spawn_requests_for_room(
     requests.push(builder(room, cache));
     requests.append(&mut remote_harvester(room, cache, memory));
 
-    requests.into_iter().flatten().collect()
+    // Anything headed out of the room goes to whoever is closest and free.
+    let (global, local): (Vec<_>, Vec<_>) = requests.into_iter().flatten().partition(|request| request.is_global());
+    cache.spawning.global_spawn_queue.extend(global);
+
+    local
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -395,6 +411,8 @@ pub fn flag_attacker(
                     10.0
                 };
 
+                let boosts = labs::plan_boosts(cache, &body, &[Part::Attack, Part::Heal, Part::Move]);
+
                 return Some(spawn_manager.create_room_spawn_request(
                     Role::Bulldozer,
                     body,
@@ -404,7 +422,7 @@ pub fn flag_attacker(
                     None,
                     None,
                     None,
-                ));
+                ).with_boosts(boosts));
             } else {
                 let mut body = vec![Part::Move, Part::Move, Part::Heal];
                 let max_energy = room.energy_capacity_available();
@@ -633,6 +651,9 @@ pub fn repairer(
         desired_repair_parts = 3;
     }
 
+    // Ramparts have to be up before the nukes land, so the landing time sets the pace.
+    desired_repair_parts = desired_repair_parts.max(nukes::nuke_repair_parts(cache).min(50) as usize);
+
     if repairing_work_parts >= desired_repair_parts as u32 {
         return None;
     }
@@ -656,7 +677,11 @@ pub fn repairer(
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn builder(room: &Room, rcache: &RoomCache) -> Option<SpawnRequest> {
     let cache = rcache.rooms.get(&room.name()).unwrap();
-    if under_storage_gate(cache, 0.5) {
+
+    // Losing structures means we need builders now, whatever the storage says.
+    let rebuilding = !cache.room_heap_cache.rebuild_queue.is_empty();
+
+    if under_storage_gate(cache, 0.5) && !rebuilding {
         return None;
     }
 
@@ -705,13 +730,15 @@ pub fn builder(room: &Room, rcache: &RoomCache) -> Option<SpawnRequest> {
             .get_used_capacity(Some(ResourceType::Energy))
             < 10000
             && building_work_parts >= 1)
+        && !rebuilding
     {
         return None;
     }
 
     let construction_sites = cache.structures.construction_sites.len() + remote_csite_count;
 
-    let desired_work_parts = (construction_sites as f32 * 1.5).round().clamp(3.0, 20.0);
+    let min_work_parts = if rebuilding { REBUILD_MIN_WORK_PARTS } else { 3.0 };
+    let desired_work_parts = (construction_sites as f32 * 1.5).round().clamp(min_work_parts, 20.0);
 
     if building_work_parts as f32 >= desired_work_parts
         || (construction_sites == 0 && remote_csite_count == 0)
@@ -726,10 +753,12 @@ pub fn builder(room: &Room, rcache: &RoomCache) -> Option<SpawnRequest> {
         return None;
     }
 
+    let priority = if rebuilding { REBUILD_BUILDER_PRIORITY } else { 4.5 };
+
     Some(rcache.spawning.create_room_spawn_request(
         Role::Builder,
         body,
-        4.5,
+        priority,
         cost,
         room.name(),
         None,
@@ -742,10 +771,33 @@ pub fn builder(room: &Room, rcache: &RoomCache) -> Option<SpawnRequest> {
 pub fn upgrader(
     room: &Room,
     cache: &CachedRoom,
+    memory: &ScreepsMemory,
     spawn_manager: &mut SpawnManager,
 ) -> Option<SpawnRequest> {
+    let target_work_parts = match cache.rcl {
+        1 => 5,
+        2 => 15,
+        3 => 22,
+        4 => 30,
+        5 => 30,
+        6 => 45,
+        7 => 50,
+        8 => 5,
+        _ => 1,
+    };
+
+    let body = crate::room::spawning::creep_sizing::upgrader_body(room, cache, target_work_parts);
+    let cost = get_body_cost(&body);
+
+    if body.is_empty() {
+        return None;
+    }
+
+    // Upgraders that will be dead by the time a new one gets there dont count.
+    let upgraders = prespawn::creeps_not_due(cache, memory, Role::Upgrader, prespawn::spawn_time(&body));
+
     let harvester_count = cache.creeps.creeps_of_role(Role::Harvester);
-    let upgrader_count = cache.creeps.creeps_of_role(Role::Upgrader);
+    let upgrader_count = upgraders.len() as u32;
 
     let max_pos = cache
         .structures
@@ -768,23 +820,7 @@ pub fn upgrader(
         return None;
     }
 
-    let target_work_parts = match cache.rcl {
-        1 => 5,
-        2 => 15,
-        3 => 22,
-        4 => 30,
-        5 => 30,
-        6 => 45,
-        7 => 50,
-        8 => 5,
-        _ => 1,
-    };
-
-    let current_work_parts = cache
-        .creeps
-        .creeps_of_role
-        .get(&Role::Upgrader)
-        .unwrap_or(&Vec::new())
+    let current_work_parts = upgraders
         .iter()
         .map(|creep| {
             let creep = game::creeps().get(creep.as_str().to_owned()).unwrap();
@@ -798,13 +834,6 @@ pub fn upgrader(
         })
         .sum::<usize>() as f32;
 
-    let body = crate::room::spawning::creep_sizing::upgrader_body(room, cache, target_work_parts);
-    let cost = get_body_cost(&body);
-
-    if body.is_empty() {
-        return None;
-    }
-
     let controller = &cache.structures.controller.as_ref().unwrap();
 
     if under_storage_gate(cache, 1.0) && controller.ticks_to_downgrade() > Some(50000) {
@@ -834,6 +863,13 @@ pub fn upgrader(
         priority *= 5.0;
     }
 
+    // At RCL8 we are capped at 15 energy a tick, so boosting is the only way to get more out of it.
+    let boosts = if cache.rcl >= 8 {
+        labs::plan_boosts(cache, &body, &[Part::Work])
+    } else {
+        HashMap::new()
+    };
+
     Some(spawn_manager.create_room_spawn_request(
         Role::Upgrader,
         body,
@@ -843,7 +879,7 @@ pub fn upgrader(
         None,
         None,
         None,
-    ))
+    ).with_boosts(boosts))
 }
 
 // TODO: Math this shit! Make it better!
@@ -922,7 +958,8 @@ pub fn hauler(
     //    prio /= 2.0;
     //}
 
-    Some(spawn_manager.create_room_spawn_request(
+    // Haulers work the remotes too, so if this room is busy a neighbour can put one out for it.
+    Some(spawn_manager.create_global_spawn_request(
         Role::Hauler,
         body,
         prio,
@@ -1025,6 +1062,7 @@ pub fn base_hauler(
 pub fn storage_sitter(
     room: &Room,
     cache: &CachedRoom,
+    memory: &ScreepsMemory,
     spawn_manager: &mut SpawnManager,
 ) -> Option<SpawnRequest> {
     if cache.rcl < 5 {
@@ -1046,12 +1084,7 @@ pub fn storage_sitter(
             return None;
         }
 
-        let creep = game::creeps().get(current_ss.to_string()).unwrap();
-
-        let ttl = creep.ticks_to_live().unwrap_or(u32::MAX);
-        let spawn_time = body.len() * 3;
-
-        ttl < spawn_time as u32
+        prespawn::needs_replacement(current_ss, &memory.creeps, prespawn::spawn_time(&body))
     } else {
         true
     };
@@ -1319,6 +1352,8 @@ pub fn remote_harvester(
 
     let hauler_count = owning_cache.creeps.creeps_of_role(Role::Hauler);
 
+    let measure_pos = owning_cache.spawn_center.unwrap().as_position(&room.name());
+
     for remote_name in &owning_room_memory.remotes {
         let remote_memory = memory.remote_rooms.get(remote_name);
         if remote_memory.is_none() {
@@ -1330,12 +1365,17 @@ pub fn remote_harvester(
             continue;
         }
 
+        // Harvesters in a keeper room without a killer are just free food.
+        if is_keeper_room(remote_name) && !keepers_handled(memory, remote_name) {
+            continue;
+        }
+
         if let Some(remote_cache) = cache.rooms.get(remote_name) {
             for source in &remote_cache.resources.sources {
                 let parts_needed_on_source = source.parts_needed(remote_cache);
-                //let can_replace = source.can_replace_creep(measure_pos, &game::rooms().get(*remote_name).unwrap());
+                let can_replace = source.can_replace_creep(measure_pos, &remote_cache.room);
 
-                if parts_needed_on_source == 0 {
+                if parts_needed_on_source == 0 && !can_replace {
                     continue;
                 }
 
@@ -1351,9 +1391,41 @@ pub fn remote_harvester(
                     .unwrap();
 
                 // If we have enough parts on the source, just skip it.
-                if parts_needed_on_source == 0
-                    || current_creeps_on_source >= max_mining_positions as usize
-                {
+                if current_creeps_on_source >= max_mining_positions as usize {
+                    continue;
+                }
+
+                // The current harvester dies about when this one gets there, so its a full sized one.
+                if can_replace {
+                    let (_filled, body) = creep_sizing::miner_body(
+                        room,
+                        remote_cache,
+                        true,
+                        source.max_work_parts,
+                        true,
+                        owning_cache.rcl <= 3,
+                        source.container.is_some(),
+                    );
+                    let cost = get_body_cost(&body);
+
+                    let priority = (4.0 + source.max_work_parts as f64) * 2.0;
+
+                    requests.push(Some(cache.spawning.create_global_spawn_request(
+                        Role::RemoteHarvester,
+                        body,
+                        priority,
+                        cost,
+                        *remote_name,
+                        Some(CreepMemory {
+                            owning_room: room.name(),
+                            owning_remote: Some(*remote_name),
+                            task_id: Some(*source_index as u128),
+                            ..Default::default()
+                        }),
+                        None,
+                        None,
+                    )));
+
                     continue;
                 }
 
@@ -1385,12 +1457,12 @@ pub fn remote_harvester(
 
                     let priority = 4.0 * parts_needed_on_source as f64;
 
-                    requests.push(Some(cache.spawning.create_room_spawn_request(
+                    requests.push(Some(cache.spawning.create_global_spawn_request(
                         Role::RemoteHarvester,
                         body,
                         priority,
                         cost,
-                        room.name(),
+                        *remote_name,
                         Some(CreepMemory {
                             owning_room: room.name(),
                             owning_remote: Some(*remote_name),
@@ -1410,12 +1482,12 @@ pub fn remote_harvester(
 
                 priority += parts_needed_on_source as f64;
 
-                requests.push(Some(cache.spawning.create_room_spawn_request(
+                requests.push(Some(cache.spawning.create_global_spawn_request(
                     Role::RemoteHarvester,
                     body,
                     priority,
                     cost,
-                    room.name(),
+                    *remote_name,
                     Some(CreepMemory {
                         owning_room: room.name(),
                         owning_remote: Some(*remote_name),
@@ -1435,12 +1507,12 @@ pub fn remote_harvester(
                     let priority = 50.0;
                     let cost = get_body_cost(&body);
 
-                    requests.push(Some(cache.spawning.create_room_spawn_request(
+                    requests.push(Some(cache.spawning.create_global_spawn_request(
                         Role::RemoteHarvester,
                         body,
                         priority,
                         cost,
-                        room.name(),
+                        *remote_name,
                         Some(CreepMemory {
                             owning_room: room.name(),
                             owning_remote: Some(*remote_name),
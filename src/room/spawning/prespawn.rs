@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use screeps::{game, Creep, Part, SharedCreepProperties};
+
+use crate::{
+    constants::{CLAIM_LIFETIME, CREEP_LIFETIME, CREEP_SPAWN_TIME},
+    memory::{CreepMemory, Role, ScreepsMemory},
+    room::cache::CachedRoom,
+};
+
+// Call once the creep is at its work site. The first time, we know exactly how long the trip took.
+pub fn record_travel_time(creep: &Creep, memory: &mut ScreepsMemory) {
+    let Some(creep_memory) = memory.creeps.get_mut(&creep.name()) else {
+        return;
+    };
+
+    if creep_memory.travel_time.is_some() {
+        return;
+    }
+
+    let Some(ticks_to_live) = creep.ticks_to_live() else {
+        return;
+    };
+
+    let lifetime = if creep.body().iter().any(|part| part.part() == Part::Claim) {
+        CLAIM_LIFETIME
+    } else {
+        CREEP_LIFETIME
+    };
+
+    creep_memory.travel_time = Some(lifetime.saturating_sub(ticks_to_live));
+}
+
+pub fn spawn_time(body: &[Part]) -> u32 {
+    body.len() as u32 * CREEP_SPAWN_TIME
+}
+
+// If we spawned a replacement right now, would it get there after this creep dies?
+// Creeps that havent made it to work yet dont have a travel time, and dont need replacing.
+pub fn needs_replacement(creep_name: &str, creeps: &HashMap<String, CreepMemory>, spawn_time: u32) -> bool {
+    let Some(travel_time) = creeps.get(creep_name).and_then(|creep_memory| creep_memory.travel_time) else {
+        return false;
+    };
+
+    let Some(creep) = game::creeps().get(creep_name.to_string()) else {
+        return false;
+    };
+
+    creep.ticks_to_live().is_some_and(|ticks_to_live| ticks_to_live <= travel_time + spawn_time)
+}
+
+// Creeps of the role that will still be around by the time a new one gets to work.
+pub fn creeps_not_due(room_cache: &CachedRoom, memory: &ScreepsMemory, role: Role, spawn_time: u32) -> Vec<String> {
+    room_cache
+        .creeps
+        .creeps_of_role
+        .get(&role)
+        .map(|creeps| {
+            creeps
+                .iter()
+                .filter(|name| !needs_replacement(name, &memory.creeps, spawn_time))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
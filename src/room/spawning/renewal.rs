@@ -0,0 +1,225 @@
+use log::info;
+use screeps::{Creep, ErrorCode, HasId, HasPosition, Part, SharedCreepProperties, StructureSpawn};
+
+use crate::{
+    config::{RENEW_MAX_RANGE, RENEW_MIN_BODY_COST, RENEW_TTL_THRESHOLD},
+    constants::{CREEP_LIFETIME, CREEP_SPAWN_TIME},
+    memory::{CreepMemory, Role, ScreepsMemory},
+    movement::move_target::MoveOptions,
+    room::cache::{CachedRoom, RoomCache},
+    traits::{creep::CreepExtensions, intents_tracking::StructureSpawnExtensionsTracking},
+    utils::get_body_cost,
+};
+
+use super::prespawn;
+
+struct RenewCost {
+    body_cost: u32,
+    spawn_time: u32,
+    // What one renew costs, and how many ticks it gives back.
+    energy: u32,
+    ticks: u32,
+}
+
+fn renew_cost(creep: &Creep) -> RenewCost {
+    let body = creep.body().iter().map(|part| part.part()).collect::<Vec<_>>();
+    let body_cost = get_body_cost(&body);
+    let parts = body.len() as u32;
+
+    // The engines SPAWN_RENEW_RATIO is 1.2, done as 6 / 5 so the ceil doesnt pick up float error.
+    let renew_divisor = 5 * CREEP_SPAWN_TIME * parts;
+
+    RenewCost {
+        body_cost,
+        spawn_time: prespawn::spawn_time(&body),
+        energy: (body_cost * 6).div_ceil(renew_divisor),
+        ticks: CREEP_LIFETIME * 6 / renew_divisor,
+    }
+}
+
+// Per tick of actual work, is topping this creep up cheaper than spawning its replacement?
+// A fresh creep costs its body over whatever is left after spawning and walking out to work.
+// Renewing only pays for the ticks it gives back, minus the ticks spent sat on the spawn
+// (one renew per tick) and the walk back out, so big creeps with long commutes can lose.
+fn cheaper_to_renew(cost: &RenewCost, creep: &Creep, creep_memory: &CreepMemory) -> bool {
+    if cost.ticks == 0 {
+        return false;
+    }
+
+    let travel_time = creep_memory.travel_time.unwrap_or(0);
+    let ticks_to_live = creep.ticks_to_live().unwrap_or(CREEP_LIFETIME);
+
+    let renews = CREEP_LIFETIME.saturating_sub(ticks_to_live) / cost.ticks;
+    let renew_useful = (renews * cost.ticks).saturating_sub(renews + travel_time);
+    if renews == 0 || renew_useful == 0 {
+        return false;
+    }
+
+    let fresh_useful = CREEP_LIFETIME.saturating_sub(cost.spawn_time + travel_time);
+    if fresh_useful == 0 {
+        return true;
+    }
+
+    let renew_per_tick = (renews * cost.energy) as f32 / renew_useful as f32;
+    let fresh_per_tick = cost.body_cost as f32 / fresh_useful as f32;
+
+    renew_per_tick < fresh_per_tick
+}
+
+fn can_be_renewed(creep: &Creep, creep_memory: &CreepMemory) -> bool {
+    // Fast fillers renew themselves, and recyclers are on their way out anyway.
+    if matches!(creep_memory.role, Role::FastFiller | Role::Recycler) || creep_memory.is_recycling.is_some() {
+        return false;
+    }
+
+    // Claim creeps cant be renewed. Boosted creeps are left out too, even though they are the
+    // expensive ones: renewing strips boosts, and the minerals are worth far more than the body.
+    !creep.body().iter().any(|part| part.part() == Part::Claim || part.boost().is_some())
+}
+
+fn should_start_renewing(creep: &Creep, creep_memory: &CreepMemory, room_cache: &CachedRoom, spawn: &StructureSpawn) -> bool {
+    if !creep.ticks_to_live().is_some_and(|ticks_to_live| ticks_to_live <= RENEW_TTL_THRESHOLD) {
+        return false;
+    }
+
+    // Only idle creeps, we dont want to drop whatever we are carrying halfway.
+    if creep_memory.hauling_task.is_some() || creep.pos().get_range_to(spawn.pos()) > RENEW_MAX_RANGE {
+        return false;
+    }
+
+    // If the room is still filling up, it has better things to spend on.
+    if room_cache.room.energy_available() < room_cache.room.energy_capacity_available() {
+        return false;
+    }
+
+    if !can_be_renewed(creep, creep_memory) {
+        return false;
+    }
+
+    let cost = renew_cost(creep);
+    cost.body_cost >= RENEW_MIN_BODY_COST && cheaper_to_renew(&cost, creep, creep_memory)
+}
+
+fn should_keep_renewing(creep: &Creep, room_cache: &CachedRoom) -> bool {
+    let cost = renew_cost(creep);
+
+    creep.ticks_to_live().is_some_and(|ticks_to_live| ticks_to_live + cost.ticks <= CREEP_LIFETIME)
+        && room_cache.room.energy_available() >= cost.energy
+}
+
+// Walk eligible creeps over to a spawn and wait there. Returns true if the creep is busy renewing.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn travel_to_renew(creep: &Creep, memory: &mut ScreepsMemory, cache: &mut RoomCache) -> bool {
+    if creep.spawning() {
+        return false;
+    }
+
+    let Some(creep_memory) = memory.creeps.get(&creep.name()) else {
+        return false;
+    };
+
+    let owning_room = creep_memory.owning_room;
+    let renewing = creep_memory.renewing.unwrap_or(false);
+
+    // Creeps out in remotes are too far off to bother.
+    if creep.room().unwrap().name() != owning_room {
+        if renewing {
+            memory.creeps.get_mut(&creep.name()).unwrap().renewing = None;
+        }
+
+        return false;
+    }
+
+    let Some(room_cache) = cache.rooms.get_mut(&owning_room) else {
+        return false;
+    };
+
+    let Some(spawn) = room_cache.structures.spawns.values().min_by_key(|spawn| spawn.pos().get_range_to(creep.pos())).cloned() else {
+        return false;
+    };
+
+    if renewing {
+        if !should_keep_renewing(creep, room_cache) {
+            memory.creeps.get_mut(&creep.name()).unwrap().renewing = None;
+            return false;
+        }
+    } else {
+        if !should_start_renewing(creep, creep_memory, room_cache, &spawn) {
+            return false;
+        }
+
+        // One creep per spawn, any more and they just crowd the spawn.
+        let already_renewing = memory
+            .creeps
+            .values()
+            .filter(|other| other.owning_room == owning_room && other.renewing.is_some())
+            .count();
+
+        if already_renewing >= room_cache.structures.spawns.len() {
+            return false;
+        }
+
+        info!("  [RENEWAL] {} heading to {} to renew", creep.name(), owning_room);
+        memory.creeps.get_mut(&creep.name()).unwrap().renewing = Some(true);
+    }
+
+    creep.bsay("♻️", false);
+
+    if spawn.spawning().is_some() {
+        // Queue up out of the way, clear_out_spawn_area will shove anything next to a spawning spawn.
+        if creep.pos().get_range_to(spawn.pos()) > 3 {
+            creep.better_move_to(memory, room_cache, spawn.pos(), 3, MoveOptions::default());
+        } else {
+            creep.set_working_area(room_cache, spawn.pos(), 3);
+        }
+    } else if !creep.pos().is_near_to(spawn.pos()) {
+        creep.better_move_to(memory, room_cache, spawn.pos(), 1, MoveOptions::default());
+    } else {
+        creep.set_working_area(room_cache, spawn.pos(), 1);
+    }
+
+    true
+}
+
+// Spawns that didnt spawn anything this tick renew whoever is waiting next to them.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn renew_creeps(memory: &mut ScreepsMemory, cache: &RoomCache) {
+    let mut renewed = Vec::new();
+
+    for room_name in &cache.my_rooms {
+        let Some(room_cache) = cache.rooms.get(room_name) else {
+            continue;
+        };
+
+        for spawn in room_cache.structures.spawns.values() {
+            if spawn.spawning().is_some() || cache.spawning.used_spawns.contains(&spawn.id()) {
+                continue;
+            }
+
+            let waiting = room_cache
+                .creeps
+                .owned_creeps
+                .values()
+                .filter(|creep| {
+                    creep.pos().is_near_to(spawn.pos())
+                        && !renewed.contains(&creep.name())
+                        && memory.creeps.get(&creep.name()).is_some_and(|creep_memory| creep_memory.renewing.is_some())
+                })
+                .min_by_key(|creep| creep.ticks_to_live().unwrap_or(u32::MAX));
+
+            let Some(creep) = waiting else {
+                continue;
+            };
+
+            match spawn.ITrenew_creep(creep) {
+                Ok(()) => renewed.push(creep.name()),
+                Err(ErrorCode::Full) => {
+                    if let Some(creep_memory) = memory.creeps.get_mut(&creep.name()) {
+                        creep_memory.renewing = None;
+                    }
+                }
+                Err(_) => {}
+            }
+        }
+    }
+}
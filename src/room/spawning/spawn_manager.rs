@@ -3,10 +3,12 @@ use std::collections::HashMap;
 use log::info;
 use rand::{rngs::StdRng, SeedableRng};
 use rand::prelude::SliceRandom;
-use screeps::{game, ErrorCode, HasPosition, Part, Position, Room, RoomName, SharedCreepProperties, SpawnOptions};
+use screeps::{game, ErrorCode, HasId, HasPosition, ObjectId, Part, Position, ResourceType, Room, RoomName, SharedCreepProperties, SpawnOptions, StructureSpawn};
 
 use crate::movement::move_target::{MoveOptions, MoveTarget};
+use crate::config::{GLOBAL_SPAWN_MAX_DISTANCE, GLOBAL_SPAWN_WAIT_PENALTY};
 use crate::room::cache::RoomCache;
+use crate::room::labs;
 use crate::traits::creep::CreepExtensions;
 use crate::traits::intents_tracking::{CreepExtensionsTracking, StructureSpawnExtensionsTracking};
 use crate::traits::position::RoomXYExtensions;
@@ -22,16 +24,47 @@ pub struct SpawnRequest {
     priority: f64,
     cost: u32,
 
-    _destination_room: Option<RoomName>,
+    // Set for requests in the global queue, the room the creep is headed to.
+    destination_room: Option<RoomName>,
+    // Global requests that came without memory belong to whoever spawns them.
+    adopt_owning_room: bool,
+    // Only this room may pick up the global request, for creeps that need to arrive together.
+    spawn_room: Option<RoomName>,
 
     creep_memory: CreepMemory,
 
-    spawn_options: Option<SpawnOptions>
+    spawn_options: Option<SpawnOptions>,
+
+    // The compound we want each part type boosted with.
+    boosts: Option<HashMap<Part, ResourceType>>,
+}
+
+impl SpawnRequest {
+    pub fn is_global(&self) -> bool {
+        self.destination_room.is_some()
+    }
+
+    pub fn pinned_to(mut self, room_name: RoomName) -> Self {
+        self.spawn_room = Some(room_name);
+
+        self
+    }
+
+    pub fn with_boosts(mut self, boosts: HashMap<Part, ResourceType>) -> Self {
+        if !boosts.is_empty() {
+            self.boosts = Some(boosts);
+        }
+
+        self
+    }
 }
 
 pub struct SpawnManager {
     pub room_spawn_queue: HashMap<RoomName, Vec<SpawnRequest>>,
-    pub _global_spawn_queue: Vec<SpawnRequest>,
+    // Goal and combat creeps, spawned by whichever room is closest and free.
+    pub global_spawn_queue: Vec<SpawnRequest>,
+    // Spawns that started spawning this tick, so nothing else tries to use them.
+    pub used_spawns: Vec<ObjectId<StructureSpawn>>,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -39,7 +72,8 @@ impl SpawnManager {
     pub fn new() -> Self {
         Self {
             room_spawn_queue: HashMap::new(),
-            _global_spawn_queue: Vec::new(),
+            global_spawn_queue: Vec::new(),
+            used_spawns: Vec::new(),
         }
     }
 
@@ -86,13 +120,29 @@ impl SpawnManager {
             priority,
             cost,
 
-            _destination_room: None,
+            destination_room: None,
+            adopt_owning_room: false,
+            spawn_room: None,
             creep_memory,
 
-            spawn_options
+            spawn_options,
+
+            boosts: None,
         }
     }
 
+    // Without creep memory, the owning room is filled in by whichever room ends up spawning it.
+    // Otherwise the creep still belongs to whoever asked for it.
+    pub fn create_global_spawn_request(&self, role: Role, body: Vec<Part>, priority: f64, cost: u32, destination_room: RoomName, creep_memory: Option<CreepMemory>, spawn_options: Option<SpawnOptions>, name: Option<String>) -> SpawnRequest {
+        let adopt_owning_room = creep_memory.is_none();
+
+        let mut request = self.create_room_spawn_request(role, body, priority, cost, destination_room, creep_memory, spawn_options, name);
+        request.destination_room = Some(destination_room);
+        request.adopt_owning_room = adopt_owning_room;
+
+        request
+    }
+
     pub fn clear_out_spawn_area(&self, room_cache: &CachedRoom) {
         for spawn in room_cache.structures.spawns.values() {
             let mut creeps_in_range = Vec::new();
@@ -124,7 +174,7 @@ impl SpawnManager {
         }
     }
 
-    pub fn room_spawn_creep(&self, room: &Room, memory: &mut ScreepsMemory, room_cache: &CachedRoom, request: &SpawnRequest) -> bool {
+    pub fn room_spawn_creep(&mut self, room: &Room, memory: &mut ScreepsMemory, room_cache: &CachedRoom, request: &SpawnRequest) -> bool {
         let (available_spawn, _unavailable_spawns) = room_cache.structures.get_spawns();
         if available_spawn.is_empty() {
             return false;
@@ -140,14 +190,28 @@ impl SpawnManager {
         let name = if request.name.is_some() {
             request.name.as_ref().unwrap().clone()
         } else {
-            format!("{}-{}-{}", role_to_name(request.role), room.name(), get_unique_id())
+            format!("{}-{}-{}", role_to_name(request.role), request.creep_memory.owning_room, get_unique_id())
         };
 
         if let Some(spawn) = available_spawn.first() {
             let spawn_result = spawn.ITspawn_creep_with_options(&request.body, &name, options);
 
             if spawn_result.is_ok() {
-                memory.create_creep(&room.name(), &name, request.creep_memory.clone());
+                self.used_spawns.push(spawn.id());
+
+                let mut creep_memory = request.creep_memory.clone();
+
+                if let Some(plan) = &request.boosts {
+                    let room_memory = memory.rooms.get_mut(&room.name()).unwrap();
+                    creep_memory.boosts = labs::reserve_boosts(room_cache, room_memory, &request.body, plan);
+
+                    if creep_memory.boosts.is_none() {
+                        info!("  [SPAWNING] Room {} is short on boosts for {}, spawning it unboosted", room.name(), name);
+                    }
+                }
+
+                let owning_room = creep_memory.owning_room;
+                memory.create_creep(&owning_room, &name, creep_memory);
                 return true;
             } else {
                 info!("[SPAWNING] Room {} failed to spawn {:?} creep: {:#?}", room.name(), request.role, spawn_result);
@@ -390,6 +454,10 @@ pub fn calculate_hauler_needs(room: &Room, memory: &mut ScreepsMemory, cache: &m
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn run_spawning(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
     let starting_cpu = game::cpu::get_used();
+
+    // Rooms that can still take a global request, and the priority it has to beat.
+    let mut global_candidates = HashMap::new();
+
     for room in &cache.my_rooms.clone() {
         let pre_check = game::cpu::get_used();
 
@@ -446,8 +514,8 @@ pub fn run_spawning(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
                     Role::Hauler => &hauler(&room, room_cache, memory, &mut cache.spawning),
                     Role::FastFiller => &fast_filler(&room, room_cache, &mut cache.spawning),
                     Role::BaseHauler => &base_hauler(&room, room_cache, &mut cache.spawning),
-                    Role::StorageSitter => &storage_sitter(&room, room_cache, &mut cache.spawning),
-                    Role::Upgrader => &upgrader(&room, room_cache, &mut cache.spawning),
+                    Role::StorageSitter => &storage_sitter(&room, room_cache, memory, &mut cache.spawning),
+                    Role::Upgrader => &upgrader(&room, room_cache, memory, &mut cache.spawning),
                     Role::Repairer => &repairer(&room, room_cache, &mut cache.spawning),
                     Role::Builder => &builder(&room, cache),
                     Role::Scout => &scout(&room, room_cache, &mut cache.spawning),
@@ -506,6 +574,12 @@ pub fn run_spawning(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
                         spawned_creep_cost += request.cost;
                     }
                 }
+
+                if !spawned_this_tick {
+                    global_candidates.insert(room.name(), request.priority);
+                }
+            } else {
+                global_candidates.insert(room.name(), f64::MIN);
             }
         }
 
@@ -516,9 +590,80 @@ pub fn run_spawning(memory: &mut ScreepsMemory, cache: &mut RoomCache) {
         info!("  [SPAWNING] Room {} took {:.2} CPU to spawn creeps", room.name(), post_check - pre_check);
     }
 
+    run_global_spawning(memory, cache, &mut global_candidates);
+
     info!("[SPAWNING] Government wide spawning took {:.2} CPU", game::cpu::get_used() - starting_cpu);
 }
 
+// Hand the global queue out to rooms with idle spawns, closest to the destination first.
+// Rooms that are waiting on energy count as further away, so a full room a few rooms
+// out will take it instead of the request sitting around.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+fn run_global_spawning(memory: &mut ScreepsMemory, cache: &mut RoomCache, candidates: &mut HashMap<RoomName, f64>) {
+    let mut requests = std::mem::take(&mut cache.spawning.global_spawn_queue);
+    requests.sort_by(|a, b| b.priority.partial_cmp(&a.priority).unwrap());
+
+    for mut request in requests {
+        let Some(destination) = request.destination_room else {
+            continue;
+        };
+
+        let mut best: Option<(RoomName, i32, bool)> = None;
+
+        for (room_name, min_priority) in candidates.iter() {
+            if request.spawn_room.is_some_and(|spawn_room| spawn_room != *room_name) {
+                continue;
+            }
+
+            // Local requests that are waiting on energy dont get their energy stolen by less important creeps.
+            if request.priority <= *min_priority {
+                continue;
+            }
+
+            let Some(room_cache) = cache.rooms.get(room_name) else {
+                continue;
+            };
+
+            let distance = utils::calc_room_distance(room_name, &destination, true);
+            if distance > GLOBAL_SPAWN_MAX_DISTANCE as i32 || room_cache.room.energy_capacity_available() < request.cost {
+                continue;
+            }
+
+            let can_spawn = room_cache.room.energy_available() >= request.cost;
+            let score = if can_spawn { distance } else { distance + GLOBAL_SPAWN_WAIT_PENALTY as i32 };
+
+            if !best.is_some_and(|(_, best_score, _)| best_score <= score) {
+                best = Some((*room_name, score, can_spawn));
+            }
+        }
+
+        let Some((room_name, _, can_spawn)) = best else {
+            info!("  [SPAWNING] No room can spawn global {:?} request for {}", request.role, destination);
+            continue;
+        };
+
+        // Either way the room is spoken for, if its short on energy its saving up for this.
+        candidates.remove(&room_name);
+
+        if !can_spawn {
+            continue;
+        }
+
+        let room_cache = cache.rooms.get(&room_name).unwrap();
+        if request.adopt_owning_room {
+            request.creep_memory.owning_room = room_name;
+        }
+
+        info!("  [SPAWNING] Room {} picked up global {:?} request for {}", room_name, request.role, destination);
+
+        if cache.spawning.room_spawn_creep(&room_cache.room, memory, room_cache, &request) {
+            // The room already averaged in this tick, so just add our share on top.
+            let room_memory = memory.rooms.get_mut(&room_name).unwrap();
+            room_memory.avg_spawn_expense += request.cost as f64 / 1500.0;
+        }
+    }
+}
+
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 fn randomize_top_priorities(room: &Room, requests: Vec<SpawnRequest>) -> Vec<SpawnRequest> {
     let mut top_scorers = Vec::new();
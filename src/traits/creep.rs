@@ -146,7 +146,7 @@ impl CreepExtensions for screeps::Creep {
         let entry = cache
             .traffic
             .working_areas
-            .entry(self.try_id().unwrap())
+            .entry(self.try_raw_id().unwrap())
             .or_insert((pos, range));
 
         *entry = (pos, range)
@@ -434,7 +434,7 @@ impl CreepExtensions for screeps::Creep {
         //    .visual()
         //    .circle(to.0 as f32, to.1 as f32, None);
 
-        let Some(id) = self.try_id() else { return };
+        let Some(id) = self.try_raw_id() else { return };
 
         let target_position = dir_to_coords(target_delta, x, y);
         let target_position =
@@ -525,9 +525,9 @@ impl CreepExtensions for screeps::Creep {
         if room_cache
             .traffic
             .cached_ops
-            .contains_key(&self.try_id().unwrap())
+            .contains_key(&self.try_raw_id().unwrap())
         {
-            return room_cache.traffic.cached_ops[&self.try_id().unwrap()].clone();
+            return room_cache.traffic.cached_ops[&self.try_raw_id().unwrap()].clone();
         }
 
         let mut possible_moves = vec![self.pos().xy()];
@@ -535,7 +535,7 @@ impl CreepExtensions for screeps::Creep {
         room_cache
             .traffic
             .cached_ops
-            .insert(self.try_id().unwrap(), possible_moves.clone());
+            .insert(self.try_raw_id().unwrap(), possible_moves.clone());
 
         if self.tired() {
             return possible_moves;
@@ -544,9 +544,9 @@ impl CreepExtensions for screeps::Creep {
         if room_cache
             .traffic
             .intended_move
-            .contains_key(&self.try_id().unwrap())
+            .contains_key(&self.try_raw_id().unwrap())
         {
-            let mut new = vec![room_cache.traffic.intended_move[&self.try_id().unwrap()]];
+            let mut new = vec![room_cache.traffic.intended_move[&self.try_raw_id().unwrap()]];
             new.extend(possible_moves);
 
             return new;
@@ -558,7 +558,7 @@ impl CreepExtensions for screeps::Creep {
         let work = room_cache
             .traffic
             .working_areas
-            .get(&self.try_id().unwrap());
+            .get(&self.try_raw_id().unwrap());
 
         for dir in Direction::iter() {
             let pos = dir_to_coords(*dir, x, y);
@@ -3,7 +3,7 @@
 use std::ops::Mul;
 
 use js_sys::{Array, JsString};
-use screeps::{pathfinder::{self, MultiRoomCostResult, SearchResults}, Attackable, Color, ConstructionSite, Creep, Direction, Dismantleable, ErrorCode, Harvestable, Healable, Position, Repairable, Resource, ResourceType, Room, RoomName, RoomPosition, SharedCreepProperties, SpawnOptions, Structure, StructureController, StructureFactory, StructureLab, StructureLink, StructureNuker, StructureObject, StructureObserver, StructurePowerSpawn, StructureProperties, StructureRampart, StructureTower, StructureType, Transferable, Withdrawable};
+use screeps::{pathfinder::{self, MultiRoomCostResult, SearchResults}, Attackable, Color, ConstructionSite, Creep, Direction, Dismantleable, ErrorCode, Harvestable, Healable, Position, PowerCreep, PowerType, Repairable, Resource, ResourceType, Room, RoomName, RoomObject, RoomPosition, SharedCreepProperties, SpawnOptions, Structure, StructureController, StructureFactory, StructureLab, StructureLink, StructureNuker, StructureObject, StructureObserver, StructurePowerSpawn, StructureProperties, StructureRampart, StructureTower, StructureType, Transferable, Withdrawable};
 
 use crate::{movement::pathfinding::PathFinderSearchResult, profiling::timing::INTENTS_USED};
 
@@ -421,6 +421,47 @@ impl SpawningExtensionsTracking for screeps::Spawning {
     }
 }
 
+pub trait PowerCreepExtensionsTracking {
+    fn ITmove_direction(&self, dir: Direction) -> Result<(), ErrorCode>;
+    fn ITuse_power(&self, power: PowerType, target: Option<&RoomObject>) -> Result<(), ErrorCode>;
+    fn ITrenew(&self, target: &StructurePowerSpawn) -> Result<(), ErrorCode>;
+    fn ITenable_room(&self, target: &StructureController) -> Result<(), ErrorCode>;
+    fn ITtransfer(&self, target: &dyn Transferable, resource_type: ResourceType, amount: Option<u32>) -> Result<(), ErrorCode>;
+    fn ITwithdraw(&self, target: &dyn Withdrawable, resource_type: ResourceType, amount: Option<u32>) -> Result<(), ErrorCode>;
+}
+
+impl PowerCreepExtensionsTracking for PowerCreep {
+    fn ITmove_direction(&self, dir: Direction) -> Result<(), ErrorCode> {
+        track_intent();
+        self.move_direction(dir)
+    }
+
+    fn ITuse_power(&self, power: PowerType, target: Option<&RoomObject>) -> Result<(), ErrorCode> {
+        track_intent();
+        self.use_power(power, target)
+    }
+
+    fn ITrenew(&self, target: &StructurePowerSpawn) -> Result<(), ErrorCode> {
+        track_intent();
+        self.renew(target)
+    }
+
+    fn ITenable_room(&self, target: &StructureController) -> Result<(), ErrorCode> {
+        track_intent();
+        self.enable_room(target)
+    }
+
+    fn ITtransfer(&self, target: &dyn Transferable, resource_type: ResourceType, amount: Option<u32>) -> Result<(), ErrorCode> {
+        track_intent();
+        self.transfer(target, resource_type, amount)
+    }
+
+    fn ITwithdraw(&self, target: &dyn Withdrawable, resource_type: ResourceType, amount: Option<u32>) -> Result<(), ErrorCode> {
+        track_intent();
+        self.withdraw(target, resource_type, amount)
+    }
+}
+
 pub trait StructureTerminalExtensionsTracking {
     fn ITsend(&self, resource_type: ResourceType, amount: u32, destination: RoomName, description: Option<&str>) -> Result<(), ErrorCode>;
 }
@@ -163,7 +163,22 @@ pub fn under_storage_gate(room_cache: &CachedRoom, gate: f32) -> bool {
 }
 
 
-pub fn _calc_terminal_cost(amount: u32, source: &RoomName, dest: &RoomName) -> u32 {
+// How much of a resource the room has sitting in storage and terminal.
+pub fn get_room_stock(room_cache: &CachedRoom, resource: ResourceType) -> u32 {
+    let mut amount = 0;
+
+    if let Some(storage) = &room_cache.structures.storage {
+        amount += storage.store().get_used_capacity(Some(resource));
+    }
+
+    if let Some(terminal) = &room_cache.structures.terminal {
+        amount += terminal.store().get_used_capacity(Some(resource));
+    }
+
+    amount
+}
+
+pub fn calc_terminal_cost(amount: u32, source: &RoomName, dest: &RoomName) -> u32 {
     let dist = calc_room_distance(source, dest, true);
 
     (amount as f32 * (1.0 - E.powf(-dist as f32 / 30.0))).ceil() as u32
@@ -256,9 +271,17 @@ lazy_static! {
             Role::Reserver => "rs",
             Role::RemoteDefender => "rd",
             Role::InvaderCoreCleaner => "ic",
+            Role::KeeperKiller => "kk",
             Role::InvaderDuoAttacker => "ia",
             Role::InvaderDuoHealer => "ih",
 
+            Role::PowerBankAttacker => "pa",
+            Role::PowerBankHealer => "ph",
+            Role::PowerBankHauler => "pc",
+
+            Role::DepositHarvester => "dm",
+            Role::DepositHauler => "dc",
+
             Role::ExpansionBuilder => "eb",
 
             #[cfg(feature = "season1")]
@@ -434,38 +457,8 @@ pub fn new_xy(x: u8, y: u8) -> RoomXY {
 }
 
 
-pub fn distance_transform(
-    room_name: &RoomName,
-    input_cm: Option<LocalCostMatrix>,
-    include_exits: bool,
-    visual: bool,
-) -> LocalCostMatrix {
-    let mut cm = if let Some(input) = input_cm {
-        input
-    } else {
-        let terrain = game::map::get_room_terrain(*room_name).unwrap();
-
-        let mut cm = LocalCostMatrix::new();
-
-        for x in 0..=49 {
-            for y in 0..=49 {
-                let score = if terrain.get(x, y) == Terrain::Wall {
-                    0
-                } else {
-                    255
-                };
-
-                cm.set(new_xy(x, y), score);
-
-                if include_exits && is_exit(x, y) {
-                    cm.set(new_xy(x, y), 0);
-                }
-            }
-        }
-
-        cm
-    };
-
+// The transform itself, no game calls, so it works off any matrix. Zero is a wall, 255 is open.
+pub fn distance_transform_matrix(mut cm: LocalCostMatrix) -> LocalCostMatrix {
     let mut top: u8;
     let mut left: u8;
     let mut top_left: u8;
@@ -474,15 +467,15 @@ pub fn distance_transform(
 
     for x in 0..50 {
         for y in 0..50 {
-            top = cm.get(new_xy(x, y - 1));
-            left = cm.get(new_xy(x - 1, y));
-            top_left = cm.get(new_xy(x - 1, y - 1));
-            top_right = cm.get(new_xy(x + 1, y - 1));
-            bottom_left = cm.get(new_xy(x - 1, y + 1));
+            top = cm.get(new_xy(x, y.wrapping_sub(1)));
+            left = cm.get(new_xy(x.wrapping_sub(1), y));
+            top_left = cm.get(new_xy(x.wrapping_sub(1), y.wrapping_sub(1)));
+            top_right = cm.get(new_xy(x + 1, y.wrapping_sub(1)));
+            bottom_left = cm.get(new_xy(x.wrapping_sub(1), y + 1));
 
             let coord = new_xy(x, y);
 
-            let num1 = top.min(left).min(top_left).min(top_right).min(bottom_left) + 1;
+            let num1 = top.min(left).min(top_left).min(top_right).min(bottom_left).saturating_add(1);
             let num2 = cm.get(coord);
             cm.set(coord, num1.min(num2) as u8);
         }
@@ -497,20 +490,57 @@ pub fn distance_transform(
             bottom = cm.get(new_xy(x, y + 1));
             right = cm.get(new_xy(x + 1, y));
             bottom_right = cm.get(new_xy(x + 1, y + 1));
-            top_right = cm.get(new_xy(x + 1, y - 1));
-            bottom_left = cm.get(new_xy(x - 1, y + 1));
+            top_right = cm.get(new_xy(x + 1, y.wrapping_sub(1)));
+            bottom_left = cm.get(new_xy(x.wrapping_sub(1), y + 1));
 
             let num1 = bottom
                 .min(right)
                 .min(bottom_right)
                 .min(top_right)
                 .min(bottom_left)
-                + 1;
+                .saturating_add(1);
             let num2 = cm.get(new_xy(x, y));
             cm.set(new_xy(x, y), num1.min(num2) as u8);
         }
     }
 
+    cm
+}
+
+pub fn distance_transform(
+    room_name: &RoomName,
+    input_cm: Option<LocalCostMatrix>,
+    include_exits: bool,
+    visual: bool,
+) -> LocalCostMatrix {
+    let mut cm = if let Some(input) = input_cm {
+        input
+    } else {
+        let terrain = game::map::get_room_terrain(*room_name).unwrap();
+
+        let mut cm = LocalCostMatrix::new();
+
+        for x in 0..=49 {
+            for y in 0..=49 {
+                let score = if terrain.get(x, y) == Terrain::Wall {
+                    0
+                } else {
+                    255
+                };
+
+                cm.set(new_xy(x, y), score);
+
+                if include_exits && is_exit(x, y) {
+                    cm.set(new_xy(x, y), 0);
+                }
+            }
+        }
+
+        cm
+    };
+
+    cm = distance_transform_matrix(cm);
+
     if visual {
         if let Some(game_room) = game::rooms().get(*room_name) {
             let vis = game_room.visual();